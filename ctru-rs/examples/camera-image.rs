@@ -9,12 +9,6 @@ use ctru::services::gspgpu::FramebufferFormat;
 
 use std::time::Duration;
 
-const WIDTH: usize = 400;
-const HEIGHT: usize = 240;
-
-// The screen size is the width and height multiplied by 2 (RGB565 store pixels in 2 bytes).
-const BUF_SIZE: usize = WIDTH * HEIGHT * 2;
-
 const WAIT_TIMEOUT: Duration = Duration::from_millis(300);
 
 fn main() {
@@ -28,6 +22,9 @@ fn main() {
     top_screen.set_double_buffering(true);
     top_screen.set_framebuffer_format(FramebufferFormat::Rgb565);
 
+    // The camera view size (`ViewSize::TopLCD`) matches the size of the top screen.
+    let (width, height) = top_screen.dimensions();
+
     let _console = Console::new(gfx.bottom_screen.borrow_mut());
 
     println!("Initializing camera");
@@ -58,7 +55,8 @@ fn main() {
             .expect("Failed to disable trimming");
     }
 
-    let mut buf = vec![0u8; BUF_SIZE];
+    // The buffer size is the width and height multiplied by 2 (RGB565 store pixels in 2 bytes).
+    let mut buf = vec![0u8; usize::from(width) * usize::from(height) * 2];
 
     println!("\nPress R to take a new picture");
     println!("Press Start to exit");
@@ -79,12 +77,7 @@ fn main() {
 
             // Take a picture and write it to the buffer.
             camera
-                .take_picture(
-                    &mut buf,
-                    WIDTH.try_into().unwrap(),
-                    HEIGHT.try_into().unwrap(),
-                    WAIT_TIMEOUT,
-                )
+                .take_picture(&mut buf, width, height, WAIT_TIMEOUT)
                 .expect("Failed to take picture");
 
             // Play the normal shutter sound.
@@ -92,7 +85,12 @@ fn main() {
                 .expect("Failed to play shutter sound");
 
             // Rotate the image and correctly display it on the screen.
-            rotate_image_to_screen(&buf, top_screen.raw_framebuffer().ptr, WIDTH, HEIGHT);
+            rotate_image_to_screen(
+                &buf,
+                top_screen.raw_framebuffer().ptr,
+                width.into(),
+                height.into(),
+            );
 
             // We will only flush and swap the "camera" screen, since the other screen is handled by the `Console`.
             top_screen.flush_buffers();
@@ -38,7 +38,7 @@ fn main() {
         // Scan all the controller inputs.
         hid.scan_input();
 
-        if hid.keys_down().contains(KeyPad::START) {
+        if hid.should_quit(KeyPad::START) {
             break;
         }
 
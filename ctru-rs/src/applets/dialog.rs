@@ -0,0 +1,80 @@
+//! Simple message dialogs.
+//!
+//! The system doesn't provide a dedicated applet for message boxes, so the dialogs in this module are built on the applets which come closest:
+//!
+//! - [`alert()`] uses the Error Display applet in its text mode, which shows a word-wrapped message with a single button to close it.
+//! - [`confirm()`] uses the [Software Keyboard](crate::applets::swkbd) with two buttons, showing the message as the hint text of its (unused) input field.
+//!
+//! Like all applets, the dialogs block the calling thread until the user closes them, and require the [`Apt`](crate::services::apt::Apt)
+//! and [`Gfx`](crate::services::gfx::Gfx) services to be active.
+#![doc(alias = "popup")]
+
+use std::ffi::CString;
+
+use crate::applets::swkbd::{Button, Filters, Kind, SoftwareKeyboard, ValidInput};
+
+/// Show a message to the user, and wait until it's dismissed.
+///
+/// # Notes
+///
+/// The message is shown by the Error Display applet, which wraps the text to fit the screen.
+/// It can be up to 1900 characters long (longer messages are truncated), and it's cut at the first NUL character, if any.
+///
+/// # Example
+///
+/// ```no_run
+/// use ctru::applets::dialog;
+///
+/// dialog::alert("The download is complete.");
+/// ```
+#[doc(alias = "errorDisp", alias = "ERROR_TEXT_WORD_WRAP")]
+pub fn alert(message: &str) {
+    let message = message.split('\0').next().unwrap_or_default();
+    let message = CString::new(message).unwrap();
+
+    // Safety: `errorInit` initializes the whole configuration.
+    let mut config: ctru_sys::errorConf = unsafe { std::mem::zeroed() };
+
+    unsafe {
+        ctru_sys::errorInit(
+            &mut config,
+            ctru_sys::ERROR_TEXT_WORD_WRAP,
+            ctru_sys::CFG_LANGUAGE_EN,
+        );
+        ctru_sys::errorText(&mut config, message.as_ptr());
+        ctru_sys::errorDisp(&mut config);
+    }
+}
+
+/// Ask the user a yes/no question, returning `true` if they chose "Yes".
+///
+/// # Notes
+///
+/// The question is shown by the [Software Keyboard](crate::applets::swkbd) as the hint text of its input field, so it must be short:
+/// only the first 64 characters are shown. The left button answers "No" and the right one "Yes". Anything typed by the user is ignored.
+///
+/// If the applet is closed in any other way (e.g. by pressing the HOME button), the answer is "No".
+///
+/// # Example
+///
+/// ```no_run
+/// use ctru::applets::dialog;
+///
+/// if dialog::confirm("Delete this file?") {
+///     // std::fs::remove_file(path)?;
+/// }
+/// ```
+#[doc(alias = "swkbdInputText")]
+pub fn confirm(message: &str) -> bool {
+    let mut keyboard = SoftwareKeyboard::new(Kind::Normal, 2);
+
+    keyboard.set_hint_text(message);
+    keyboard.set_validation(ValidInput::Anything, Filters::empty());
+    keyboard.configure_button(Button::Left, "No", false);
+    keyboard.configure_button(Button::Right, "Yes", true);
+
+    // The input itself is discarded.
+    let mut buffer = [0u8; 16];
+
+    matches!(keyboard.write_exact(&mut buffer), Ok(Button::Right))
+}
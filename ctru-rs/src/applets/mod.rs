@@ -7,6 +7,207 @@
 //! Instead, the application builds a configuration storing the various parameters which is then used to "launch" the applet.
 //!
 //! Applets block execution of the thread that launches them as long as the user doesn't close the applet.
+//!
+//! Applets which don't have a typed wrapper yet can still be launched manually via [`launch()`].
+
+use std::ptr::NonNull;
+
+use libc::{free, memalign};
+
+use crate::error::ResultCode;
 
+pub mod dialog;
 pub mod mii_selector;
 pub mod swkbd;
+
+/// Size of a memory page. Memory blocks shared with applets must be aligned to (and sized in multiples of) this value.
+const PAGE_SIZE: usize = 0x1000;
+
+/// Identifier of a library applet.
+///
+/// The most common applets are available as associated constants, but any raw `NS_APPID` value can be used.
+#[doc(alias = "NS_APPID")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AppletId(pub ctru_sys::NS_APPID);
+
+impl AppletId {
+    /// Software Keyboard.
+    pub const SOFTWARE_KEYBOARD: Self = Self(ctru_sys::APPID_SOFTWARE_KEYBOARD);
+    /// Application Error Display.
+    pub const ERROR: Self = Self(ctru_sys::APPID_ERROR);
+    /// Mii Selector.
+    pub const MII_SELECTOR: Self = Self(ctru_sys::APPID_APPLETED);
+    /// Photo Selector.
+    pub const PHOTO_SELECTOR: Self = Self(ctru_sys::APPID_PNOTE_AP);
+    /// Sound Selector.
+    pub const SOUND_SELECTOR: Self = Self(ctru_sys::APPID_SNOTE_AP);
+    /// eShop Mint.
+    pub const MINT: Self = Self(ctru_sys::APPID_MINT);
+    /// Circle Pad Pro calibration applet.
+    pub const EXTRAPAD: Self = Self(ctru_sys::APPID_EXTRAPAD);
+}
+
+/// Page-aligned memory block to be shared with a library applet.
+///
+/// Some applets expect a block of memory (besides the parameter buffer) to work with.
+/// See [`launch()`] for more information on how it is passed to the applet.
+pub struct SharedMemory {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+impl SharedMemory {
+    /// Allocate a new zeroed memory block of at least `size` bytes.
+    ///
+    /// The size is rounded up to a multiple of the page size (`0x1000` bytes).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `size` is 0 or if the allocation fails.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "shared memory blocks cannot be empty");
+
+        let len = (size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+
+        let ptr = unsafe { memalign(PAGE_SIZE, len) }.cast::<u8>();
+        let ptr = NonNull::new(ptr).expect("failed to allocate the shared memory block");
+
+        unsafe { ptr.as_ptr().write_bytes(0, len) };
+
+        Self { ptr, len }
+    }
+
+    /// Returns the size of the memory block in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the memory block is empty. This is never the case for a valid [`SharedMemory`].
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a slice to the memory block's contents.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+
+    /// Returns a mutable slice to the memory block's contents.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        unsafe { free(self.ptr.as_ptr().cast()) };
+    }
+}
+
+/// Reply of a library applet launched via [`launch()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppletReply {
+    /// Parameter sent back by the applet when it closed, truncated (or padded with the original data) to the length of the parameter sent to it.
+    pub parameter: Vec<u8>,
+    /// Whether the application should keep running. This is `false` if the user asked to close the application
+    /// (e.g. from the HOME Menu) while the applet was running.
+    pub should_continue: bool,
+}
+
+/// Launch a library applet and wait for it to close, returning its reply.
+///
+/// This is an escape hatch for applets that don't have a typed wrapper (like [`SoftwareKeyboard`](swkbd::SoftwareKeyboard)) yet.
+/// Its use requires knowledge about the applet's expected configuration layout.
+///
+/// # Parameter passing
+///
+/// Communication with library applets happens via APT "parameters":
+///
+/// 1. The contents of `parameter` are copied and sent to the applet when it is started, together with a handle to
+///    `shared_memory` (if provided). The applet maps the shared memory block in its own address space.
+/// 2. The application is suspended while the applet runs.
+/// 3. When the applet closes, it sends a parameter back to the application, which is returned in [`AppletReply::parameter`].
+///    The reply is received in a copy of `parameter`, so it has the same length: longer replies are truncated, while the bytes
+///    after a shorter reply keep the original data. Applets usually reply with the same structure they are configured with.
+///
+/// The shared memory block is borrowed for the whole duration of the call, and its handle is closed before returning.
+/// The applet may have written into it, so its contents should be read again after this function returns.
+///
+/// # Notes
+///
+/// Applets require the [`Apt`](crate::services::apt::Apt) and [`Gfx`](crate::services::gfx::Gfx) services to be active.
+///
+/// # Errors
+///
+/// This function will return an error if the shared memory block could not be created.
+/// Errors reported by the applet itself are part of its reply, since they depend on its own protocol.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::applets::{self, AppletId, SharedMemory};
+///
+/// // Configuration layout expected by the applet.
+/// let parameter = [0u8; 0x400];
+/// let mut shared_memory = SharedMemory::new(0x1000);
+///
+/// let reply = applets::launch(AppletId::EXTRAPAD, &parameter, Some(&mut shared_memory))?;
+///
+/// if !reply.should_continue {
+///     // The user wants to close the application.
+///     return Ok(());
+/// }
+///
+/// // `reply.parameter` holds the applet's response.
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "aptLaunchLibraryApplet")]
+pub fn launch(
+    applet_id: AppletId,
+    parameter: &[u8],
+    shared_memory: Option<&mut SharedMemory>,
+) -> crate::Result<AppletReply> {
+    let mut handle: ctru_sys::Handle = 0;
+
+    if let Some(memory) = shared_memory {
+        let permissions = ctru_sys::MEMPERM_READ | ctru_sys::MEMPERM_WRITE;
+
+        ResultCode(unsafe {
+            ctru_sys::svcCreateMemoryBlock(
+                &mut handle,
+                memory.ptr.as_ptr() as u32,
+                memory.len() as u32,
+                permissions,
+                permissions,
+            )
+        })?;
+    }
+
+    // The reply is written over the sent parameter.
+    let mut buffer = parameter.to_vec();
+
+    let should_continue = unsafe {
+        let should_continue = ctru_sys::aptLaunchLibraryApplet(
+            applet_id.0,
+            buffer.as_mut_ptr().cast(),
+            buffer.len(),
+            handle,
+        );
+
+        if handle != 0 {
+            let _ = ctru_sys::svcCloseHandle(handle);
+        }
+
+        should_continue
+    };
+
+    Ok(AppletReply {
+        parameter: buffer,
+        should_continue,
+    })
+}
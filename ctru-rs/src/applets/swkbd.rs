@@ -7,11 +7,14 @@
 
 use bitflags::bitflags;
 use ctru_sys::{
-    self, swkbdInit, swkbdInputText, swkbdSetButton, swkbdSetFeatures, swkbdSetHintText, SwkbdState,
+    self, swkbdInit, swkbdInputText, swkbdSetButton, swkbdSetFeatures, swkbdSetHintText,
+    swkbdSetInitialText, SwkbdState,
 };
 use libc;
+use std::ffi::CString;
 use std::fmt::Display;
 use std::iter::once;
+use std::rc::Rc;
 use std::str;
 
 /// Configuration structure to setup the Software Keyboard applet.
@@ -19,6 +22,18 @@ use std::str;
 #[derive(Clone)]
 pub struct SoftwareKeyboard {
     state: Box<SwkbdState>,
+    // Data read by the filter callback of numeric keyboards. The state holds a pointer to it.
+    numeric_range: Option<Rc<NumericRange>>,
+    // Handed over to the state right before launching the applet, since libctru only stores a pointer to it.
+    initial_text: Option<CString>,
+}
+
+/// Bounds enforced by [`SoftwareKeyboard::numeric()`].
+struct NumericRange {
+    min: i64,
+    max: i64,
+    /// Message shown by the applet when the input is rejected.
+    message: CString,
 }
 
 /// The type of keyboard used by the [`SoftwareKeyboard`].
@@ -178,10 +193,108 @@ impl SoftwareKeyboard {
         unsafe {
             let mut state = Box::<SwkbdState>::default();
             swkbdInit(state.as_mut(), keyboard_type.into(), num_buttons, -1);
-            SoftwareKeyboard { state }
+            SoftwareKeyboard {
+                state,
+                numeric_range: None,
+                initial_text: None,
+            }
         }
     }
 
+    /// Initialize a new configuration for a number pad which only accepts integers between `min` and `max` (inclusive).
+    ///
+    /// The keyboard has a "Cancel" and an "OK" button. Use [`SoftwareKeyboard::get_number()`] to launch it.
+    ///
+    /// # Notes
+    ///
+    /// The input is checked by the applet itself (with a filter callback): if the user confirms a number out of range,
+    /// an error message is shown and the keyboard stays open. The minus sign is available on the number pad only if `min` is negative.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `min` is greater than `max`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # fn main() {
+    /// #
+    /// use ctru::applets::swkbd::SoftwareKeyboard;
+    ///
+    /// // Keyboard to enter a network port.
+    /// let mut keyboard = SoftwareKeyboard::numeric(1, 65535);
+    /// keyboard.set_hint_text("Port");
+    /// #
+    /// # }
+    /// ```
+    #[doc(alias = "swkbdSetFilterCallback")]
+    pub fn numeric(min: i64, max: i64) -> Self {
+        assert!(
+            min <= max,
+            "the minimum ({min}) is greater than the maximum ({max})"
+        );
+
+        let mut keyboard = Self::new(Kind::Numpad, 2);
+        keyboard.configure_button(Button::Left, "Cancel", false);
+        keyboard.configure_button(Button::Right, "OK", true);
+        keyboard.set_validation(ValidInput::NotEmptyNotBlank, Filters::CALLBACK);
+
+        // Room for the longest number in range, sign included.
+        let len = |n: i64| n.unsigned_abs().to_string().len() + usize::from(n < 0);
+        keyboard.set_max_text_len(len(min).max(len(max)) as u16);
+
+        if min < 0 {
+            keyboard.state.numpad_key_left = u16::from(b'-');
+        }
+
+        // Interior NUL bytes are impossible, since the message only contains numbers.
+        let message = CString::new(format!("Enter a number between {min} and {max}.")).unwrap();
+        let range = Rc::new(NumericRange { min, max, message });
+
+        unsafe {
+            ctru_sys::swkbdSetFilterCallback(
+                keyboard.state.as_mut(),
+                Some(numeric_filter),
+                Rc::as_ptr(&range) as *mut _,
+            );
+        }
+        keyboard.numeric_range = Some(range);
+
+        keyboard
+    }
+
+    /// Launches the applet and parses the input as an integer. Meant to be used with [`SoftwareKeyboard::numeric()`].
+    ///
+    /// Returns [`None`] in place of the number if the input isn't a valid integer, which can only happen
+    /// if the user closed the keyboard with a button that doesn't submit the input (e.g. "Cancel").
+    ///
+    /// TODO: UNSAFE OPERATION, LAUNCHING APPLETS REQUIRES GRAPHICS, WITHOUT AN ACTIVE GFX THIS WILL CAUSE A SEGMENTATION FAULT.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::applets::swkbd::{Button, SoftwareKeyboard};
+    /// let mut keyboard = SoftwareKeyboard::numeric(1, 65535);
+    ///
+    /// if let (Some(port), Button::Right) = keyboard.get_number()? {
+    ///     println!("Connecting to port {port}");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_number(&mut self) -> Result<(Option<i64>, Button), Error> {
+        // The longest 64 bit integer (with its sign) and the NUL terminator.
+        let (text, button) = self.get_string(21)?;
+
+        Ok((text.trim().parse().ok(), button))
+    }
+
     /// Launches the applet based on the given configuration and returns a string containing the text input.
     ///
     /// # Notes
@@ -255,6 +368,12 @@ impl SoftwareKeyboard {
     #[doc(alias = "swkbdInputText")]
     pub fn write_exact(&mut self, buf: &mut [u8]) -> Result<Button, Error> {
         unsafe {
+            let initial_text = self
+                .initial_text
+                .as_ref()
+                .map_or(std::ptr::null(), |text| text.as_ptr());
+            swkbdSetInitialText(self.state.as_mut(), initial_text);
+
             match swkbdInputText(self.state.as_mut(), buf.as_mut_ptr(), buf.len()) {
                 ctru_sys::SWKBD_BUTTON_NONE => Err(self.parse_swkbd_error()),
                 ctru_sys::SWKBD_BUTTON_LEFT => Ok(Button::Left),
@@ -360,6 +479,83 @@ impl SoftwareKeyboard {
         }
     }
 
+    /// Returns the hint text of this software keyboard, set with [`SoftwareKeyboard::set_hint_text()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # fn main() {
+    /// #
+    /// use ctru::applets::swkbd::SoftwareKeyboard;
+    /// let mut keyboard = SoftwareKeyboard::default();
+    ///
+    /// keyboard.set_hint_text("Server address");
+    /// assert_eq!(keyboard.hint_text(), "Server address");
+    /// #
+    /// # }
+    /// ```
+    pub fn hint_text(&self) -> String {
+        let text = &self.state.hint_text;
+        let len = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+
+        String::from_utf16_lossy(&text[..len])
+    }
+
+    /// Set the text already written in the input box when the keyboard is launched, so the user can edit an existing value.
+    ///
+    /// An empty string removes the initial text. If the text contains a NUL character, only the part before it is used.
+    ///
+    /// # Notes
+    ///
+    /// The initial text is subject to the [maximum text length](SoftwareKeyboard::set_max_text_len): if it's longer,
+    /// it's truncated to the maximum number of UTF-16 code units before being shown. It isn't checked by the [validation](SoftwareKeyboard::set_validation)
+    /// filters, which only apply to the text confirmed by the user.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # fn main() {
+    /// #
+    /// use ctru::applets::swkbd::SoftwareKeyboard;
+    /// let mut keyboard = SoftwareKeyboard::default();
+    ///
+    /// // Edit the previously entered address instead of typing it again.
+    /// keyboard.set_initial_text("192.168.1.10:8080");
+    /// #
+    /// # }
+    /// ```
+    #[doc(alias = "swkbdSetInitialText")]
+    pub fn set_initial_text(&mut self, text: &str) {
+        let text = text.split('\0').next().unwrap_or_default();
+
+        // The text can't contain any NUL character after the split.
+        self.initial_text = (!text.is_empty()).then(|| CString::new(text).unwrap());
+    }
+
+    /// Enable or disable predictive input (necessary for Kanji on JPN consoles), which suggests words while typing.
+    ///
+    /// This is the same as the [`Features::PREDICTIVE_INPUT`] feature, but it can also be turned off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # fn main() {
+    /// #
+    /// use ctru::applets::swkbd::SoftwareKeyboard;
+    /// let mut keyboard = SoftwareKeyboard::default();
+    ///
+    /// // Addresses aren't words.
+    /// keyboard.set_predictive_input(false);
+    /// #
+    /// # }
+    /// ```
+    pub fn set_predictive_input(&mut self, enabled: bool) {
+        self.state.predictive_input = enabled;
+    }
+
     /// Configure the look and behavior of a button for this keyboard.
     ///
     /// # Arguments
@@ -440,6 +636,30 @@ impl SoftwareKeyboard {
     }
 }
 
+/// Filter callback of [`SoftwareKeyboard::numeric()`], which rejects input outside of the configured range.
+unsafe extern "C" fn numeric_filter(
+    user: *mut libc::c_void,
+    message: *mut *const libc::c_char,
+    text: *const libc::c_char,
+    text_len: usize,
+) -> ctru_sys::SwkbdCallbackResult {
+    // Safety: `user` points to the `NumericRange` kept alive by the keyboard, and `text` is valid for `text_len` bytes.
+    let range = &*(user as *const NumericRange);
+    let text = std::slice::from_raw_parts(text.cast::<u8>(), text_len);
+
+    let in_range = str::from_utf8(text)
+        .ok()
+        .and_then(|text| text.trim().parse::<i64>().ok())
+        .is_some_and(|number| (range.min..=range.max).contains(&number));
+
+    if in_range {
+        ctru_sys::SWKBD_CALLBACK_OK
+    } else {
+        *message = range.message.as_ptr();
+        ctru_sys::SWKBD_CALLBACK_CONTINUE
+    }
+}
+
 /// Creates a new [`SoftwareKeyboard`] configuration set to using a [`Kind::Normal`] keyboard and 2 [`Button`]s.
 impl Default for SoftwareKeyboard {
     fn default() -> Self {
@@ -7,13 +7,96 @@
 
 use std::cell::RefMut;
 use std::default::Default;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
 
-use ctru_sys::{consoleClear, consoleInit, consoleSelect, consoleSetWindow, PrintConsole};
+use ctru_sys::{
+    consoleClear, consoleInit, consoleSelect, consoleSetFont, consoleSetWindow, PrintConsole,
+};
 
 use crate::services::gfx::Screen;
+use crate::services::gspgpu::FramebufferFormat;
 
 static mut EMPTY_CONSOLE: PrintConsole = unsafe { const_zero::const_zero!(PrintConsole) };
 
+/// Size (in bytes) of a single glyph in a [`ConsoleFont`].
+pub const GLYPH_SIZE: usize = 8;
+
+/// Bitmap font used by a [`Console`] to render text.
+///
+/// # Glyph layout
+///
+/// Every glyph is 8x8 pixels with 1 bit per pixel, which makes it [`GLYPH_SIZE`] bytes long.
+/// Each byte represents a row of the glyph (starting from the top), and the most significant bit of each byte is the leftmost pixel.
+/// A set bit is drawn with the foreground colour, while an unset bit is drawn with the background colour.
+///
+/// Glyphs are stored one after the other, starting with the one for the first character of the font.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::console::ConsoleFont;
+///
+/// // A font with a single, fully filled glyph for the space character.
+/// static GLYPHS: [u8; 8] = [0xFF; 8];
+///
+/// let font = ConsoleFont::new(&GLYPHS, b' ');
+/// assert_eq!(font.num_chars(), 1);
+/// ```
+#[doc(alias = "ConsoleFont")]
+#[derive(Clone, Copy, Debug)]
+pub struct ConsoleFont {
+    glyphs: &'static [u8],
+    first_char: u8,
+}
+
+impl ConsoleFont {
+    /// Create a new font from the bitmap data of its glyphs.
+    ///
+    /// `first_char` is the (ASCII) character represented by the first glyph in `glyphs`.
+    /// Characters without a glyph aren't drawn.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the length of `glyphs` isn't a multiple of [`GLYPH_SIZE`],
+    /// or if the glyphs go past the last ASCII character.
+    pub fn new(glyphs: &'static [u8], first_char: u8) -> Self {
+        assert_eq!(
+            glyphs.len() % GLYPH_SIZE,
+            0,
+            "the glyph data must be a multiple of {GLYPH_SIZE} bytes"
+        );
+        assert!(
+            usize::from(first_char) + glyphs.len() / GLYPH_SIZE <= 256,
+            "the font has more glyphs than available characters"
+        );
+
+        Self { glyphs, first_char }
+    }
+
+    /// Returns the character represented by the first glyph.
+    pub fn first_char(&self) -> u8 {
+        self.first_char
+    }
+
+    /// Returns the number of glyphs in the font.
+    pub fn num_chars(&self) -> usize {
+        self.glyphs.len() / GLYPH_SIZE
+    }
+}
+
+/// When text written to a [`Console`] via its [`Write`] implementation is shown on screen. See [`Console::set_write_flush_mode()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushMode {
+    /// Text is printed as soon as it's written.
+    #[default]
+    Immediate,
+    /// Text is kept in a buffer until [`Write::flush()`] is called.
+    Manual,
+}
+
 /// Virtual text console.
 ///
 /// [`Console`] lets the application redirect `stdout` and `stderr` to a simple text displayer on the 3DS screen.
@@ -32,10 +115,24 @@ static mut EMPTY_CONSOLE: PrintConsole = unsafe { const_zero::const_zero!(PrintC
 /// If you'd like to see live standard output while running the application but cannot or do not want to show the text on the 3DS itself,
 /// you can try using [`Soc::redirect_to_3dslink`](crate::services::soc::Soc::redirect_to_3dslink) while activating the `--server` flag for `3dslink` (also supported by `cargo-3ds`).
 /// More info in the [`cargo-3ds` docs](https://github.com/rust3ds/cargo-3ds#running-executables).
+///
+/// # Writing to a specific console
+///
+/// [`Console`] implements [`Write`], so text can be written directly to it (e.g. with [`write!`]) even if it isn't the currently selected console.
+/// This also makes it easy to mirror the output to other writers, like a log file or an in-memory buffer.
 #[doc(alias = "PrintConsole")]
 pub struct Console<'screen> {
     context: Box<PrintConsole>,
-    _screen: RefMut<'screen, dyn Screen>,
+    word_wrap: bool,
+    status_rows: u8,
+    flush_mode: FlushMode,
+    // Text written in `FlushMode::Manual` which hasn't been printed yet.
+    pending: Vec<u8>,
+    screen: RefMut<'screen, dyn Screen>,
+    // Off-screen buffer (pointer and length) the console draws to in place of the screen, if any.
+    // `libctru` writes to it whenever something is printed, so no reference to it is ever kept.
+    buffer: Option<(NonNull<u16>, usize)>,
+    _buffer: PhantomData<&'screen mut [u16]>,
 }
 
 impl<'screen> Console<'screen> {
@@ -79,8 +176,209 @@ impl<'screen> Console<'screen> {
 
         Console {
             context,
-            _screen: screen,
+            word_wrap: false,
+            status_rows: 0,
+            flush_mode: FlushMode::Immediate,
+            pending: Vec::new(),
+            screen,
+            buffer: None,
+            _buffer: PhantomData,
+        }
+    }
+
+    /// Initialize a console which only uses a window of the chosen screen, leaving the rest of the screen free for other drawing.
+    ///
+    /// The window's position and size are counted in characters (8x8 pixels with the default font): the console has 30 rows on both screens,
+    /// 40 columns on the [`BottomScreen`](crate::services::gfx::BottomScreen) and 50 (100 in wide mode) on the [`TopScreen`](crate::services::gfx::TopScreen).
+    ///
+    /// # Notes
+    ///
+    /// Like [`Console::new()`], this changes the [`FramebufferFormat`](crate::services::gspgpu::FramebufferFormat) of the screen to RGB565
+    /// and disables double buffering on it. If the screen was already using RGB565, the pixels outside the window are preserved,
+    /// otherwise the whole screen is cleared, since its old contents can't be shown in the new format.
+    ///
+    /// Anything drawn outside the window (see [`Console::screen_mut()`]) is never touched by the console, while anything drawn inside it
+    /// is overwritten as the text scrolls. With double buffering disabled, the console and any other drawing share the only framebuffer
+    /// of the screen: calling [`Flush::flush_buffers()`](crate::services::gfx::Flush::flush_buffers) is enough to show the drawing,
+    /// and [`Swap::swap_buffers()`](crate::services::gfx::Swap::swap_buffers) doesn't hide the console's text.
+    /// Re-enabling double buffering on the screen breaks this, since the console would only draw on one of the two framebuffers.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the window is empty or doesn't fit in the screen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::console::Console;
+    /// use ctru::services::gfx::{Flush, Gfx, Screen};
+    /// let gfx = Gfx::new()?;
+    ///
+    /// // A debug log in the bottom-right corner of the top screen.
+    /// let mut console = Console::with_window(gfx.top_screen.borrow_mut(), 30, 20, 20, 10);
+    ///
+    /// println!("Level loaded");
+    ///
+    /// // Draw the game on the rest of the screen.
+    /// let screen = console.screen_mut();
+    /// let framebuffer = screen.raw_framebuffer();
+    /// # let _ = framebuffer;
+    /// // ...
+    /// screen.flush_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleInit", alias = "consoleSetWindow")]
+    pub fn with_window(
+        mut screen: RefMut<'screen, dyn Screen>,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+    ) -> Self {
+        // `consoleInit` clears the whole screen, so keep a copy of what's there if it can be restored.
+        let previous = (screen.framebuffer_format() == FramebufferFormat::Rgb565).then(|| {
+            let framebuffer = screen.raw_framebuffer();
+            let len = framebuffer.width * framebuffer.height * 2;
+
+            unsafe { std::slice::from_raw_parts(framebuffer.ptr, len) }.to_vec()
+        });
+
+        let mut console = Console::new(screen);
+
+        let columns = console.context.consoleWidth;
+        let rows = console.context.consoleHeight;
+
+        assert!(
+            width > 0 && height > 0,
+            "the console window must be at least one character wide and tall"
+        );
+        assert!(
+            i32::from(x) + i32::from(width) <= columns && i32::from(y) + i32::from(height) <= rows,
+            "the console window doesn't fit in the screen ({columns}x{rows} characters)"
+        );
+
+        unsafe {
+            consoleSetWindow(
+                console.context.as_mut(),
+                x.into(),
+                y.into(),
+                width.into(),
+                height.into(),
+            )
+        };
+
+        if let Some(previous) = previous {
+            let framebuffer = console.screen.raw_framebuffer();
+            let len = (framebuffer.width * framebuffer.height * 2).min(previous.len());
+
+            unsafe { std::ptr::copy_nonoverlapping(previous.as_ptr(), framebuffer.ptr, len) };
+
+            // Only the window is cleared.
+            console.clear();
         }
+
+        console
+    }
+
+    /// Returns the screen used by the console, to draw on the parts of it outside the console's window.
+    ///
+    /// See [`Console::with_window()`] for the details about drawing next to a console.
+    pub fn screen_mut(&mut self) -> &mut dyn Screen {
+        &mut *self.screen
+    }
+
+    /// Initialize a console which draws its text on a caller-provided buffer instead of the chosen screen.
+    ///
+    /// The console has the same size it would have on the screen (see [`Console`]), and the buffer's contents can be copied with
+    /// [`Console::buffer()`] to be composited with other graphics (e.g. uploaded as a texture and blended over a 3D scene).
+    ///
+    /// # Notes
+    ///
+    /// The buffer uses the same layout of the screen's framebuffer in RGB565 format: each element is a pixel,
+    /// with red in the 5 most significant bits, green in the middle 6 and blue in the 5 least significant bits.
+    /// The pixels are stored rotated by 90 degrees, one column of 240 pixels after the other, with each column going
+    /// from the bottom of the screen to the top: the pixel at (x, y) (as seen by the user) is at index `x * 240 + (239 - y)`.
+    /// The background is black (0), so a translucent overlay can be obtained by treating black pixels as transparent.
+    ///
+    /// The buffer must be at least `240 * width` pixels long, where `width` is the width of the screen in pixels
+    /// (see [`Screen::dimensions()`]). It's cleared when the console is created.
+    ///
+    /// Like [`Console::new()`], this still selects the console for printing, and it still clears the screen and changes its
+    /// [`FramebufferFormat`] to RGB565, but the console never draws on the screen afterwards.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the buffer is too short.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::console::Console;
+    /// use ctru::services::gfx::Gfx;
+    /// let gfx = Gfx::new()?;
+    ///
+    /// let mut overlay = vec![0u16; 240 * 400];
+    /// let console = Console::with_buffer(gfx.top_screen.borrow_mut(), &mut overlay);
+    ///
+    /// println!("FPS: 60");
+    ///
+    /// // Blend the text over the scene.
+    /// let text = console.buffer().unwrap();
+    /// # assert_eq!(text.len(), 240 * 400);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleInit")]
+    pub fn with_buffer(screen: RefMut<'screen, dyn Screen>, buffer: &'screen mut [u16]) -> Self {
+        let (width, height) = screen.dimensions();
+        let len = usize::from(width) * usize::from(height);
+
+        assert!(
+            buffer.len() >= len,
+            "the console buffer is too short (length = {}) to hold the screen's pixels ({len})",
+            buffer.len()
+        );
+
+        buffer.fill(0);
+
+        let mut console = Console::new(screen);
+
+        let ptr = NonNull::from(&mut buffer[..len]).cast::<u16>();
+        console.buffer = Some((ptr, len));
+        console.context.frameBuffer = ptr.as_ptr();
+
+        console
+    }
+
+    /// Returns a copy of the pixels drawn by the console, if it was created with [`Console::with_buffer()`].
+    ///
+    /// See [`Console::with_buffer()`] for the layout of the pixels.
+    ///
+    /// # Notes
+    ///
+    /// The console draws on its buffer whenever something is printed to `stdout` or `stderr` (from any thread), so the buffer can't be borrowed:
+    /// its contents are copied while both `stdout` and `stderr` are locked, which makes sure no text is being drawn in the meantime.
+    /// Text printed without going through the standard library's handles (e.g. by C code calling `printf`) isn't synchronized.
+    pub fn buffer(&self) -> Option<Vec<u16>> {
+        let (ptr, len) = self.buffer?;
+
+        let _stdout = io::stdout().lock();
+        let _stderr = io::stderr().lock();
+
+        // Safety: the buffer is borrowed for the console's lifetime, and it's only written while printing to `stdout` or `stderr`,
+        // which can't happen until both are unlocked.
+        Some(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), len) }.to_vec())
     }
 
     /// Returns `true` if a valid [`Console`] to print on is currently selected.
@@ -188,10 +486,490 @@ impl<'screen> Console<'screen> {
     pub unsafe fn set_window(&mut self, x: i32, y: i32, width: i32, height: i32) {
         consoleSetWindow(self.context.as_mut(), x, y, width, height);
     }
+
+    /// Change the font used to render text on this console.
+    ///
+    /// # Notes
+    ///
+    /// Only text printed after this call uses the new font. Have a look at [`ConsoleFont`] to learn about the expected glyph layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::{Console, ConsoleFont};
+    ///
+    /// // Glyphs for the digits, from '0' to '9'.
+    /// static DIGITS: [u8; 80] = [0x7E; 80];
+    ///
+    /// let mut console = Console::new(gfx.top_screen.borrow_mut());
+    /// console.set_font(ConsoleFont::new(&DIGITS, b'0'));
+    ///
+    /// println!("0123456789");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleSetFont")]
+    pub fn set_font(&mut self, font: ConsoleFont) {
+        let mut raw_font = ctru_sys::ConsoleFont {
+            // libctru never writes to the glyph data.
+            gfx: font.glyphs.as_ptr().cast_mut(),
+            asciiOffset: font.first_char.into(),
+            numChars: font.num_chars() as u16,
+        };
+
+        // The font struct is copied into the console, but the glyph data must live as long as the console is in use,
+        // which is guaranteed by the `'static` lifetime.
+        unsafe { consoleSetFont(self.context.as_mut(), &mut raw_font) };
+    }
+
+    /// Enable or disable word wrapping for text written to this console via its [`Write`] implementation.
+    ///
+    /// Text printed with [`println!`], [`eprintln!`] and similar macros is never wrapped, since it goes through the standard output,
+    /// which `libctru` draws character by character. To wrap such text, write it to the [`Console`] instead (e.g. with [`writeln!`]).
+    ///
+    /// When enabled, a word that doesn't fit in the rest of the line is moved to the next line as a whole,
+    /// instead of being split at the edge of the console. Words longer than a full line are still split.
+    ///
+    /// # Notes
+    ///
+    /// A word split across separate writes may still be broken.
+    ///
+    /// Word wrapping is disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use std::io::Write;
+    /// use ctru::console::Console;
+    ///
+    /// let mut console = Console::new(gfx.top_screen.borrow_mut());
+    /// console.set_write_word_wrap(true);
+    ///
+    /// writeln!(
+    ///     console,
+    ///     "This long sentence is split at the spaces between words when it reaches the edge of the screen."
+    /// )?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_write_word_wrap(&mut self, enabled: bool) {
+        self.word_wrap = enabled;
+    }
+
+    /// Returns `true` if word wrapping is enabled for text written to this console via its [`Write`] implementation.
+    ///
+    /// See [`Console::set_write_word_wrap()`] for more information.
+    pub fn write_word_wrap(&self) -> bool {
+        self.word_wrap
+    }
+
+    /// Choose when text written to this console via its [`Write`] implementation is shown on screen.
+    ///
+    /// Text printed with [`println!`], [`eprintln!`] and similar macros isn't affected, since it goes through the standard output,
+    /// which is always flushed at the end of every line. To batch such text, write it to the [`Console`] instead (e.g. with [`writeln!`]).
+    ///
+    /// With [`FlushMode::Manual`], written text is collected in a buffer and printed all at once when [`Write::flush()`] is called,
+    /// instead of selecting the console and printing on every write. This makes writing many small pieces of text (e.g. a long log) much faster.
+    /// Switching back to [`FlushMode::Immediate`] prints any text still in the buffer.
+    ///
+    /// The default mode is [`FlushMode::Immediate`].
+    ///
+    /// # Notes
+    ///
+    /// In [`FlushMode::Manual`], nothing is shown until [`Write::flush()`] is called (or the console is dropped).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use std::io::Write;
+    /// use ctru::console::{Console, FlushMode};
+    ///
+    /// let mut console = Console::new(gfx.top_screen.borrow_mut());
+    /// console.set_write_flush_mode(FlushMode::Manual);
+    ///
+    /// for line in 0..1000 {
+    ///     writeln!(console, "Log line {line}")?;
+    /// }
+    ///
+    /// // Show the whole log at once.
+    /// console.flush()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_write_flush_mode(&mut self, mode: FlushMode) {
+        self.flush_mode = mode;
+
+        if mode == FlushMode::Immediate {
+            let _ = self.flush();
+        }
+    }
+
+    /// Returns the [`FlushMode`] used for text written to this console via its [`Write`] implementation.
+    ///
+    /// See [`Console::set_write_flush_mode()`] for more information.
+    pub fn write_flush_mode(&self) -> FlushMode {
+        self.flush_mode
+    }
+
+    /// Set the width (in characters) of the tab stops used when printing `'\t'`.
+    ///
+    /// The default tab width is 3 characters.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `width` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use ctru::console::Console;
+    ///
+    /// let mut console = Console::new(gfx.top_screen.borrow_mut());
+    /// console.set_tab_width(4);
+    ///
+    /// println!("Name\tValue");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_tab_width(&mut self, width: u8) {
+        assert!(width > 0, "the tab width must be at least 1");
+
+        self.context.tabSize = width.into();
+    }
+
+    /// Returns the width (in characters) of the tab stops.
+    pub fn tab_width(&self) -> u8 {
+        self.context.tabSize as u8
+    }
+
+    /// Reserve the top `rows` rows of the console for status lines, which don't scroll with the rest of the text.
+    ///
+    /// The remaining rows (below the status lines) become the scrolling region, where all printed text goes.
+    /// Status lines can only be changed with [`Console::write_status()`]. Passing 0 removes the status lines.
+    ///
+    /// # Notes
+    ///
+    /// Rows are counted in characters: the console has 30 rows on both screens (with the default font),
+    /// so locking `rows` rows leaves `30 - rows` rows for the scrolling region, starting at row `rows`.
+    /// Both regions span the whole width of the console.
+    ///
+    /// This function resizes the scrolling region with `consoleSetWindow`, which moves the cursor to its top-left corner.
+    /// Any text already on screen is left untouched, so it's usually a good idea to call [`Console::clear()`] beforehand.
+    /// Calling [`Console::set_window()`] afterwards overrides the scrolling region.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `rows` doesn't leave at least one row for the scrolling region.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use std::io::Write;
+    /// use ctru::console::Console;
+    ///
+    /// let mut console = Console::new(gfx.top_screen.borrow_mut());
+    /// console.set_status_rows(1);
+    ///
+    /// for file in 0..100 {
+    ///     console.write_status(0, &format!("Downloading: {file}/100"))?;
+    ///     writeln!(console, "file_{file}.bin")?;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "consoleSetWindow")]
+    pub fn set_status_rows(&mut self, rows: u8) {
+        let width = self.context.consoleWidth;
+        let height = self.context.consoleHeight;
+
+        assert!(
+            i32::from(rows) < height,
+            "the console has only {height} rows, at least one must be left for the scrolling region"
+        );
+
+        self.status_rows = rows;
+
+        unsafe {
+            consoleSetWindow(
+                self.context.as_mut(),
+                0,
+                rows.into(),
+                width,
+                height - i32::from(rows),
+            )
+        };
+    }
+
+    /// Returns the number of status rows reserved with [`Console::set_status_rows()`].
+    pub fn status_rows(&self) -> u8 {
+        self.status_rows
+    }
+
+    /// Replace the contents of a status line, leaving the cursor of the scrolling region where it was.
+    ///
+    /// `row` is counted from the top of the console. The text is cut at the first line break or at the end of the line,
+    /// and the rest of the line is cleared.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the text couldn't be written to the console.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `row` isn't one of the status rows reserved with [`Console::set_status_rows()`].
+    pub fn write_status(&mut self, row: u8, text: &str) -> io::Result<()> {
+        assert!(
+            row < self.status_rows,
+            "row {row} isn't a status row (there are {} status rows)",
+            self.status_rows
+        );
+
+        let width = self.context.consoleWidth;
+
+        let mut line: Vec<u8> = text
+            .bytes()
+            .take_while(|&b| b != b'\n' && b != b'\r')
+            .take(width as usize)
+            .collect();
+        line.resize(width as usize, b' ');
+
+        // Save the scrolling region and its cursor.
+        let window_x = self.context.windowX;
+        let window_y = self.context.windowY;
+        let window_width = self.context.windowWidth;
+        let window_height = self.context.windowHeight;
+        let cursor = (self.context.cursorX, self.context.cursorY);
+
+        // Temporarily move the window over the status lines. Filling the line exactly doesn't move the cursor to the next row.
+        unsafe { consoleSetWindow(self.context.as_mut(), 0, 0, width, self.status_rows.into()) };
+        self.context.cursorY = row.into();
+
+        let result = self.print(&line);
+
+        unsafe {
+            consoleSetWindow(
+                self.context.as_mut(),
+                window_x,
+                window_y,
+                window_width,
+                window_height,
+            )
+        };
+        (self.context.cursorX, self.context.cursorY) = cursor;
+
+        result
+    }
+
+    /// Print `buf` on this console, regardless of which console is currently selected.
+    fn print(&mut self, buf: &[u8]) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+
+        // Any text still buffered belongs to the currently selected console.
+        stdout.flush()?;
+
+        let previous_console = unsafe { consoleSelect(self.context.as_mut()) };
+
+        let result = stdout.write_all(buf).and_then(|_| stdout.flush());
+
+        unsafe { consoleSelect(previous_console) };
+
+        result
+    }
+
+    /// Print `buf` on this console, applying word wrapping if enabled.
+    fn print_wrapped(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.word_wrap {
+            let wrapped = self.wrap_words(buf);
+            self.print(&wrapped)
+        } else {
+            self.print(buf)
+        }
+    }
+
+    /// Insert line breaks in `buf` so that words aren't split at the edge of the console.
+    ///
+    /// The cursor is tracked the same way `libctru` moves it, starting from the console's current position.
+    fn wrap_words(&self, buf: &[u8]) -> Vec<u8> {
+        wrap_words(
+            buf,
+            self.context.windowWidth.max(1) as usize,
+            self.context.tabSize.max(1) as usize,
+            self.context.cursorX.max(0) as usize,
+        )
+    }
+}
+
+/// Insert line breaks in `buf` so that words aren't split at the edge of a console `width` characters wide,
+/// with the cursor starting at `column`.
+fn wrap_words(buf: &[u8], width: usize, tab_width: usize, mut column: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(buf.len());
+    let mut rest = buf;
+
+    while let Some(&byte) = rest.first() {
+        let len = match byte {
+            b'\n' | b'\r' => {
+                column = 0;
+                1
+            }
+            b'\t' => {
+                column += tab_width - column % tab_width;
+                1
+            }
+            // Backspace
+            0x08 => {
+                column = column.saturating_sub(1);
+                1
+            }
+            // ANSI escape sequences don't move the cursor horizontally (at least the ones we care about).
+            0x1B => match rest.get(1) {
+                Some(b'[') => rest[2..]
+                    .iter()
+                    .position(|b| (0x40..=0x7E).contains(b))
+                    .map_or(rest.len(), |end| end + 3),
+                _ => 1,
+            },
+            b' ' => {
+                if column >= width {
+                    column = 0;
+                }
+                column += 1;
+                1
+            }
+            _ => {
+                let len = rest
+                    .iter()
+                    // Only the bytes handled above end a word, so that at least one byte is consumed.
+                    .position(|b| matches!(b, b'\n' | b'\r' | b'\t' | b' ' | 0x08 | 0x1B))
+                    .unwrap_or(rest.len());
+
+                if column >= width {
+                    column = 0;
+                } else if column > 0 && column + len > width {
+                    output.push(b'\n');
+                    column = 0;
+                }
+
+                // Words longer than a line are split by libctru as usual.
+                for _ in 0..len {
+                    if column >= width {
+                        column = 0;
+                    }
+                    column += 1;
+                }
+
+                len
+            }
+        };
+
+        output.extend_from_slice(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    output
+}
+
+impl Write for Console<'_> {
+    /// Write a buffer to this console, regardless of which console is currently selected.
+    ///
+    /// # Notes
+    ///
+    /// The text is printed by temporarily selecting this console as the target for standard output.
+    /// Because of this, text written directly to a [`Console`] is sent to `3dslink` instead if
+    /// the output is being redirected via [`Soc::redirect_to_3dslink()`](crate::services::soc::Soc::redirect_to_3dslink).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// # use ctru::services::gfx::Gfx;
+    /// # let gfx = Gfx::new()?;
+    /// #
+    /// use std::io::Write;
+    /// use ctru::console::Console;
+    ///
+    /// let mut top_console = Console::new(gfx.top_screen.borrow_mut());
+    /// let bottom_console = Console::new(gfx.bottom_screen.borrow_mut());
+    ///
+    /// // The bottom console is selected, but we can still write on the top one.
+    /// writeln!(top_console, "I'm on the top screen!")?;
+    ///
+    /// // Mirror the same output into a buffer.
+    /// let mut log = Vec::new();
+    /// for writer in [&mut top_console as &mut dyn Write, &mut log] {
+    ///     writeln!(writer, "Hello, twice!")?;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.flush_mode {
+            FlushMode::Immediate => self.print_wrapped(buf)?,
+            FlushMode::Manual => self.pending.extend_from_slice(buf),
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // In `FlushMode::Immediate` text is flushed on every write, so there is nothing pending.
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        self.print_wrapped(&pending)
+    }
 }
 
 impl Drop for Console<'_> {
     fn drop(&mut self) {
+        // Show any text still buffered in `FlushMode::Manual`.
+        let _ = self.flush();
+
         unsafe {
             // Safety: We are about to deallocate the PrintConsole data pointed
             // to by libctru. Without this drop code libctru would have a
@@ -215,3 +993,37 @@ impl Drop for Console<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::wrap_words;
+
+    #[test]
+    fn wrap_words_moves_words_to_next_line() {
+        assert_eq!(wrap_words(b"hello world", 8, 4, 0), b"hello \nworld");
+        assert_eq!(wrap_words(b"hi there", 8, 4, 0), b"hi there");
+    }
+
+    #[test]
+    fn wrap_words_splits_long_words() {
+        assert_eq!(wrap_words(b"abcdefghij", 4, 4, 0), b"abcdefghij");
+        assert_eq!(wrap_words(b"ab abcdefghij", 4, 4, 0), b"ab \nabcdefghij");
+    }
+
+    #[test]
+    fn wrap_words_tracks_control_characters() {
+        assert_eq!(wrap_words(b"abc\nabcd", 4, 4, 0), b"abc\nabcd");
+        assert_eq!(wrap_words(b"\tab", 4, 4, 0), b"\tab");
+        assert_eq!(
+            wrap_words(b"ab\x1b[31mcd ef", 6, 4, 0),
+            b"ab\x1b[31mcd \nef"
+        );
+    }
+
+    #[test]
+    fn wrap_words_handles_other_whitespace() {
+        // Form feeds and vertical tabs are printed as part of the word.
+        assert_eq!(wrap_words(b"a\x0cb", 8, 4, 0), b"a\x0cb");
+        assert_eq!(wrap_words(b"a\x0bb cd", 5, 4, 0), b"a\x0bb \ncd");
+    }
+}
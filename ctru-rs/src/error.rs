@@ -6,6 +6,7 @@ use std::borrow::Cow;
 use std::error;
 use std::ffi::CStr;
 use std::fmt;
+use std::io;
 use std::ops::{ControlFlow, FromResidual, Try};
 
 use ctru_sys::result::{R_DESCRIPTION, R_LEVEL, R_MODULE, R_SUMMARY};
@@ -94,9 +95,49 @@ pub enum Error {
         /// Size of the requested data (in bytes).
         wanted: usize,
     },
+    /// An argument passed to the function is invalid (e.g. out of range), so the operation wasn't attempted.
+    InvalidArgument(String),
+    /// The operation isn't supported by the system or environment the application runs in.
+    Unsupported(String),
+    /// The operation failed for a reason which isn't described by a system result code.
+    Other(String),
+    /// An error with a description of the operation which caused it.
+    ///
+    /// See [`ResultExt::context()`] to learn how to attach a description to an error.
+    Context {
+        /// Description of the failed operation.
+        description: String,
+        /// The original error.
+        source: Box<Error>,
+    },
 }
 
 impl Error {
+    /// Returns `true` if the error is likely temporary, meaning the failed operation may succeed if retried shortly after.
+    ///
+    /// # Notes
+    ///
+    /// The following result codes are treated as transient:
+    /// - codes with the `temporary` [level](https://www.3dbrew.org/wiki/Error_codes#Level),
+    /// - `0xD88007FA` (the requested service doesn't exist), which is returned when a service is requested before
+    ///   the system module providing it has registered it, e.g. by an application launched right after boot.
+    ///
+    /// Any other code (e.g. a missing file or a full storage device) is treated as permanent, even if its level is `status`.
+    ///
+    /// Errors with added [context](ResultExt::context) are transient if the original error is. All other errors are never transient.
+    ///
+    /// See [`Service::init_with_retries()`](crate::services::Service::init_with_retries) to learn how to retry a service initialization.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            &Self::Os(result) => {
+                R_LEVEL(result) as u32 == ctru_sys::RL_TEMPORARY
+                    || TRANSIENT_RESULT_CODES.contains(&(result as u32))
+            }
+            Self::Context { source, .. } => source.is_transient(),
+            _ => false,
+        }
+    }
+
     /// Create an [`Error`] out of the last set value in `errno`.
     ///
     /// This can be used to get a human-readable error string from calls to `libc` functions.
@@ -146,6 +187,17 @@ impl fmt::Debug for Error {
                 .field("provided", provided)
                 .field("wanted", wanted)
                 .finish(),
+            Self::InvalidArgument(err) => f.debug_tuple("InvalidArgument").field(err).finish(),
+            Self::Unsupported(err) => f.debug_tuple("Unsupported").field(err).finish(),
+            Self::Other(err) => f.debug_tuple("Other").field(err).finish(),
+            Self::Context {
+                description,
+                source,
+            } => f
+                .debug_struct("Context")
+                .field("description", description)
+                .field("source", source)
+                .finish(),
         }
     }
 }
@@ -155,25 +207,196 @@ impl fmt::Display for Error {
         match self {
             // TODO: should we consider using ctru_sys::osStrError here as well?
             // It might do some of the work for us or provide additional details
-            &Self::Os(err) => write!(
-                f,
-                "libctru result code 0x{err:08X}: [{} {}] {}: {}",
-                result_code_level_str(err),
-                result_code_module_str(err),
-                result_code_summary_str(err),
-                result_code_description_str(err)
-            ),
+            &Self::Os(err) => match result_code_known_str(err) {
+                Some(description) => write!(f, "libctru result code 0x{err:08X}: {description}"),
+                None => write!(
+                    f,
+                    "libctru result code 0x{err:08X}: [{} {}] {}: {}",
+                    result_code_level_str(err),
+                    result_code_module_str(err),
+                    result_code_summary_str(err),
+                    result_code_description_str(err)
+                ),
+            },
             Self::Libc(err) => write!(f, "{err}"),
             Self::ServiceAlreadyActive => write!(f, "service already active"),
             Self::OutputAlreadyRedirected => {
                 write!(f, "output streams are already redirected to 3dslink")
             }
-            Self::BufferTooShort{provided, wanted} => write!(f, "the provided buffer's length is too short (length = {provided}) to hold the wanted data (size = {wanted})")
+            Self::BufferTooShort{provided, wanted} => write!(f, "the provided buffer's length is too short (length = {provided}) to hold the wanted data (size = {wanted})"),
+            Self::InvalidArgument(err) => write!(f, "invalid argument: {err}"),
+            Self::Unsupported(err) => write!(f, "unsupported operation: {err}"),
+            Self::Other(err) => write!(f, "{err}"),
+            Self::Context { description, source } => write!(f, "{description}: {source}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Extension trait to attach a description of the failed operation to an error.
+///
+/// This is useful to make logs and panic messages more readable, since a raw result code
+/// doesn't say which operation returned it.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::error::{Error, ResultExt};
+///
+/// let result: ctru::Result<()> = Err(Error::ServiceAlreadyActive);
+///
+/// let error = result.context("Failed to initialize the camera").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "Failed to initialize the camera: service already active"
+/// );
+/// ```
+pub trait ResultExt<T> {
+    /// Wrap the error (if any) into an [`Error::Context`] with the specified description.
+    fn context(self, description: &str) -> Result<T>;
+
+    /// Wrap the error (if any) into an [`Error::Context`] with a description computed by `f`.
+    ///
+    /// Unlike [`ResultExt::context()`], the description is only built if the operation failed.
+    fn with_context<F>(self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> String;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn context(self, description: &str) -> Result<T> {
+        self.with_context(|| description.to_owned())
+    }
+
+    fn with_context<F>(self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> String,
+    {
+        self.map_err(|err| Error::Context {
+            description: f(),
+            source: Box::new(err.into()),
+        })
+    }
+}
+
+impl From<Error> for io::Error {
+    /// Convert the error into a [`std::io::Error`], with a matching [`ErrorKind`](io::ErrorKind) whenever possible.
+    ///
+    /// The original [`Error`] is kept as the inner error, so it can be retrieved with [`io::Error::into_inner()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use std::io;
+    ///
+    /// // "The requested path does not exist" error code returned by the FS service.
+    /// let error = ctru::Error::Os(0xC8804470u32 as i32);
+    ///
+    /// let io_error = io::Error::from(error);
+    /// assert_eq!(io_error.kind(), io::ErrorKind::NotFound);
+    /// ```
+    fn from(err: Error) -> Self {
+        io::Error::new(err.io_error_kind(), err)
+    }
+}
+
+impl Error {
+    /// Returns the [`io::ErrorKind`] that best describes the error.
+    fn io_error_kind(&self) -> io::ErrorKind {
+        match self {
+            &Self::Os(err) => result_code_io_error_kind(err),
+            Self::BufferTooShort { .. } | Self::InvalidArgument(_) => io::ErrorKind::InvalidInput,
+            Self::Unsupported(_) => io::ErrorKind::Unsupported,
+            Self::Context { source, .. } => source.io_error_kind(),
+            _ => io::ErrorKind::Other,
         }
     }
 }
 
-impl error::Error for Error {}
+/// Result codes which are worth retrying even though their level isn't `temporary`. See [`Error::is_transient()`].
+const TRANSIENT_RESULT_CODES: &[u32] = &[
+    // The port isn't registered (yet) to the service manager.
+    0xD88007FA,
+];
+
+/// Commonly encountered result codes, paired with a human-readable explanation.
+///
+/// These are matched against the whole result code, since the meaning of a description
+/// often depends on the module which returned it.
+const KNOWN_RESULT_CODES: &[(u32, &str)] = &[
+    (0x082044BE, "the file or directory already exists (FS)"),
+    (0x086044D2, "not enough free space on the storage device (FS)"),
+    (0x09401BFE, "the operation timed out"),
+    (0xC8804470, "the requested path does not exist (FS)"),
+    (0xC8804478, "the requested file or archive does not exist (FS)"),
+    (0xD88007FA, "the requested port or service does not exist (kernel)"),
+    (
+        0xD880A7FA,
+        "the DSP firmware could not be found, make sure it is dumped to \"sdmc:/3ds/dspfirm.cdc\" (DSP)",
+    ),
+    (0xD8A083FA, "the requested title could not be found (AM)"),
+    (0xD8E007F7, "invalid handle (kernel)"),
+    (0xE0E046BE, "invalid path (FS)"),
+    (0xE0E046BF, "the path is too long (FS)"),
+];
+
+fn result_code_known_str(result: ctru_sys::Result) -> Option<&'static str> {
+    KNOWN_RESULT_CODES
+        .iter()
+        .find(|&&(code, _)| code == result as u32)
+        .map(|&(_, description)| description)
+}
+
+fn result_code_io_error_kind(result: ctru_sys::Result) -> io::ErrorKind {
+    use ctru_sys::{
+        RD_ALREADY_EXISTS, RD_INVALID_ADDRESS, RD_INVALID_COMBINATION, RD_INVALID_ENUM_VALUE,
+        RD_INVALID_POINTER, RD_INVALID_SELECTION, RD_INVALID_SIZE, RD_NOT_AUTHORIZED, RD_NOT_FOUND,
+        RD_NOT_IMPLEMENTED, RD_OUT_OF_MEMORY, RD_TIMEOUT, RS_INVALIDARG, RS_NOTFOUND,
+        RS_NOTSUPPORTED, RS_WOULDBLOCK, RS_WRONGARG,
+    };
+
+    // FS errors use their own description codes, so the most common ones are matched as a whole.
+    match result as u32 {
+        0x082044BE => return io::ErrorKind::AlreadyExists,
+        0xC8804470 | 0xC8804478 => return io::ErrorKind::NotFound,
+        0xE0E046BE | 0xE0E046BF => return io::ErrorKind::InvalidInput,
+        _ => (),
+    }
+
+    match R_DESCRIPTION(result) as u32 {
+        RD_NOT_FOUND => return io::ErrorKind::NotFound,
+        RD_ALREADY_EXISTS => return io::ErrorKind::AlreadyExists,
+        RD_NOT_AUTHORIZED => return io::ErrorKind::PermissionDenied,
+        RD_TIMEOUT => return io::ErrorKind::TimedOut,
+        RD_OUT_OF_MEMORY => return io::ErrorKind::OutOfMemory,
+        RD_NOT_IMPLEMENTED => return io::ErrorKind::Unsupported,
+        RD_INVALID_ADDRESS
+        | RD_INVALID_COMBINATION
+        | RD_INVALID_ENUM_VALUE
+        | RD_INVALID_POINTER
+        | RD_INVALID_SELECTION
+        | RD_INVALID_SIZE => return io::ErrorKind::InvalidInput,
+        _ => (),
+    }
+
+    match R_SUMMARY(result) as u32 {
+        RS_NOTFOUND => io::ErrorKind::NotFound,
+        RS_NOTSUPPORTED => io::ErrorKind::Unsupported,
+        RS_INVALIDARG | RS_WRONGARG => io::ErrorKind::InvalidInput,
+        RS_WOULDBLOCK => io::ErrorKind::WouldBlock,
+        _ => io::ErrorKind::Other,
+    }
+}
 
 fn result_code_level_str(result: ctru_sys::Result) -> Cow<'static, str> {
     use ctru_sys::{
@@ -376,3 +599,43 @@ fn result_code_module_str(result: ctru_sys::Result) -> Cow<'static, str> {
         code => return Cow::Owned(format!("(unknown module: {code:#x})")),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transient_errors() {
+        let temporary = ctru_sys::MAKERESULT(
+            ctru_sys::RL_TEMPORARY as i32,
+            ctru_sys::RS_NOTFOUND as i32,
+            ctru_sys::RM_SRV as i32,
+            ctru_sys::RD_NOT_FOUND as i32,
+        );
+
+        assert!(Error::Os(temporary).is_transient());
+        // Service not registered yet.
+        assert!(Error::Os(0xD88007FA_u32 as i32).is_transient());
+    }
+
+    #[test]
+    fn permanent_errors() {
+        // Path not found.
+        assert!(!Error::Os(0xC8804470_u32 as i32).is_transient());
+        // Not enough free space.
+        assert!(!Error::Os(0x086044D2_u32 as i32).is_transient());
+        // Invalid handle.
+        assert!(!Error::Os(0xD8E007F7_u32 as i32).is_transient());
+        assert!(!Error::ServiceAlreadyActive.is_transient());
+    }
+
+    #[test]
+    fn transient_context() {
+        let error = Error::Context {
+            description: String::from("initializing the service"),
+            source: Box::new(Error::Os(0xD88007FA_u32 as i32)),
+        };
+
+        assert!(error.is_transient());
+    }
+}
@@ -91,13 +91,7 @@ fn panic_hook_setup() {
             println!("\nPress SELECT to exit the software");
 
             match Hid::new() {
-                Ok(mut hid) => loop {
-                    hid.scan_input();
-                    let keys = hid.keys_down();
-                    if keys.contains(KeyPad::SELECT) {
-                        break;
-                    }
-                },
+                Ok(mut hid) => while !hid.wait_for_key().contains(KeyPad::SELECT) {},
                 Err(e) => println!("Error while intializing Hid controller during panic: {e}"),
             }
         }
@@ -3,12 +3,18 @@
 //! LINEAR memory is a sector of the 3DS' RAM that binds virtual addresses exactly to the physical address.
 //! As such, it is used for fast and safe memory sharing between different hardware components (such as the GPU and the DSP processor).
 //!
+//! The amount of memory reserved to the LINEAR heap is decided when the application starts.
+//! Have a look at [`linear_heap_size!`](crate::linear_heap_size) to learn how to change it.
+//!
 //! # Additional Resources
 //!
 //! - <https://github.com/devkitPro/libctru/blob/master/libctru/source/allocator/linear.cpp>
 //! - <https://www.3dbrew.org/wiki/Memory_layout>
 
 use std::alloc::{AllocError, Allocator, Layout};
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
 
 // Implementing an `std::alloc::Allocator` type is the best way to handle this case, since it gives
@@ -45,3 +51,243 @@ unsafe impl Allocator for LinearAllocator {
         ctru_sys::linearFree(ptr.as_ptr().cast());
     }
 }
+
+/// Set the size (in bytes) of the LINEAR heap reserved when the application starts.
+///
+/// By default, `libctru` splits the free APPLICATION memory equally between the LINEAR heap and the normal heap (used by the Rust allocator).
+/// Applications that allocate many large buffers for hardware components (e.g. camera images or audio waves) may run out of LINEAR memory
+/// while plenty of normal heap is left unused. This macro overrides the size of the LINEAR heap, and the normal heap gets the remaining memory.
+///
+/// # Notes
+///
+/// Every byte reserved for the LINEAR heap is taken from the normal heap, so `Box`, `Vec` and other standard allocations have less memory available.
+/// The size is rounded by `libctru` to a multiple of the page size (`0x1000` bytes). If it exceeds the available memory, the application crashes on startup.
+///
+/// This macro must be used only once, in the binary crate (not in a library).
+///
+/// # Example
+///
+/// ```no_run
+/// // Reserve 48MB of LINEAR memory for camera buffers.
+/// ctru::linear_heap_size!(48 * 1024 * 1024);
+///
+/// fn main() {
+///     // ...
+/// }
+/// ```
+#[macro_export]
+macro_rules! linear_heap_size {
+    ($size:expr) => {
+        #[no_mangle]
+        #[doc(hidden)]
+        static __ctru_linear_heap_size: u32 = $size;
+    };
+}
+
+/// A pointer type that uniquely owns a single value allocated in LINEAR memory.
+///
+/// This is a thin wrapper around a [`Box`] using the [`LinearAllocator`], which is useful to
+/// share single values (e.g. configuration structs or DMA descriptors) with hardware components.
+/// The value is allocated with its correct alignment and dropped (and freed) when the [`LinearBox`] goes out of scope.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::linear::LinearBox;
+///
+/// let mut config = LinearBox::new([0u32; 4]);
+/// config[0] = 42;
+///
+/// // The pointer can be handed over to a hardware service.
+/// let ptr = config.as_ptr();
+/// # assert!(!ptr.is_null());
+/// ```
+pub struct LinearBox<T: ?Sized>(Box<T, LinearAllocator>);
+
+impl<T> LinearBox<T> {
+    /// Allocate `value` in LINEAR memory.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic (via [`handle_alloc_error`](std::alloc::handle_alloc_error)) if there isn't enough LINEAR memory left.
+    pub fn new(value: T) -> Self {
+        Self(Box::new_in(value, LinearAllocator))
+    }
+
+    /// Consume the [`LinearBox`], returning the wrapped value.
+    pub fn into_inner(this: Self) -> T {
+        *this.0
+    }
+}
+
+impl<T: Clone> LinearBox<[T]> {
+    /// Allocate a slice of `len` elements in LINEAR memory, each initialized to `value`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic (via [`handle_alloc_error`](std::alloc::handle_alloc_error)) if there isn't enough LINEAR memory left.
+    pub fn new_slice(value: T, len: usize) -> Self {
+        let mut vec = Vec::with_capacity_in(len, LinearAllocator);
+        vec.resize(len, value);
+
+        Self(vec.into_boxed_slice())
+    }
+}
+
+impl<T: ?Sized> LinearBox<T> {
+    /// Returns a raw pointer to the value, to be used for FFI.
+    pub fn as_ptr(&self) -> *const T {
+        &*self.0
+    }
+
+    /// Returns a raw mutable pointer to the value, to be used for FFI.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        &mut *self.0
+    }
+}
+
+impl<T: ?Sized> Deref for LinearBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for LinearBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for LinearBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LinearBox").field(&&*self.0).finish()
+    }
+}
+
+/// Arena of LINEAR memory, handing out allocations from a single block reserved up front.
+///
+/// Allocating and freeing many short-lived buffers directly from the LINEAR heap fragments it over time.
+/// An [`Arena`] avoids this by reserving one block when created and serving each allocation from its unused part:
+/// individual allocations are never freed, instead the whole arena is emptied at once with [`Arena::reset()`]
+/// (e.g. at the beginning of each frame).
+///
+/// The [`Allocator`] trait is implemented for `&Arena`, so it can be used with [`Box`], [`Vec`] and other allocator-aware types.
+/// Since every allocation borrows the arena, the borrow checker ensures the arena outlives its allocations and that they
+/// are all dropped before it is reset.
+///
+/// To use this struct the main crate must activate the `allocator_api` unstable feature.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::linear::Arena;
+///
+/// let mut arena = Arena::new(64 * 1024);
+///
+/// for _frame in 0..3 {
+///     // Scratch buffers for this frame.
+///     let vertices = Box::new_in([0.0f32; 256], &arena);
+///     let mut commands = Vec::with_capacity_in(128, &arena);
+///     commands.push(0u32);
+///     # let _ = vertices;
+///
+///     // ... hand the buffers over to the GPU ...
+///
+///     drop(commands);
+///     drop(vertices);
+///     arena.reset();
+/// }
+/// ```
+pub struct Arena {
+    block: NonNull<u8>,
+    capacity: usize,
+    used: Cell<usize>,
+}
+
+impl Arena {
+    /// Reserve an arena of `capacity` bytes in LINEAR memory.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic (via [`handle_alloc_error`](std::alloc::handle_alloc_error)) if there isn't enough LINEAR memory left.
+    pub fn new(capacity: usize) -> Self {
+        // The GPU needs its buffers aligned to 16 bytes, so start from a generous alignment.
+        let layout = Layout::from_size_align(capacity.max(1), 0x80).unwrap();
+
+        let block = LinearAllocator
+            .allocate(layout)
+            .unwrap_or_else(|_| std::alloc::handle_alloc_error(layout));
+
+        Self {
+            block: block.cast(),
+            capacity,
+            used: Cell::new(0),
+        }
+    }
+
+    /// Returns the size (in bytes) of the arena.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of bytes currently allocated from the arena, including the padding needed by alignment.
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Empty the arena, making its whole capacity available again.
+    ///
+    /// This only resets an offset, so it takes constant time regardless of the number of allocations.
+    /// Taking the arena mutably guarantees no allocation is still alive.
+    pub fn reset(&mut self) {
+        self.used.set(0);
+    }
+}
+
+unsafe impl Allocator for &Arena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let base = self.block.as_ptr() as usize;
+
+        // Align the address of the allocation, not just the offset, so any alignment is supported.
+        let start = (base + self.used.get())
+            .checked_add(layout.align() - 1)
+            .ok_or(AllocError)?
+            & !(layout.align() - 1);
+        let end = start.checked_add(layout.size()).ok_or(AllocError)?;
+
+        if end > base + self.capacity {
+            return Err(AllocError);
+        }
+
+        self.used.set(end - base);
+
+        // Safety: the allocation is inside the block, which isn't null.
+        let ptr = unsafe { NonNull::new_unchecked(self.block.as_ptr().add(start - base)) };
+
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        // Allocations are freed all at once by `Arena::reset()`.
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        unsafe { ctru_sys::linearFree(self.block.as_ptr().cast()) };
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("capacity", &self.capacity)
+            .field("used", &self.used.get())
+            .finish()
+    }
+}
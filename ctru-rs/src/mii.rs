@@ -246,6 +246,12 @@ pub struct MoleDetails {
 /// Some values are not ordered *like* the Mii Editor UI. The mapped values can be seen [here](https://www.3dbrew.org/wiki/Mii#Mapped_Editor_.3C-.3E_Hex_values).
 ///
 /// This struct can be retrieved by [`MiiSelector::launch()`](crate::applets::mii_selector::MiiSelector::launch).
+///
+/// # Notes
+///
+/// The Mii data doesn't contain any picture of the Mii (not even a thumbnail): it only describes the parts that make up its face.
+/// Drawing the face requires the Mii rendering library used by the system (CFL) and its 3D models, which aren't available
+/// to homebrew applications, so this crate doesn't provide a way to render a Mii.
 #[derive(Clone, Debug)]
 pub struct Mii {
     /// Mii options.
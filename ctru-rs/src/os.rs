@@ -1,5 +1,12 @@
 //! Utilities to get information about the operating system and hardware state.
 
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::error::{ResultCode, ResultExt};
+use crate::services::cfgu::Cfgu;
+use crate::services::ps::Ps;
+
 /// System version information. This struct is used for both kernel and firmware versions.
 ///
 /// # Example
@@ -50,6 +57,86 @@ pub fn kernel_version() -> Version {
     Version(unsafe { ctru_sys::osGetKernelVersion() })
 }
 
+/// Version of the system software, as shown in the System Settings (e.g. `11.17.0-50E`).
+///
+/// See [`system_version()`] to learn how to retrieve it. Versions can be compared, e.g. to check for a minimum required version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemVersion {
+    /// Major version (e.g. `11` in `11.17.0-50E`).
+    pub major: u8,
+    /// Minor version (e.g. `17` in `11.17.0-50E`).
+    pub minor: u8,
+    /// Build version (e.g. `0` in `11.17.0-50E`).
+    pub build: u8,
+    /// Version of the NUP (system update) data (e.g. `50` in `11.17.0-50E`).
+    pub nup: u8,
+    /// Region letter (e.g. `E` in `11.17.0-50E`).
+    pub region: char,
+}
+
+impl fmt::Display for SystemVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}-{}{}",
+            self.major, self.minor, self.build, self.nup, self.region
+        )
+    }
+}
+
+/// Get the version of the system software, as shown in the System Settings.
+///
+/// # Notes
+///
+/// Unlike the [FIRM](firm_version) and [kernel](kernel_version) versions, the system version isn't stored in memory:
+/// it's read from the `version.bin` files of the CVer and NVer system titles installed on the NAND.
+/// Reading them requires access to the `fs:USER` service and to the NAND title archives, which is granted to applications
+/// launched by the Homebrew Launcher or installed as CIA titles on consoles running a custom firmware. It may fail on emulators.
+///
+/// The system version is the best way to check for features added by a system update, but keep in mind that custom firmwares
+/// can run older system software on a newer FIRM, and vice versa.
+///
+/// # Errors
+///
+/// This function will return an error if the version titles couldn't be read.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// let version = ctru::os::system_version()?;
+///
+/// if (version.major, version.minor) < (11, 4) {
+///     println!("System version {version} is too old, please update.");
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "osGetSystemVersionData")]
+pub fn system_version() -> crate::Result<SystemVersion> {
+    let mut nver = ctru_sys::OS_VersionBin::default();
+    let mut cver = ctru_sys::OS_VersionBin::default();
+
+    ResultCode(unsafe { ctru_sys::fsInit() })?;
+
+    let result = unsafe { ctru_sys::osGetSystemVersionData(&mut nver, &mut cver) };
+
+    unsafe { ctru_sys::fsExit() };
+
+    ResultCode(result)?;
+
+    Ok(SystemVersion {
+        major: cver.mainver,
+        minor: cver.minor,
+        build: cver.build,
+        nup: nver.mainver,
+        region: char::from(nver.region as u8),
+    })
+}
+
 // TODO: I can't seem to find good documentation on it, but we could probably
 // define enums for firmware type (NATIVE_FIRM, SAFE_FIRM etc.) as well as
 // application memory layout. Leaving those as future enhancements for now
@@ -105,6 +192,100 @@ impl MemRegion {
     }
 }
 
+/// Free LINEAR memory (in bytes) below which [`memory_pressure()`] reports [`MemoryPressure::Low`].
+const LOW_LINEAR_FREE: usize = 2 * 1024 * 1024;
+/// Free LINEAR memory (in bytes) below which [`memory_pressure()`] reports [`MemoryPressure::Critical`].
+const CRITICAL_LINEAR_FREE: usize = 512 * 1024;
+/// Free heap memory (in bytes) below which [`memory_pressure()`] reports [`MemoryPressure::Low`].
+const LOW_HEAP_FREE: usize = 4 * 1024 * 1024;
+/// Free heap memory (in bytes) below which [`memory_pressure()`] reports [`MemoryPressure::Critical`].
+const CRITICAL_HEAP_FREE: usize = 1024 * 1024;
+
+/// How close the application is to running out of memory. See [`memory_pressure()`].
+///
+/// Levels are ordered by severity, so they can be compared (e.g. `pressure >= MemoryPressure::Low`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryPressure {
+    /// There is plenty of free memory.
+    Normal,
+    /// Free memory is getting low: caches should be trimmed.
+    Low,
+    /// The next large allocations are likely to fail.
+    Critical,
+}
+
+/// Returns how close the application is to running out of memory, based on the free space in the normal heap and in the LINEAR heap.
+///
+/// # Notes
+///
+/// `libctru` splits the APPLICATION memory between the normal heap (used by the Rust allocator) and the LINEAR heap when the application starts
+/// (see [`linear_heap_size!`](crate::linear_heap_size)), so the free space of the [`MemRegion::Application`] region itself is always close to 0.
+/// The free space of both heaps is checked instead, and the most severe level is returned:
+///
+/// | Level                        | Normal heap | LINEAR heap |
+/// |------------------------------|-------------|-------------|
+/// | [`MemoryPressure::Low`]      | < 4 MiB     | < 2 MiB     |
+/// | [`MemoryPressure::Critical`] | < 1 MiB     | < 512 KiB   |
+///
+/// These thresholds are heuristic: free memory may be fragmented, so a large allocation can still fail under [`MemoryPressure::Normal`].
+/// Applications with unusual allocation patterns should compare the free space against their own needs.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::os::MemoryPressure;
+///
+/// # let mut texture_cache = vec![0u8; 1024];
+/// if ctru::os::memory_pressure() >= MemoryPressure::Low {
+///     texture_cache.clear();
+///     texture_cache.shrink_to_fit();
+/// }
+/// ```
+#[doc(alias = "mallinfo", alias = "linearSpaceFree")]
+pub fn memory_pressure() -> MemoryPressure {
+    let linear_free = unsafe { ctru_sys::linearSpaceFree() } as usize;
+    let heap_free = heap_free_space();
+
+    if heap_free < CRITICAL_HEAP_FREE || linear_free < CRITICAL_LINEAR_FREE {
+        MemoryPressure::Critical
+    } else if heap_free < LOW_HEAP_FREE || linear_free < LOW_LINEAR_FREE {
+        MemoryPressure::Low
+    } else {
+        MemoryPressure::Normal
+    }
+}
+
+/// Returns the free space (in bytes) of the normal heap: the part not yet claimed by the allocator, plus the freed chunks it holds.
+fn heap_free_space() -> usize {
+    // Layout of newlib's `struct mallinfo`.
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct MallInfo {
+        arena: libc::size_t,
+        ordblks: libc::size_t,
+        smblks: libc::size_t,
+        hblks: libc::size_t,
+        hblkhd: libc::size_t,
+        usmblks: libc::size_t,
+        fsmblks: libc::size_t,
+        uordblks: libc::size_t,
+        fordblks: libc::size_t,
+        keepcost: libc::size_t,
+    }
+
+    extern "C" {
+        static __ctru_heap_size: u32;
+        fn mallinfo() -> MallInfo;
+    }
+
+    // Safety: `libctru` sets the heap size before `main` and never changes it afterwards.
+    let heap_size = unsafe { __ctru_heap_size } as usize;
+    let info = unsafe { mallinfo() };
+
+    heap_size.saturating_sub(info.arena) + info.fordblks
+}
+
 /// WiFi signal strength. This enum's `u8` representation corresponds with
 /// the number of bars displayed in the Home menu.
 ///
@@ -132,8 +313,10 @@ pub enum WifiStrength {
 
 impl WifiStrength {
     /// Get the current WiFi signal strength.
+    ///
+    /// See [`wifi_strength()`] to get the raw number of bars.
     pub fn current() -> Self {
-        match unsafe { ctru_sys::osGetWifiStrength() } {
+        match wifi_strength() {
             0 => Self::Disconnected,
             1 => Self::Bad,
             2 => Self::Decent,
@@ -143,12 +326,1081 @@ impl WifiStrength {
     }
 }
 
+/// Get the current WiFi signal strength as the number of bars (0-3).
+///
+/// # Notes
+///
+/// This is the same value shown by the Home Menu's signal meter. It is read directly from the shared configuration memory,
+/// so it requires no service to be active and is cheap enough to be polled every frame.
+///
+/// See [`WifiStrength`] for a typed version of this value.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let bars = ctru::os::wifi_strength();
+/// assert!(bars < 4);
+/// ```
+#[doc(alias = "osGetWifiStrength")]
+pub fn wifi_strength() -> u8 {
+    unsafe { ctru_sys::osGetWifiStrength() }
+}
+
+/// Get the hardware (MAC) address of the console's WiFi module.
+///
+/// # Notes
+///
+/// The address is read directly from the shared configuration memory (see [`ConfigMem::wifi_mac_address()`]),
+/// which is mapped in every process: it requires no service to be active nor any special permission.
+///
+/// The address is unique to each console, and it's the one seen by other devices on the local network.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let mac = ctru::os::mac_address();
+///
+/// println!(
+///     "MAC address: {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+///     mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+/// );
+/// ```
+#[doc(alias = "wifi_macaddr")]
+pub fn mac_address() -> [u8; 6] {
+    config_mem().wifi_mac_address()
+}
+
 /// Get the current value of the stereoscopic 3D slider on a scale from 0.0­–­1.0.
+///
+/// The value is read from the shared configuration memory, so it requires no service to be active. See also [`Hid::slider_3d()`](crate::services::hid::Hid::slider_3d).
+#[doc(alias = "osGet3DSliderState")]
 pub fn current_3d_slider_state() -> f32 {
     unsafe { ctru_sys::osGet3DSliderState() }
 }
 
+/// ID of the config block holding the parental controls settings.
+const PARENTAL_CONTROLS_BLOCK_ID: u32 = 0x000C0000;
+
+/// Size (in bytes) of the parental controls config block.
+const PARENTAL_CONTROLS_BLOCK_SIZE: usize = 0xC0;
+
+/// Bit of the parental restrictions bitmask which is set when the parental controls are enabled.
+const PARENTAL_CONTROLS_ENABLED_BIT: u32 = 1 << 0;
+
+/// Bit of the parental restrictions bitmask which restricts the display of 3D images.
+const RESTRICT_3D_BIT: u32 = 1 << 2;
+
+/// Returns `true` if the user restricted the display of 3D images in the System Settings.
+///
+/// # Notes
+///
+/// The setting is found in the Parental Controls of the System Settings ("Display of 3D Images"). It's stored in the config block `0x000C0000`,
+/// which starts with a little-endian bitmask of the restricted features: bit 0 is set when the parental controls are enabled,
+/// and bit 2 is set when 3D images are restricted. The restriction only applies if both are set.
+/// See <https://www.3dbrew.org/wiki/Config_Savegame#Configuration_blocks> for the layout of the block.
+///
+/// The restriction is only a setting: the 3D effect is still shown if the application enables it, so applications should
+/// check this value and render in 2D when it's `true`. Nintendo 2DS models can't show 3D images regardless of the setting
+/// (see [`Cfgu::model()`](crate::services::cfgu::Cfgu::model)).
+///
+/// This function briefly initializes the [`Cfgu`](crate::services::cfgu::Cfgu) service. If the setting can't be read, `false` is returned.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let enable_3d = !ctru::os::is_3d_disabled_by_user();
+///
+/// if enable_3d {
+///     println!("Slide the 3D slider up for the full experience!");
+/// }
+/// ```
+#[doc(alias = "CFGU_GetConfigInfoBlk2")]
+pub fn is_3d_disabled_by_user() -> bool {
+    let Ok(cfgu) = Cfgu::new() else {
+        return false;
+    };
+
+    let mut block = [0u8; PARENTAL_CONTROLS_BLOCK_SIZE];
+
+    if cfgu
+        .config_block(PARENTAL_CONTROLS_BLOCK_ID, &mut block)
+        .is_err()
+    {
+        return false;
+    }
+
+    let restrictions = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+
+    let restrict_3d = PARENTAL_CONTROLS_ENABLED_BIT | RESTRICT_3D_BIT;
+
+    restrictions & restrict_3d == restrict_3d
+}
+
+/// Get the ID of the processor core the calling thread is running on.
+///
+/// # Notes
+///
+/// Cores are numbered as follows:
+///
+/// * `0` - The application core ("appcore"), where the main thread of the application runs.
+/// * `1` - The system core ("syscore"), usable by the application only after lending it some time via [`Apt::set_app_cpu_time_limit()`](crate::services::apt::Apt::set_app_cpu_time_limit).
+/// * `2` - Additional application core, only available on New 3DS models.
+/// * `3` - Additional system core, only available on New 3DS models and reserved to the OS.
+///
+/// See [`Apt::available_processors()`](crate::services::apt::Apt::available_processors) to get the list of cores usable by the application.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let core = ctru::os::current_processor();
+/// assert!(core < 4);
+/// ```
+#[doc(alias = "svcGetProcessorID")]
+pub fn current_processor() -> usize {
+    unsafe { ctru_sys::svcGetProcessorID() }.try_into().unwrap()
+}
+
+/// Returns `true` if the application is running on the Citra emulator.
+///
+/// # Notes
+///
+/// Citra implements an additional type of system information (`0x20000`) for `svcGetSystemInfo`, which isn't available on real hardware.
+/// Requesting it returns `1` on Citra, while the console's kernel (including custom firmwares like Luma3DS) returns an error.
+/// This makes the detection reliable, unless another emulator mimics Citra's behaviour.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// if ctru::os::is_citra() {
+///     println!("The camera will only show a test pattern.");
+/// }
+/// ```
+#[doc(alias = "svcGetSystemInfo")]
+pub fn is_citra() -> bool {
+    let mut is_citra = 0;
+
+    let result = unsafe { ctru_sys::svcGetSystemInfo(&mut is_citra, 0x20000, 0) };
+
+    ctru_sys::R_SUCCEEDED(result) && is_citra == 1
+}
+
+/// Query kernel information of type `category` with the `param` argument.
+///
+/// This is a thin wrapper around `svcGetSystemInfo`: the meaning of `param` and of the returned value depends on the category.
+/// See [`used_memory()`] and [`uptime_ticks()`] for typed helpers.
+///
+/// # Notes
+///
+/// The categories documented on <https://www.3dbrew.org/wiki/SVC> are:
+///
+/// - `0`: memory used (in bytes) by all processes in a memory region. `param` selects the region like [`MemRegion`] (`0` for all regions).
+/// - `2`: memory used (in bytes) by the kernel itself (`param` is ignored).
+/// - `26`: number of processes launched by the kernel at boot (`param` is ignored).
+/// - `0x10000`: information about Luma3DS (version, commit hash, configuration), only available under that custom firmware.
+/// - `0x20000`: emulator detection, only available under Citra. See [`is_citra()`].
+///
+/// Access to `svcGetSystemInfo` is granted by the application's exheader. Applications launched through the Homebrew Launcher
+/// under a custom firmware can call it freely, while titles installed with a restrictive exheader (and some other categories,
+/// which are reserved to privileged kernels) get an error instead.
+///
+/// # Errors
+///
+/// This function will return an error if the category is unknown to the running kernel, or if the application isn't allowed to use the SVC.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// // Memory used by the kernel.
+/// let kernel_memory = ctru::os::system_info(2, 0)?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "svcGetSystemInfo")]
+pub fn system_info(category: u32, param: i32) -> crate::Result<i64> {
+    let mut out = 0;
+
+    ResultCode(unsafe { ctru_sys::svcGetSystemInfo(&mut out, category, param) })?;
+
+    Ok(out)
+}
+
+/// Returns the total number of bytes used by all processes running on the console (including system modules).
+///
+/// # Notes
+///
+/// This reads the same counter as [`MemRegion::used()`] for [`MemRegion::All`], but an error is returned (instead of `0`)
+/// when the application isn't allowed to query it. See [`system_info()`] for more information.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// let used = ctru::os::used_memory()?;
+/// println!("{} KiB used", used / 1024);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "svcGetSystemInfo")]
+pub fn used_memory() -> crate::Result<u64> {
+    Ok(system_info(0, MemRegion::All as i32)? as u64)
+}
+
+/// Returns the number of system ticks elapsed since the console was powered on.
+///
+/// # Notes
+///
+/// `svcGetSystemInfo` has no uptime category, so the ticks are read with `svcGetSystemTick`, which every application can call.
+/// Ticks run at the ARM11 clock rate (`ctru_sys::SYSCLOCK_ARM11`, about 268 MHz) and keep counting while the console sleeps.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let ticks = ctru::os::uptime_ticks();
+/// let seconds = ticks / ctru_sys::SYSCLOCK_ARM11 as u64;
+///
+/// println!("Up for {seconds} seconds");
+/// ```
+#[doc(alias = "svcGetSystemTick")]
+pub fn uptime_ticks() -> u64 {
+    unsafe { ctru_sys::svcGetSystemTick() }
+}
+
+/// Seconds between the epoch of `osGetTime` (1900-01-01) and the Unix epoch (1970-01-01).
+const SECONDS_1900_TO_1970: i64 = 2_208_988_800;
+
+/// Calendar date and time of day, as returned by [`datetime()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    /// Year (e.g. 2024).
+    pub year: i32,
+    /// Month of the year, from 1 (January) to 12 (December).
+    pub month: u8,
+    /// Day of the month, from 1 to 31.
+    pub day: u8,
+    /// Hour of the day, from 0 to 23.
+    pub hour: u8,
+    /// Minute of the hour, from 0 to 59.
+    pub minute: u8,
+    /// Second of the minute, from 0 to 59.
+    pub second: u8,
+    /// Day of the week, from 0 (Sunday) to 6 (Saturday).
+    pub weekday: u8,
+}
+
+impl DateTime {
+    /// Returns the date and time corresponding to the number of seconds elapsed since the Unix epoch (1970-01-01 00:00:00).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::os::DateTime;
+    ///
+    /// let datetime = DateTime::from_unix_timestamp(951_782_400);
+    ///
+    /// assert_eq!((datetime.year, datetime.month, datetime.day), (2000, 2, 29));
+    /// // It was a Tuesday.
+    /// assert_eq!(datetime.weekday, 2);
+    /// ```
+    pub fn from_unix_timestamp(timestamp: i64) -> Self {
+        let days = timestamp.div_euclid(86400);
+        let seconds = timestamp.rem_euclid(86400);
+
+        // Civil date from the days since the epoch, counting years from March so that leap days come last.
+        let days_since_0000 = days + 719_468;
+        let era = days_since_0000.div_euclid(146_097);
+        let day_of_era = days_since_0000.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+            // The epoch was a Thursday.
+            weekday: (days + 4).rem_euclid(7) as u8,
+        }
+    }
+
+    /// Returns the number of seconds elapsed since the Unix epoch (1970-01-01 00:00:00), ignoring [`DateTime::weekday`].
+    ///
+    /// # Notes
+    ///
+    /// The console has no notion of time zones, so the date and time are treated as UTC.
+    /// Timestamps obtained from [`datetime()`] thus represent the local time set by the user, which is also what
+    /// the system records (e.g. in the timestamps of the files on the SD card).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::os::{self, DateTime};
+    ///
+    /// let now = os::datetime();
+    ///
+    /// assert_eq!(DateTime::from_unix_timestamp(now.to_unix_timestamp()), now);
+    /// ```
+    pub fn to_unix_timestamp(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+}
+
+/// Returns the current date and time, as set in the System Settings.
+///
+/// # Notes
+///
+/// The time is read with `osGetTime`, which combines the date and time last written by the kernel to the shared configuration page
+/// (from the console's real-time clock, plus the offset chosen by the user in the System Settings) with the system ticks elapsed since then.
+/// It's as accurate as the clock set by the user, and doesn't depend on the state of the `libc` time functions.
+///
+/// The console has no notion of time zones: the returned value is the local time of the user. See [`DateTime::to_unix_timestamp()`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let now = ctru::os::datetime();
+///
+/// println!(
+///     "Saved on {}-{:02}-{:02} at {:02}:{:02}",
+///     now.year, now.month, now.day, now.hour, now.minute
+/// );
+/// ```
+#[doc(alias = "osGetTime")]
+#[doc(alias = "now")]
+pub fn datetime() -> DateTime {
+    let milliseconds = unsafe { ctru_sys::osGetTime() };
+
+    DateTime::from_unix_timestamp((milliseconds / 1000) as i64 - SECONDS_1900_TO_1970)
+}
+
+/// Fill `buf` with random bytes generated by the console's hardware random number generator.
+///
+/// The bytes are cryptographically secure, so they can be used for keys and nonces.
+///
+/// # Notes
+///
+/// The bytes are generated by the `ps:ps` service, which is brought up for the duration of the call (see [`Ps`](crate::services::ps::Ps)).
+/// Applications which need random bytes often can keep a [`Ps`](crate::services::ps::Ps) handle around and call
+/// [`Ps::generate_random_bytes()`](crate::services::ps::Ps::generate_random_bytes) instead.
+///
+/// # Errors
+///
+/// This function will return an error if the `ps:ps` service isn't available to the application (see [`Service::is_available()`](crate::services::Service::is_available)),
+/// or if the bytes couldn't be generated. The buffer is never filled with weaker random data in that case.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// // A new nonce for each encrypted save.
+/// let mut nonce = [0u8; 12];
+/// ctru::os::random_bytes(&mut nonce)?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "PS_GenerateRandomBytes")]
+pub fn random_bytes(buf: &mut [u8]) -> crate::Result<()> {
+    if buf.is_empty() {
+        return Ok(());
+    }
+
+    let ps = Ps::new().context("the ps:ps service is needed to generate random bytes")?;
+
+    ps.generate_random_bytes(buf)
+}
+
+/// Returns the arguments the application was launched with.
+///
+/// # Notes
+///
+/// Arguments are passed by the Homebrew Launcher (and by `3dslink`) in a small buffer shared with the application, which `libctru` parses
+/// at startup into `__system_argc` and `__system_argv`. By convention, the first argument is the path of the `.3dsx` file that was launched
+/// (e.g. `sdmc:/3ds/viewer.3dsx`), followed by any additional argument: when a file is opened through a file association in the Homebrew Launcher,
+/// its path is the second argument.
+///
+/// The list is empty if the application wasn't started by a launcher supporting this protocol, e.g. when it's installed as a CIA title
+/// or launched by some emulators. Arguments which aren't valid UTF-8 are converted lossily.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// // Open the file the application was launched with, if any.
+/// if let Some(path) = ctru::os::args().get(1) {
+///     println!("Opening {path}");
+/// }
+/// ```
+#[doc(alias = "__system_argv")]
+pub fn args() -> Vec<String> {
+    extern "C" {
+        static __system_argc: libc::c_int;
+        static __system_argv: *mut *mut libc::c_char;
+    }
+
+    // Safety: `libctru` sets both values before `main` and never changes them afterwards.
+    let (argc, argv) = unsafe { (__system_argc, __system_argv) };
+
+    if argv.is_null() {
+        return Vec::new();
+    }
+
+    (0..usize::try_from(argc).unwrap_or(0))
+        .map(|i| unsafe { *argv.add(i) })
+        .take_while(|arg| !arg.is_null())
+        .map(|arg| {
+            unsafe { std::ffi::CStr::from_ptr(arg) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+/// Returns the number of handles (to services, threads, events, files, sockets, etc.) currently open by the application.
+///
+/// # Notes
+///
+/// Handle counts are read from the kernel object of the application's own process, which doesn't require any special privilege,
+/// so they are reliable under homebrew launchers too. A steadily increasing count is a good sign of a handle leak.
+///
+/// The maximum number of handles is decided by the application's exheader and can't be queried at runtime.
+/// Emulators may not implement this query, in which case an error is returned.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// let handles = ctru::os::handle_count()?;
+/// assert!(handles > 0);
+///
+/// println!("{handles} handles open (at most {} at once)", ctru::os::peak_handle_count()?);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "svcGetProcessInfo")]
+pub fn handle_count() -> crate::Result<usize> {
+    process_info(4)
+}
+
+/// Returns the highest number of handles that have been open at the same time by the application.
+///
+/// See [`handle_count()`] for more information.
+#[doc(alias = "svcGetProcessInfo")]
+pub fn peak_handle_count() -> crate::Result<usize> {
+    process_info(5)
+}
+
+/// Returns the number of threads currently running in the application (including the main thread).
+///
+/// # Notes
+///
+/// This count is read from the kernel object of the application's own process, which doesn't require any special privilege.
+/// See [`thread_limit()`] to get the maximum number of threads.
+#[doc(alias = "svcGetProcessInfo")]
+pub fn thread_count() -> crate::Result<usize> {
+    process_info(7)
+}
+
+/// Returns the maximum number of threads the application is allowed to create, according to its resource limits.
+///
+/// # Notes
+///
+/// Resource limits are shared by all processes in the same category (e.g. all applications),
+/// so the limit is an upper bound rather than a guarantee.
+#[doc(
+    alias = "svcGetResourceLimit",
+    alias = "svcGetResourceLimitLimitValues"
+)]
+pub fn thread_limit() -> crate::Result<usize> {
+    let mut resource_limit = 0;
+    let mut limit = 0;
+    let mut name = ctru_sys::RESLIMIT_THREAD;
+
+    unsafe {
+        ResultCode(ctru_sys::svcGetResourceLimit(
+            &mut resource_limit,
+            ctru_sys::CUR_PROCESS_HANDLE,
+        ))?;
+
+        let result =
+            ctru_sys::svcGetResourceLimitLimitValues(&mut limit, resource_limit, &mut name, 1);
+
+        let _ = ctru_sys::svcCloseHandle(resource_limit);
+
+        ResultCode(result)?;
+    }
+
+    Ok(limit as usize)
+}
+
+/// Query information of type `kind` about the current process.
+fn process_info(kind: u32) -> crate::Result<usize> {
+    let mut out = 0;
+
+    ResultCode(unsafe {
+        ctru_sys::svcGetProcessInfo(&mut out, ctru_sys::CUR_PROCESS_HANDLE, kind)
+    })?;
+
+    Ok(out as usize)
+}
+
+/// Maximum number of processes the kernel can run at the same time.
+const MAX_PROCESSES: usize = 64;
+
+/// Result returned by `svcOpenProcess` when no process has the requested ID.
+const PROCESS_NOT_FOUND: ctru_sys::Result = 0xD9001818_u32 as i32;
+
+/// Information about a process running on the console. See [`process_list()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcessInfo {
+    /// Process ID.
+    pub id: u32,
+    /// Name of the process (e.g. `"hid"` or `"menu"`), up to 8 characters long.
+    pub name: String,
+    /// Title ID of the program the process is running.
+    pub title_id: u64,
+}
+
+/// Returns the list of processes running on the console, including system modules and the application itself.
+///
+/// # Notes
+///
+/// Listing and opening other processes requires elevated access: the application's exheader must allow `svcGetProcessList` and `svcOpenProcess`,
+/// as it's usually the case for custom system modules. Names and title IDs are read with the `svcGetProcessInfo` extensions of Luma3DS (types `0x10000`
+/// and `0x10001`), which aren't available on the stock kernel.
+///
+/// Under Luma3DS, the SVC access checks are disabled for homebrew, so this works from the Homebrew Launcher too.
+/// Calling a forbidden SVC crashes the application instead of returning an error, so the presence of Luma3DS is checked beforehand.
+///
+/// # Errors
+///
+/// This function will return an error if the kernel extensions needed to query the processes aren't available, or if a process couldn't be opened or queried.
+/// Processes which exit while the list is being built are left out instead.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// for process in ctru::os::process_list()? {
+///     println!("{:>3} {:<8} {:016X}", process.id, process.name, process.title_id);
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "svcGetProcessList", alias = "svcOpenProcess")]
+pub fn process_list() -> crate::Result<Vec<ProcessInfo>> {
+    if system_info(0x10000, 0).is_err() {
+        return Err(crate::Error::Unsupported(String::from(
+            "listing processes requires the Luma3DS kernel extensions",
+        )))
+        .context("listing processes requires the kernel extensions of Luma3DS");
+    }
+
+    let mut ids = [0u32; MAX_PROCESSES];
+    let mut count = 0;
+
+    ResultCode(unsafe {
+        ctru_sys::svcGetProcessList(&mut count, ids.as_mut_ptr(), MAX_PROCESSES as i32)
+    })?;
+
+    ids.iter()
+        .take(count as usize)
+        .map(|&id| -> crate::Result<Option<ProcessInfo>> {
+            let mut handle = 0;
+            let result = unsafe { ctru_sys::svcOpenProcess(&mut handle, id) };
+
+            // The process exited after the list was retrieved.
+            if result == PROCESS_NOT_FOUND {
+                return Ok(None);
+            }
+
+            ResultCode(result)?;
+
+            let mut name = 0;
+            let mut title_id = 0;
+
+            let result = unsafe {
+                let result = ctru_sys::svcGetProcessInfo(&mut name, handle, 0x10000);
+                let result = if ctru_sys::R_SUCCEEDED(result) {
+                    ctru_sys::svcGetProcessInfo(&mut title_id, handle, 0x10001)
+                } else {
+                    result
+                };
+
+                let _ = ctru_sys::svcCloseHandle(handle);
+
+                result
+            };
+
+            ResultCode(result)?;
+
+            // The name is stored in the first 8 bytes, padded with NULs.
+            let name = name.to_le_bytes();
+            let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+
+            Ok(Some(ProcessInfo {
+                id,
+                name: String::from_utf8_lossy(&name[..len]).into_owned(),
+                title_id: title_id as u64,
+            }))
+        })
+        .filter_map(Result::transpose)
+        .collect()
+}
+
+/// Returns the free space (in bytes) on the SD card.
+///
+/// # Notes
+///
+/// The space is queried from the SDMC archive specifically (the SD card, as seen by `sdmc:/`),
+/// and is counted in whole clusters of the card's file system. The NAND and other archives aren't considered.
+///
+/// # Errors
+///
+/// This function will return an error if the FS service couldn't be reached or if no SD card is inserted.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// let download_size = 20 * 1024 * 1024;
+///
+/// if ctru::os::sdmc_free_space()? < download_size {
+///     println!("Not enough free space on the SD card");
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "FSUSER_GetSdmcArchiveResource")]
+pub fn sdmc_free_space() -> crate::Result<u64> {
+    let resource = sdmc_archive_resource()?;
+
+    Ok(u64::from(resource.freeClusters) * u64::from(resource.clusterSize))
+}
+
+/// Returns the total capacity (in bytes) of the SD card.
+///
+/// See [`sdmc_free_space()`] for more information.
+#[doc(alias = "FSUSER_GetSdmcArchiveResource")]
+pub fn sdmc_total_space() -> crate::Result<u64> {
+    let resource = sdmc_archive_resource()?;
+
+    Ok(u64::from(resource.totalClusters) * u64::from(resource.clusterSize))
+}
+
+/// Query the cluster counts of the SDMC archive on a temporary FS session.
+fn sdmc_archive_resource() -> crate::Result<ctru_sys::FS_ArchiveResource> {
+    let mut resource = ctru_sys::FS_ArchiveResource::default();
+
+    ResultCode(unsafe { ctru_sys::fsInit() })?;
+
+    let result = unsafe { ctru_sys::FSUSER_GetSdmcArchiveResource(&mut resource) };
+
+    unsafe { ctru_sys::fsExit() };
+
+    ResultCode(result)?;
+    Ok(resource)
+}
+
 /// Whether or not a headset is currently plugged into the device.
 pub fn is_headset_connected() -> bool {
     unsafe { ctru_sys::osIsHeadsetConnected() }
 }
+
+/// Returns `true` if the battery is currently charging.
+///
+/// # Notes
+///
+/// This value is the "charging" bit (bit 1) of the battery state byte in the shared configuration memory (offset `0x85`),
+/// which the system updates from the MCU. Reading it requires no service to be active, so it is cheap enough to be polled every frame.
+///
+/// The bit is cleared once the battery is full, even if the charger is still plugged in.
+/// Use [`ConfigMem::is_adapter_connected()`] to check whether the console is running on external power.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// // Save some power while running on battery.
+/// let target_fps = if ctru::os::is_charging() { 60 } else { 30 };
+/// # let _ = target_fps;
+/// ```
+pub fn is_charging() -> bool {
+    config_mem().is_charging()
+}
+
+/// Battery level (in percent) below which [`BatteryStatus::low_battery`] is set.
+const LOW_BATTERY_PERCENT: u8 = 10;
+/// Number of battery level samples averaged by [`battery_status()`].
+const BATTERY_WINDOW: usize = 8;
+
+/// Samples of the battery level used by [`battery_status()`] to smooth its value.
+static BATTERY_SMOOTHING: Mutex<Option<BatterySmoothing>> = Mutex::new(None);
+
+/// State of the battery, as returned by [`battery_status()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatteryStatus {
+    /// Smoothed battery level, from 0 to 100.
+    pub percent: u8,
+    /// Whether the battery is charging.
+    pub charging: bool,
+    /// Whether the battery is low (10% or less) and not charging.
+    pub low_battery: bool,
+}
+
+struct BatterySmoothing {
+    samples: [u8; BATTERY_WINDOW],
+    len: usize,
+    next: usize,
+    charging: bool,
+    reported: u8,
+}
+
+/// Returns the state of the battery, with a level stable enough to be shown in a battery widget.
+///
+/// # Notes
+///
+/// The level is read in percent from the MCU (via a temporary `mcu::HWC` session). If the application isn't allowed to access
+/// that service, the coarser level shown by the Home Menu (0 to 5, see [`ConfigMem::battery_level()`]) is used instead, in steps of 20%.
+///
+/// The raw level read from the MCU fluctuates by a few points from one read to the next, so the returned level is smoothed:
+/// - it's the average of the last 8 reads (made by previous calls to this function),
+/// - it never goes up while discharging, nor down while charging,
+/// - the samples are discarded whenever the charging state changes, so plugging or unplugging the charger is reflected right away.
+///
+/// Since the level changes slowly, calling this function once every second or so is enough.
+///
+/// [`BatteryStatus::low_battery`] is set when the smoothed level is at or below 10% while the battery isn't charging.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let battery = ctru::os::battery_status();
+///
+/// assert!(battery.percent <= 100);
+///
+/// if battery.low_battery {
+///     println!("Battery low, save your game!");
+/// }
+/// ```
+#[doc(alias = "MCUHWC_GetBatteryLevel")]
+pub fn battery_status() -> BatteryStatus {
+    let charging = is_charging();
+    let sample = mcu_battery_level().unwrap_or_else(|_| config_mem().battery_level() * 20);
+
+    let mut smoothing = BATTERY_SMOOTHING.lock().unwrap();
+
+    let percent = match smoothing.as_mut().filter(|s| s.charging == charging) {
+        Some(smoothing) => smoothing.push(sample),
+        // First read, or the charger was plugged in or out.
+        None => {
+            *smoothing = Some(BatterySmoothing {
+                samples: [sample; BATTERY_WINDOW],
+                len: 1,
+                next: 1,
+                charging,
+                reported: sample,
+            });
+
+            sample
+        }
+    };
+
+    BatteryStatus {
+        percent,
+        charging,
+        low_battery: !charging && percent <= LOW_BATTERY_PERCENT,
+    }
+}
+
+impl BatterySmoothing {
+    /// Add a sample to the window, returning the new smoothed level.
+    fn push(&mut self, sample: u8) -> u8 {
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % BATTERY_WINDOW;
+        self.len = (self.len + 1).min(BATTERY_WINDOW);
+
+        let sum: usize = self.samples[..self.len]
+            .iter()
+            .map(|&s| usize::from(s))
+            .sum();
+        let average = ((sum + self.len / 2) / self.len) as u8;
+
+        self.reported = if self.charging {
+            self.reported.max(average)
+        } else {
+            self.reported.min(average)
+        };
+
+        self.reported
+    }
+}
+
+/// Read the battery level (in percent) from the MCU.
+fn mcu_battery_level() -> crate::Result<u8> {
+    ResultCode(unsafe { ctru_sys::mcuHwcInit() })?;
+
+    let mut level = 0;
+    let result = unsafe { ctru_sys::MCUHWC_GetBatteryLevel(&mut level) };
+
+    unsafe { ctru_sys::mcuHwcExit() };
+
+    ResultCode(result)?;
+    Ok(level.min(100))
+}
+
+/// Address of the kernel configuration memory page.
+const KERNEL_CONFIG_MEM: usize = 0x1FF80000;
+/// Address of the shared configuration memory page.
+const SHARED_CONFIG_MEM: usize = 0x1FF81000;
+
+/// Read-only view of the configuration memory pages mapped by the kernel into every process.
+///
+/// The configuration memory is made of two pages: the kernel configuration page (at `0x1FF80000`)
+/// and the shared page (at `0x1FF81000`), which the system keeps up to date with the current hardware state.
+/// Reading these values doesn't require any service to be active, so they are cheap enough to be polled every frame.
+///
+/// Retrieve this struct with [`config_mem()`]. Offsets in the getters' documentation are relative to the start of their page.
+///
+/// # Notes
+///
+/// Some system values (like the system language) aren't stored in the configuration memory.
+/// Use the [`Cfgu`](crate::services::cfgu::Cfgu) service to read them.
+///
+/// See <https://www.3dbrew.org/wiki/Configuration_Memory> for more details on the layout.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// let config = ctru::os::config_mem();
+///
+/// assert!(config.wifi_strength() < 4);
+/// assert!(config.battery_level() <= 5);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ConfigMem(());
+
+/// Get a view of the configuration memory.
+pub fn config_mem() -> ConfigMem {
+    ConfigMem(())
+}
+
+impl ConfigMem {
+    /// Read a value from the configuration memory.
+    ///
+    /// This is the only point where the configuration memory is accessed.
+    fn read<T: Copy>(&self, address: usize) -> T {
+        // SAFETY: Both configuration pages are always mapped as readable in every process and all addresses passed
+        // to this function are correctly aligned. The reads are volatile since the system updates the values at any time.
+        unsafe { std::ptr::read_volatile(address as *const T) }
+    }
+
+    /// Kernel version (kernel page, offset `0x00`).
+    pub fn kernel_version(&self) -> Version {
+        Version(self.read(KERNEL_CONFIG_MEM))
+    }
+
+    /// FIRM version (kernel page, offset `0x60`).
+    pub fn firm_version(&self) -> Version {
+        Version(self.read(KERNEL_CONFIG_MEM + 0x60))
+    }
+
+    /// Size of the APPLICATION memory region in bytes (kernel page, offset `0x40`).
+    pub fn application_memory_size(&self) -> usize {
+        self.read::<u32>(KERNEL_CONFIG_MEM + 0x40) as usize
+    }
+
+    /// MAC address of the WiFi module (shared page, offset `0x60`).
+    pub fn wifi_mac_address(&self) -> [u8; 6] {
+        self.read(SHARED_CONFIG_MEM + 0x60)
+    }
+
+    /// WiFi signal strength as the number of bars, 0 to 3 (shared page, offset `0x66`).
+    ///
+    /// This is the same value returned by [`wifi_strength()`].
+    pub fn wifi_strength(&self) -> u8 {
+        self.read(SHARED_CONFIG_MEM + 0x66)
+    }
+
+    /// Raw network state (shared page, offset `0x67`).
+    pub fn network_state(&self) -> u8 {
+        self.read(SHARED_CONFIG_MEM + 0x67)
+    }
+
+    /// Position of the 3D slider, from 0.0 to 1.0 (shared page, offset `0x80`).
+    ///
+    /// This is the same value returned by [`current_3d_slider_state()`].
+    pub fn slider_3d_state(&self) -> f32 {
+        self.read(SHARED_CONFIG_MEM + 0x80)
+    }
+
+    /// Raw state of the 3D LED (shared page, offset `0x84`).
+    pub fn led_3d_state(&self) -> u8 {
+        self.read(SHARED_CONFIG_MEM + 0x84)
+    }
+
+    /// Whether the charger is plugged in (shared page, offset `0x85`, bit 0).
+    pub fn is_adapter_connected(&self) -> bool {
+        self.battery_state() & 0x1 != 0
+    }
+
+    /// Whether the battery is charging (shared page, offset `0x85`, bit 1).
+    pub fn is_charging(&self) -> bool {
+        self.battery_state() & 0x2 != 0
+    }
+
+    /// Battery level, from 0 to 5 (shared page, offset `0x85`, bits 2-4).
+    ///
+    /// This is the same value shown by the Home Menu's battery meter.
+    pub fn battery_level(&self) -> u8 {
+        (self.battery_state() >> 2) & 0x7
+    }
+
+    fn battery_state(&self) -> u8 {
+        self.read(SHARED_CONFIG_MEM + 0x85)
+    }
+
+    /// Title ID of the Home Menu (shared page, offset `0xA0`).
+    pub fn menu_title_id(&self) -> u64 {
+        self.read(SHARED_CONFIG_MEM + 0xA0)
+    }
+
+    /// Title ID of the currently active menu, e.g. the running system applet (shared page, offset `0xA8`).
+    pub fn active_menu_title_id(&self) -> u64 {
+        self.read(SHARED_CONFIG_MEM + 0xA8)
+    }
+
+    /// Whether a headset is plugged in (shared page, offset `0xC0`).
+    ///
+    /// This is the same value returned by [`is_headset_connected()`].
+    pub fn is_headset_connected(&self) -> bool {
+        self.read::<u8>(SHARED_CONFIG_MEM + 0xC0) != 0
+    }
+}
+
+/// Turn off the console.
+///
+/// # Notes
+///
+/// This function requires access to the `ptm:sysm` service, which is usually reserved to system applications.
+/// Most homebrew environments don't grant it, in which case an error is returned.
+///
+/// The shutdown is asynchronous: this function returns as soon as the request is accepted and the application
+/// is then notified to close like it would be when pressing the power button.
+///
+/// # Errors
+///
+/// This function will return an error if the application is not allowed to access the `ptm:sysm` service
+/// or if the shutdown request was refused.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// ctru::os::shutdown()?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "PTMSYSM_ShutdownAsync")]
+pub fn shutdown() -> crate::Result<()> {
+    with_ptm_sysm(|| unsafe { ctru_sys::PTMSYSM_ShutdownAsync(0) })
+}
+
+/// Reboot the console.
+///
+/// # Notes
+///
+/// This function has the same requirements and behaviour as [`shutdown()`].
+///
+/// # Errors
+///
+/// This function will return an error if the application is not allowed to access the `ptm:sysm` service
+/// or if the reboot request was refused.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// ctru::os::reboot()?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "PTMSYSM_RebootAsync")]
+pub fn reboot() -> crate::Result<()> {
+    with_ptm_sysm(|| unsafe { ctru_sys::PTMSYSM_RebootAsync(0) })
+}
+
+/// Run a request on a temporary `ptm:sysm` session.
+fn with_ptm_sysm(request: impl FnOnce() -> ctru_sys::Result) -> crate::Result<()> {
+    ResultCode(unsafe { ctru_sys::ptmSysmInit() })?;
+
+    let result = request();
+
+    unsafe { ctru_sys::ptmSysmExit() };
+
+    ResultCode(result)?;
+    Ok(())
+}
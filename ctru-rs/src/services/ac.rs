@@ -0,0 +1,159 @@
+//! Automatic Connection service.
+//!
+//! This service manages the console's connection to the Internet, using the WiFi slots stored in the System Settings.
+//! Applications which need the network should make sure a connection is up before using the [`Soc`](crate::services::soc::Soc) service.
+//!
+//! See also <https://www.3dbrew.org/wiki/NWM_Services#AC>
+#![doc(alias = "wifi")]
+#![doc(alias = "network")]
+
+use std::time::Duration;
+
+use crate::error::{Error, ResultCode};
+
+/// Value of `ACU_GetStatus` when the console is connected to the Internet.
+const STATUS_CONNECTED: u32 = 3;
+
+/// Handle to the AC service.
+pub struct Ac(());
+
+impl Ac {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// Since this service requires no special or elevated permissions, errors are rare in practice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ac::Ac;
+    ///
+    /// let ac = Ac::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "acInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::acInit() })?;
+
+        Ok(Self(()))
+    }
+
+    /// Returns `true` if the console is connected to the Internet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ac::Ac;
+    /// let ac = Ac::new()?;
+    ///
+    /// if !ac.is_connected()? {
+    ///     println!("Not connected to the Internet");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ACU_GetStatus")]
+    pub fn is_connected(&self) -> crate::Result<bool> {
+        let mut status = 0;
+
+        ResultCode(unsafe { ctru_sys::ACU_GetStatus(&mut status) })?;
+
+        Ok(status == STATUS_CONNECTED)
+    }
+
+    /// Connect to the Internet using the WiFi slots stored in the System Settings, blocking until the connection is established
+    /// or `timeout` is reached.
+    ///
+    /// If the console is already connected, this function returns immediately.
+    ///
+    /// # Notes
+    ///
+    /// No dialog is shown to the user: the system silently tries the stored slots, which usually takes a few seconds.
+    /// If the timeout is reached the attempt isn't cancelled, so the connection may still be established afterwards.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the timeout was reached, or if no stored slot could be connected to
+    /// (e.g. none is configured, or the access points are out of range). Connecting also fails while the WiFi is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::time::Duration;
+    ///
+    /// use ctru::services::ac::Ac;
+    /// use ctru::services::soc::Soc;
+    ///
+    /// let mut ac = Ac::new()?;
+    ///
+    /// ac.connect(Duration::from_secs(30))?;
+    ///
+    /// // The network is now up and ready to be used.
+    /// let soc = Soc::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ACU_ConnectAsync")]
+    #[doc(alias = "ACU_CreateDefaultConfig")]
+    pub fn connect(&mut self, timeout: Duration) -> crate::Result<()> {
+        if self.is_connected()? {
+            return Ok(());
+        }
+
+        let mut config: ctru_sys::acuConfig = unsafe { std::mem::zeroed() };
+        ResultCode(unsafe { ctru_sys::ACU_CreateDefaultConfig(&mut config) })?;
+
+        let mut event: ctru_sys::Handle = 0;
+        ResultCode(unsafe { ctru_sys::svcCreateEvent(&mut event, ctru_sys::RESET_ONESHOT) })?;
+
+        let result = unsafe {
+            let mut result = ctru_sys::ACU_ConnectAsync(&config, event);
+
+            // The event is signaled once the attempt ends, whether it succeeded or not.
+            if ctru_sys::R_SUCCEEDED(result) {
+                result = ctru_sys::svcWaitSynchronization(
+                    event,
+                    timeout.as_nanos().try_into().unwrap_or(i64::MAX),
+                );
+            }
+
+            let _ = ctru_sys::svcCloseHandle(event);
+
+            result
+        };
+
+        ResultCode(result)?;
+
+        if self.is_connected()? {
+            Ok(())
+        } else {
+            Err(Error::Other(String::from(
+                "the connection attempt ended without connecting to any network",
+            )))
+        }
+    }
+}
+
+impl Drop for Ac {
+    #[doc(alias = "acExit")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::acExit() };
+    }
+}
@@ -8,10 +8,128 @@
 #![doc(alias = "app")]
 #![doc(alias = "manager")]
 
-use crate::error::ResultCode;
+use crate::error::{Error, ResultCode};
+use crate::services::cfgu::Language;
 use crate::services::fs::FsMediaType;
 use std::marker::PhantomData;
 
+/// Size (in bytes) of an SMDH file.
+const SMDH_SIZE: usize = 0x36C0;
+/// Offset of the application titles in an SMDH file.
+const SMDH_TITLES_OFFSET: usize = 0x8;
+/// Size (in bytes) of a single application title in an SMDH file.
+const SMDH_TITLE_SIZE: usize = 0x200;
+/// Number of application titles in an SMDH file.
+const SMDH_TITLE_COUNT: usize = 16;
+/// Offset of the large icon in an SMDH file.
+const SMDH_LARGE_ICON_OFFSET: usize = 0x24C0;
+
+/// Width and height (in pixels) of a title's icon.
+pub const ICON_SIZE: usize = 48;
+
+/// Names of a title in a specific language, as shown in the Home Menu.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SmdhTitle {
+    /// Short name of the title.
+    pub short_description: String,
+    /// Long name of the title.
+    pub long_description: String,
+    /// Name of the title's publisher.
+    pub publisher: String,
+}
+
+/// Metadata of a title, as shown in the Home Menu.
+///
+/// The SMDH ("System Menu Data Header") holds the title's names in all supported languages and its icon.
+/// Retrieve it with [`Am::get_title_smdh()`], or parse it from raw data (e.g. the end of a `.3dsx` file) with [`Smdh::from_bytes()`].
+///
+/// See <https://www.3dbrew.org/wiki/SMDH> for more details on the format.
+#[doc(alias = "SMDH")]
+#[derive(Clone, Debug)]
+pub struct Smdh {
+    titles: Vec<SmdhTitle>,
+    icon: Vec<u16>,
+}
+
+impl Smdh {
+    /// Parse an SMDH file.
+    ///
+    /// Returns `None` if `data` is too short or doesn't start with the `SMDH` magic value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::services::am::Smdh;
+    ///
+    /// assert!(Smdh::from_bytes(b"not an SMDH").is_none());
+    /// ```
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < SMDH_SIZE || !data.starts_with(b"SMDH") {
+            return None;
+        }
+
+        let titles = data[SMDH_TITLES_OFFSET..]
+            .chunks_exact(SMDH_TITLE_SIZE)
+            .take(SMDH_TITLE_COUNT)
+            .map(|title| SmdhTitle {
+                short_description: utf16_field(&title[..0x80]),
+                long_description: utf16_field(&title[0x80..0x180]),
+                publisher: utf16_field(&title[0x180..]),
+            })
+            .collect();
+
+        // The icon is made of 8x8 tiles, with the pixels of each tile in Morton (Z-order) order.
+        let tiled = &data[SMDH_LARGE_ICON_OFFSET..SMDH_SIZE];
+        let mut icon = vec![0; ICON_SIZE * ICON_SIZE];
+
+        for (i, pixel) in tiled.chunks_exact(2).enumerate() {
+            let tile = i / 64;
+            let tile_x = (tile % (ICON_SIZE / 8)) * 8;
+            let tile_y = (tile / (ICON_SIZE / 8)) * 8;
+
+            let morton = i % 64;
+            let x = (morton & 1) | ((morton >> 1) & 2) | ((morton >> 2) & 4);
+            let y = ((morton >> 1) & 1) | ((morton >> 2) & 2) | ((morton >> 3) & 4);
+
+            icon[(tile_y + y) * ICON_SIZE + tile_x + x] = u16::from_le_bytes([pixel[0], pixel[1]]);
+        }
+
+        Some(Self { titles, icon })
+    }
+
+    /// Returns the names of the title in the specified language.
+    ///
+    /// # Notes
+    ///
+    /// Titles don't always provide names for every language. Unused entries are usually empty or filled with the English names.
+    pub fn title(&self, language: Language) -> &SmdhTitle {
+        &self.titles[language as usize]
+    }
+
+    /// Returns the names of the title in all 16 language slots of the SMDH, indexed by [`Language`].
+    pub fn titles(&self) -> &[SmdhTitle] {
+        &self.titles
+    }
+
+    /// Returns the 48x48 icon of the title, as RGB565 pixels in row-major order (starting from the top-left corner).
+    pub fn icon(&self) -> &[u16] {
+        &self.icon
+    }
+}
+
+/// Decode a null-terminated UTF-16 string.
+fn utf16_field(data: &[u8]) -> String {
+    let units = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0);
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
 /// General information about a specific title entry.
 #[doc(alias = "AM_TitleEntry")]
 pub struct Title<'a> {
@@ -173,6 +291,91 @@ impl Am {
             })
             .collect())
     }
+
+    /// Returns the metadata (names and icon) of an installed title.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the title doesn't exist or doesn't have an icon (e.g. some system titles),
+    /// or if the icon file isn't a valid SMDH.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::am::Am;
+    /// use ctru::services::cfgu::Language;
+    /// use ctru::services::fs::FsMediaType;
+    /// let app_manager = Am::new()?;
+    ///
+    /// for title in app_manager.title_list(FsMediaType::Sd)? {
+    ///     if let Ok(smdh) = app_manager.get_title_smdh(title.id(), FsMediaType::Sd) {
+    ///         println!("{}", smdh.title(Language::English).short_description);
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "FSUSER_OpenFileDirectly")]
+    pub fn get_title_smdh(&self, title_id: u64, mediatype: FsMediaType) -> crate::Result<Smdh> {
+        // The icon is stored in the "icon" file of the title's ExeFS.
+        let archive_path: [u32; 4] = [
+            title_id as u32,
+            (title_id >> 32) as u32,
+            mediatype as u32,
+            0,
+        ];
+        let file_path: [u32; 5] = [0, 0, 2, u32::from_le_bytes(*b"icon"), 0];
+
+        let mut buf = vec![0u8; SMDH_SIZE];
+        let mut bytes_read = 0;
+
+        unsafe {
+            // The FS service is reference counted, so this is fine even if it's already in use.
+            ResultCode(ctru_sys::fsInit())?;
+
+            let mut handle = 0;
+            let mut result = ctru_sys::FSUSER_OpenFileDirectly(
+                &mut handle,
+                ctru_sys::ARCHIVE_SAVEDATA_AND_CONTENT,
+                ctru_sys::FS_Path {
+                    type_: ctru_sys::PATH_BINARY,
+                    size: std::mem::size_of_val(&archive_path) as u32,
+                    data: archive_path.as_ptr().cast(),
+                },
+                ctru_sys::FS_Path {
+                    type_: ctru_sys::PATH_BINARY,
+                    size: std::mem::size_of_val(&file_path) as u32,
+                    data: file_path.as_ptr().cast(),
+                },
+                ctru_sys::FS_OPEN_READ,
+                0,
+            );
+
+            if ctru_sys::R_SUCCEEDED(result) {
+                result = ctru_sys::FSFILE_Read(
+                    handle,
+                    &mut bytes_read,
+                    0,
+                    buf.as_mut_ptr().cast(),
+                    SMDH_SIZE as u32,
+                );
+
+                ctru_sys::FSFILE_Close(handle);
+            }
+
+            ctru_sys::fsExit();
+
+            ResultCode(result)?;
+        }
+
+        Smdh::from_bytes(&buf[..bytes_read as usize])
+            .ok_or_else(|| Error::Other(String::from("the title's SMDH data is invalid")))
+    }
 }
 
 impl Drop for Am {
@@ -6,7 +6,75 @@
 //! It also handles running applets, small programs made available by the OS to streamline specific functionality.
 //! Those are implemented in the [`applets`](crate::applets) module.
 
-use crate::error::ResultCode;
+use std::ffi::{CStr, CString};
+use std::fmt;
+
+use crate::applets::AppletId;
+use crate::error::{Error, ResultCode};
+use crate::services::gfx::Gfx;
+
+/// Size of the argument buffer expected by the homebrew loader.
+const HBLDR_ARGV_SIZE: usize = 0x400;
+
+/// Unique identifier of a title (application, applet or system module).
+///
+/// Title IDs are usually displayed as 16 hexadecimal digits, which is how this type is formatted by [`Display`](fmt::Display).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TitleId(pub u64);
+
+impl TitleId {
+    /// Returns the upper 32 bits of the title ID, which identify the type of title (e.g. `0x00040000` for applications).
+    pub fn high(&self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+
+    /// Returns the lower 32 bits of the title ID, which identify the title within its type.
+    pub fn low(&self) -> u32 {
+        self.0 as u32
+    }
+}
+
+impl fmt::Display for TitleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016X}", self.0)
+    }
+}
+
+impl From<u64> for TitleId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+/// Position of an applet in the system, which determines the resources available to it.
+#[doc(alias = "APT_AppletPos")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AppletPosition {
+    /// Application.
+    Application,
+    /// Library applet launched by an application.
+    LibraryApplet,
+    /// System applet (e.g. the Home Menu).
+    System,
+    /// Library applet launched by a system applet.
+    SystemLibraryApplet,
+    /// Resident applet.
+    Resident,
+}
+
+/// Information about a registered applet or application.
+///
+/// See [`Apt::running_applet()`] to learn how to retrieve this.
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct AppletInfo {
+    /// APT identifier of the applet.
+    pub applet_id: AppletId,
+    /// Title ID of the program.
+    pub program_id: TitleId,
+    /// Position of the applet, if any.
+    pub position: Option<AppletPosition>,
+}
 
 /// Handle to the Applet service.
 pub struct Apt(());
@@ -82,6 +150,361 @@ impl Apt {
             Ok(())
         }
     }
+
+    /// Returns the amount of time (in percentage) lent to the application thread spawned on the syscore (core #1).
+    ///
+    /// A value of `0` means no time has been lent, making the syscore unavailable to the application.
+    #[doc(alias = "APT_GetAppCpuTimeLimit")]
+    pub fn app_cpu_time_limit(&self) -> crate::Result<u32> {
+        let mut percent = 0;
+
+        ResultCode(unsafe { ctru_sys::APT_GetAppCpuTimeLimit(&mut percent) })?;
+
+        Ok(percent)
+    }
+
+    /// Allow or prevent the user from returning to the Home Menu with the HOME button.
+    ///
+    /// # Notes
+    ///
+    /// While the HOME button is disabled, pressing it only shows a "cannot return to the Home Menu" icon at the bottom of the screen.
+    /// This is useful to protect short critical operations (e.g. writing save data), but the button should be re-enabled as soon
+    /// as possible, since the user is otherwise trapped in the application. The POWER button (and sleep mode) is not affected.
+    ///
+    /// The setting is reset when the application exits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// let mut apt = Apt::new()?;
+    ///
+    /// apt.set_home_allowed(false);
+    ///
+    /// // Write the save data...
+    ///
+    /// apt.set_home_allowed(true);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "aptSetHomeAllowed")]
+    pub fn set_home_allowed(&mut self, allowed: bool) {
+        unsafe { ctru_sys::aptSetHomeAllowed(allowed) }
+    }
+
+    /// Returns `true` if the user is allowed to return to the Home Menu with the HOME button.
+    ///
+    /// See [`Apt::set_home_allowed()`].
+    #[doc(alias = "aptIsHomeAllowed")]
+    pub fn is_home_allowed(&self) -> bool {
+        unsafe { ctru_sys::aptIsHomeAllowed() }
+    }
+
+    /// Suspend the application and jump to the Home Menu, returning once the user resumes the application.
+    ///
+    /// Returns `false` if the user closed the application from the Home Menu, in which case it should exit as soon as possible
+    /// (just like when [`Apt::main_loop()`] returns `false`).
+    ///
+    /// # Notes
+    ///
+    /// The Home Menu draws on the screens while the application is suspended, so the framebuffers currently shown by `gfx`
+    /// are presented again when the application resumes. Nothing is drawn into them, so the last presented frame is shown until the next one.
+    ///
+    /// If the HOME button is [disabled](Apt::set_home_allowed), this function returns `true` immediately without leaving the application.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// use ctru::services::gfx::Gfx;
+    /// let mut apt = Apt::new()?;
+    /// let gfx = Gfx::new()?;
+    ///
+    /// // e.g. when the user selects "Home Menu" in the pause screen.
+    /// if !apt.suspend_to_home(&gfx) {
+    ///     // The application was closed from the Home Menu.
+    ///     return Ok(());
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "aptJumpToHomeMenu")]
+    pub fn suspend_to_home(&mut self, gfx: &Gfx) -> bool {
+        if !self.is_home_allowed() {
+            return true;
+        }
+
+        // libctru takes care of the APT transition and of giving the GPU rights back and forth.
+        unsafe { ctru_sys::aptJumpToHomeMenu() };
+
+        unsafe {
+            ctru_sys::gfxConfigScreen(ctru_sys::GFX_TOP, true);
+            ctru_sys::gfxConfigScreen(ctru_sys::GFX_BOTTOM, true);
+        }
+        gfx.wait_for_vblank();
+
+        // Handle a close request received while in the Home Menu.
+        self.main_loop()
+    }
+
+    /// Returns the title ID of the running application.
+    ///
+    /// # Notes
+    ///
+    /// Homebrew applications launched via the Homebrew Launcher take the title ID of the title used as a launch point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// let apt = Apt::new()?;
+    ///
+    /// println!("Running title: {}", apt.program_id()?);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "APT_GetProgramID")]
+    pub fn program_id(&self) -> crate::Result<TitleId> {
+        let mut program_id = 0;
+
+        ResultCode(unsafe { ctru_sys::APT_GetProgramID(&mut program_id) })?;
+
+        Ok(TitleId(program_id))
+    }
+
+    /// Returns information about the applet the application runs as.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// let apt = Apt::new()?;
+    ///
+    /// let info = apt.running_applet()?;
+    ///
+    /// println!("Running title: {} ({:?})", info.program_id, info.position);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "APT_GetAppletInfo", alias = "envGetAptAppId")]
+    pub fn running_applet(&self) -> crate::Result<AppletInfo> {
+        let applet_id = unsafe { ctru_sys::envGetAptAppId() };
+
+        let mut program_id = 0;
+        let mut media_type = 0;
+        let mut registered = false;
+        let mut load_state = false;
+        let mut attributes = 0;
+
+        ResultCode(unsafe {
+            ctru_sys::APT_GetAppletInfo(
+                applet_id,
+                &mut program_id,
+                &mut media_type,
+                &mut registered,
+                &mut load_state,
+                &mut attributes,
+            )
+        })?;
+
+        // The position is stored in the lowest 3 bits of the attributes.
+        let position = match attributes & 0x7 {
+            0 => Some(AppletPosition::Application),
+            1 => Some(AppletPosition::LibraryApplet),
+            2 => Some(AppletPosition::System),
+            3 => Some(AppletPosition::SystemLibraryApplet),
+            4 => Some(AppletPosition::Resident),
+            _ => None,
+        };
+
+        Ok(AppletInfo {
+            applet_id: AppletId(applet_id),
+            program_id: TitleId(program_id),
+            position,
+        })
+    }
+
+    /// Returns the IDs of the processor cores the application can spawn threads on.
+    ///
+    /// # Notes
+    ///
+    /// The appcore (core #0) is always available. The syscore (core #1) is available only if some time has been lent to the application
+    /// via [`Apt::set_app_cpu_time_limit()`], and core #2 is available only on New 3DS models.
+    /// Core #3 (New 3DS only) is reserved to the OS and never listed.
+    ///
+    /// See [`os::current_processor()`](crate::os::current_processor) for more information about the core numbering.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// let apt = Apt::new()?;
+    ///
+    /// let cores = apt.available_processors()?;
+    ///
+    /// assert!(cores.contains(&0));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "APT_CheckNew3DS")]
+    pub fn available_processors(&self) -> crate::Result<Vec<usize>> {
+        let mut cores = vec![0];
+
+        if self.app_cpu_time_limit()? > 0 {
+            cores.push(1);
+        }
+
+        let mut is_new_3ds = false;
+        ResultCode(unsafe { ctru_sys::APT_CheckNew3DS(&mut is_new_3ds) })?;
+
+        if is_new_3ds {
+            cores.push(2);
+        }
+
+        Ok(cores)
+    }
+
+    /// Set the `.3dsx` homebrew application to launch once this application exits.
+    ///
+    /// `path` must point to a file on the SD card (e.g. `"sdmc:/3ds/app.3dsx"` or `"/3ds/app.3dsx"`).
+    /// The path is also passed to the target application as its first argument.
+    ///
+    /// After calling this function, the application should exit normally (by returning from `main`).
+    /// The homebrew loader will then boot the target application in its place.
+    ///
+    /// # Notes
+    ///
+    /// This function uses the protocol of the homebrew loader (the `hb:ldr` service provided by the custom firmware),
+    /// so it only works for applications started from the Homebrew Launcher.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the application isn't running in a homebrew environment
+    /// (e.g. when installed as a CIA) or if the homebrew loader is not available.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `path` contains NUL bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// let mut apt = Apt::new()?;
+    ///
+    /// apt.chainload("sdmc:/3ds/other-app.3dsx")?;
+    ///
+    /// // Exit the application to boot the target.
+    /// return Ok(());
+    /// #
+    /// # }
+    /// ```
+    #[doc(alias = "aptSetChainloaderToSelf")]
+    pub fn chainload(&mut self, path: &str) -> crate::Result<()> {
+        if !unsafe { ctru_sys::envIsHomebrew() } {
+            return Err(Error::Unsupported(String::from(
+                "chainloading is only available to homebrew applications",
+            )));
+        }
+
+        // The loader expects paths relative to the root of the SD card.
+        let target = path.strip_prefix("sdmc:").unwrap_or(path);
+        let target = CString::new(target).expect("Failed to convert the path into a CString");
+
+        // The argument buffer holds the number of arguments, followed by the NUL-terminated arguments.
+        let mut argv = [0u8; HBLDR_ARGV_SIZE];
+        let argument = path.as_bytes();
+        let wanted = 4 + argument.len() + 1;
+        if wanted > HBLDR_ARGV_SIZE {
+            return Err(Error::BufferTooShort {
+                provided: HBLDR_ARGV_SIZE,
+                wanted,
+            });
+        }
+        argv[..4].copy_from_slice(&1u32.to_le_bytes());
+        argv[4..4 + argument.len()].copy_from_slice(argument);
+
+        let mut handle: ctru_sys::Handle = 0;
+        ResultCode(unsafe {
+            ctru_sys::svcConnectToPort(
+                &mut handle,
+                CStr::from_bytes_with_nul(b"hb:ldr\0").unwrap().as_ptr(),
+            )
+        })?;
+
+        let result = unsafe {
+            hbldr_request(
+                handle,
+                2,
+                target.as_bytes_with_nul().as_ptr(),
+                target.as_bytes_with_nul().len(),
+                0,
+            )
+            .and_then(|_| hbldr_request(handle, 3, argv.as_ptr(), argv.len(), 1))
+        };
+
+        unsafe {
+            let _ = ctru_sys::svcCloseHandle(handle);
+        }
+
+        result?;
+
+        unsafe { ctru_sys::aptSetChainloaderToSelf() };
+
+        Ok(())
+    }
+}
+
+/// Send a request carrying a single static buffer to the homebrew loader.
+///
+/// # Safety
+///
+/// `handle` must be a valid session to the `hb:ldr` port and `buffer` must be valid for reads of `size` bytes.
+unsafe fn hbldr_request(
+    handle: ctru_sys::Handle,
+    command: u16,
+    buffer: *const u8,
+    size: usize,
+    buffer_id: u8,
+) -> crate::Result<()> {
+    let command_buffer = ctru_sys::getThreadCommandBuffer();
+
+    *command_buffer = ctru_sys::IPC_MakeHeader(command, 0, 2);
+    *command_buffer.add(1) = ctru_sys::IPC_Desc_StaticBuffer(size, buffer_id.into());
+    *command_buffer.add(2) = buffer as u32;
+
+    ResultCode(ctru_sys::svcSendSyncRequest(handle))?;
+    ResultCode(*command_buffer.add(1) as ctru_sys::Result)?;
+
+    Ok(())
 }
 
 impl Drop for Apt {
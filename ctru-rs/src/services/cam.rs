@@ -5,10 +5,20 @@
 #![doc(alias = "camera")]
 
 use crate::error::{Error, ResultCode};
+use crate::linear::LinearBox;
 use crate::services::gspgpu::FramebufferFormat;
+use crate::services::y2r::{self, Y2r};
 use ctru_sys::Handle;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 
+/// Raw selection of the currently active camera(s). Only one selection can be active at a time.
+static ACTIVE_CAMERA: AtomicU32 = AtomicU32::new(ctru_sys::SELECT_NONE);
+
+/// Settings applied to each single camera, indexed by the camera's bit in the raw selection. See [`Configuration`].
+static CONFIGURATIONS: Mutex<[Configuration; 3]> = Mutex::new([Configuration::new(); 3]);
+
 /// Handle to the Camera service.
 #[non_exhaustive]
 pub struct Cam {
@@ -253,6 +263,56 @@ impl TrimmingParams {
     }
 }
 
+/// Settings applied to a single camera through [`Camera`]'s methods.
+///
+/// The camera service doesn't offer a way to read most settings back from the hardware, so they are cached in [`CONFIGURATIONS`]
+/// (and reset by [`Cam::new()`]). Settings applied to a selection of cameras are cached for each camera in it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Configuration {
+    view_size: Option<ViewSize>,
+    output_format: Option<OutputFormat>,
+}
+
+impl Configuration {
+    const fn new() -> Self {
+        Self {
+            view_size: None,
+            output_format: None,
+        }
+    }
+}
+
+/// Update the settings cached for every camera in the raw selection.
+fn update_configuration(camera: u32, update: impl Fn(&mut Configuration)) {
+    let mut configurations = CONFIGURATIONS.lock().unwrap();
+
+    for (bit, configuration) in configurations.iter_mut().enumerate() {
+        if camera & (1 << bit) != 0 {
+            update(configuration);
+        }
+    }
+}
+
+/// Returns the setting cached for the cameras in the raw selection, if it's the same for all of them.
+fn cached_setting<T: PartialEq>(
+    camera: u32,
+    setting: impl Fn(&Configuration) -> Option<T>,
+) -> Option<T> {
+    let configurations = CONFIGURATIONS.lock().unwrap();
+
+    let mut values = configurations
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| camera & (1 << bit) != 0)
+        .map(|(_, configuration)| setting(configuration));
+
+    let first = values.next()??;
+
+    values
+        .all(|value| value.as_ref() == Some(&first))
+        .then_some(first)
+}
+
 /// Data used by the camera to calibrate image quality for a single camera.
 #[doc(alias = "CAMU_ImageQualityCalibrationData")]
 #[derive(Default, Clone, Copy, Debug)]
@@ -329,6 +389,11 @@ impl Camera for BothOutwardCam {
 }
 
 /// Generic functionality common to all cameras.
+///
+/// # Notes
+///
+/// Some settings can be read back directly from the hardware (e.g. [`Camera::trimming_params()`] or [`Camera::is_auto_exposure_enabled()`]),
+/// while others are cached when they are set, and can be retrieved until the service is initialized again (e.g. [`Camera::view_size()`] and [`Camera::output_format()`]).
 // TODO: Change "set true/set parameters" scheme (classic of C code) into a single "set parameter" scheme using enums. This is valid for stuff such as [`TrimmingParams`]
 pub trait Camera {
     /// Returns the raw value of the selected camera.
@@ -632,8 +697,11 @@ pub trait Camera {
                 crop_1.1,
                 ctru_sys::CONTEXT_A,
             ))?;
-            Ok(())
         }
+
+        update_configuration(self.camera_as_raw(), |c| c.view_size = None);
+
+        Ok(())
     }
 
     /// Set the view size of the camera.
@@ -645,8 +713,42 @@ pub trait Camera {
                 size.into(),
                 ctru_sys::CONTEXT_A,
             ))?;
-            Ok(())
         }
+
+        update_configuration(self.camera_as_raw(), |c| c.view_size = Some(size));
+
+        Ok(())
+    }
+
+    /// Returns the view size last set via [`Camera::set_view_size()`].
+    ///
+    /// # Notes
+    ///
+    /// This value is cached when it is set, and not read from the hardware. The cache is reset by [`Cam::new()`].
+    /// Returns `None` if the view size was never set (in which case the system default is in use)
+    /// or if a custom size was set via [`Camera::set_detail_size()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cam::{Cam, Camera, ViewSize};
+    /// let mut cam = Cam::new()?;
+    ///
+    /// assert_eq!(cam.inner_cam.view_size(), None);
+    ///
+    /// cam.inner_cam.set_view_size(ViewSize::TopLCD)?;
+    ///
+    /// assert_eq!(cam.inner_cam.view_size(), Some(ViewSize::TopLCD));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn view_size(&self) -> Option<ViewSize> {
+        cached_setting(self.camera_as_raw(), |c| c.view_size)
     }
 
     /// Set the frame rate of the camera.
@@ -724,8 +826,21 @@ pub trait Camera {
                 format.into(),
                 ctru_sys::CONTEXT_A,
             ))?;
-            Ok(())
         }
+
+        update_configuration(self.camera_as_raw(), |c| c.output_format = Some(format));
+
+        Ok(())
+    }
+
+    /// Returns the output format last set via [`Camera::set_output_format()`].
+    ///
+    /// # Notes
+    ///
+    /// This value is cached when it is set, and not read from the hardware. The cache is reset by [`Cam::new()`].
+    /// Returns `None` if the output format was never set, in which case the system default ([`OutputFormat::Yuv422`]) is in use.
+    fn output_format(&self) -> Option<OutputFormat> {
+        cached_setting(self.camera_as_raw(), |c| c.output_format)
     }
 
     /// Set the region in which auto exposure should be based on.
@@ -819,6 +934,77 @@ pub trait Camera {
         }
     }
 
+    /// Power on the camera and keep it on until [`Camera::deactivate()`] is called (or the [`Cam`] handle is dropped).
+    ///
+    /// # Notes
+    ///
+    /// [`Camera::take_picture()`] powers on the camera by itself when it isn't active, and powers it off after the capture.
+    /// Powering on the sensor takes a while and its first frames are often too dark, so activating the camera beforehand
+    /// makes successive captures (e.g. a live preview) much faster and more consistent.
+    ///
+    /// Only one camera (or [pair of cameras](Cam::both_outer_cams)) can be active at a time: activating a camera deactivates the previous one.
+    /// An active camera draws power even while no picture is being taken, so deactivate it when it isn't needed (e.g. when leaving the viewfinder)
+    /// to save battery.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # use std::time::Duration;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cam::{Cam, Camera, OutputFormat, ViewSize};
+    /// let mut cam = Cam::new()?;
+    ///
+    /// let outward = &mut cam.outer_right_cam;
+    /// outward.set_view_size(ViewSize::TopLCD)?;
+    /// outward.set_output_format(OutputFormat::Rgb565)?;
+    ///
+    /// let mut buffer = vec![0; 400 * 240 * 2];
+    ///
+    /// outward.activate()?;
+    ///
+    /// // Viewfinder loop.
+    /// for _ in 0..10 {
+    ///     outward.take_picture(&mut buffer, 400, 240, Duration::from_millis(300))?;
+    /// }
+    ///
+    /// outward.deactivate()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "CAMU_Activate")]
+    fn activate(&mut self) -> crate::Result<()> {
+        ResultCode(unsafe { ctru_sys::CAMU_Activate(self.camera_as_raw()) })?;
+
+        ACTIVE_CAMERA.store(self.camera_as_raw(), Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Power off the camera, if it was [activated](Camera::activate).
+    ///
+    /// This does nothing if another camera is active.
+    #[doc(alias = "CAMU_Activate")]
+    fn deactivate(&mut self) -> crate::Result<()> {
+        if !self.is_active() {
+            return Ok(());
+        }
+
+        ResultCode(unsafe { ctru_sys::CAMU_Activate(ctru_sys::SELECT_NONE) })?;
+
+        ACTIVE_CAMERA.store(ctru_sys::SELECT_NONE, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Returns `true` if the camera has been [activated](Camera::activate).
+    fn is_active(&self) -> bool {
+        ACTIVE_CAMERA.load(Ordering::Relaxed) == self.camera_as_raw()
+    }
+
     /// Set the camera as the current sleep camera.
     // TODO: Explain sleep camera
     #[doc(alias = "CAMU_SetSleepCamera")]
@@ -831,6 +1017,12 @@ pub trait Camera {
 
     /// Request the camera to take a picture and write it in a buffer.
     ///
+    /// # Notes
+    ///
+    /// The image is transferred to `buffer` via DMA, which requires physically contiguous memory.
+    /// Regular heap allocations (like the [`Vec`] in the example below) are not guaranteed to be contiguous,
+    /// which may result in garbled images. Use [`Camera::take_picture_linear()`] to make sure the buffer is allocated in LINEAR memory.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the camera is busy or if the timeout duration gets reached.
@@ -906,8 +1098,13 @@ pub trait Camera {
             });
         }
 
+        // Only power the camera for this capture if it wasn't already active.
+        let was_active = self.is_active();
+        if !was_active {
+            self.activate()?;
+        }
+
         unsafe {
-            ResultCode(ctru_sys::CAMU_Activate(self.camera_as_raw()))?;
             ResultCode(ctru_sys::CAMU_ClearBuffer(self.port_as_raw()))?;
             ResultCode(ctru_sys::CAMU_StartCapture(self.port_as_raw()))?;
         };
@@ -924,7 +1121,7 @@ pub trait Camera {
             Ok::<Handle, i32>(completion_handle)
         }?;
 
-        unsafe {
+        let wait_result = unsafe {
             // Panicking without closing an SVC handle causes an ARM exception, we have to handle it carefully (TODO: SVC module)
             let wait_result = ResultCode(ctru_sys::svcWaitSynchronization(
                 receive_event,
@@ -934,13 +1131,134 @@ pub trait Camera {
             // We close everything first, then we check for possible errors
             let _ = ctru_sys::svcCloseHandle(receive_event); // We wouldn't return the error even if there was one, so no use of ResultCode is needed
             ResultCode(ctru_sys::CAMU_StopCapture(self.port_as_raw()))?;
-            ResultCode(ctru_sys::CAMU_Activate(ctru_sys::SELECT_NONE))?;
 
-            wait_result?;
+            wait_result
         };
 
+        if !was_active {
+            self.deactivate()?;
+        }
+
+        wait_result?;
+
         Ok(())
     }
+
+    /// Request the camera to take a picture and write it in a buffer allocated in LINEAR memory.
+    ///
+    /// This works exactly like [`Camera::take_picture()`], but the type of `buffer` guarantees that the image
+    /// is transferred into LINEAR (physically contiguous) memory, as required by the camera's DMA transfers.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the camera is busy or if the timeout duration gets reached.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Width of the desired image
+    /// * `height` - Height of the desired image
+    /// * `timeout` - Duration to wait for the image
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # use std::time::Duration;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::linear::LinearBox;
+    /// use ctru::services::cam::{Cam, Camera, OutputFormat, ViewSize};
+    /// let mut cam = Cam::new()?;
+    ///
+    /// let inward = &mut cam.inner_cam;
+    ///
+    /// inward.set_view_size(ViewSize::TopLCD)?;
+    /// inward.set_output_format(OutputFormat::Rgb565)?;
+    ///
+    /// // Size of the top screen buffer at 2 bytes per pixel (RGB565), in LINEAR memory.
+    /// let mut buffer = LinearBox::new_slice(0u8, 400 * 240 * 2);
+    ///
+    /// inward.take_picture_linear(&mut buffer, 400, 240, Duration::from_secs(3))?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn take_picture_linear(
+        &mut self,
+        buffer: &mut LinearBox<[u8]>,
+        width: u16,
+        height: u16,
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        self.take_picture(buffer, width, height, timeout)
+    }
+
+    /// Request the camera to take a picture and convert it to an RGB image with the [`Y2r`] service, ready to be shown on screen.
+    ///
+    /// The picture is taken in YUV422 (switching the camera's [output format](Camera::set_output_format()) if needed)
+    /// into a temporary buffer in LINEAR memory, then converted to `format` and written to `buffer`.
+    /// The same `timeout` is used for both the capture and the conversion.
+    ///
+    /// # Notes
+    ///
+    /// The image isn't rotated: to copy it to a framebuffer (which is rotated by 90 degrees, see [`Screen::raw_framebuffer()`](crate::services::gfx::Screen::raw_framebuffer)),
+    /// it must be written pixel by pixel, e.g. with a [`FrameBuffer`](crate::services::gfx::FrameBuffer).
+    ///
+    /// Like the capture, the conversion writes to `buffer` via DMA, so it should be allocated in LINEAR memory.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `buffer` is too short, if the camera or the converter are busy, or if the timeout is reached.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `width` isn't a multiple of 8 between 8 and 1024, or if `height` isn't a multiple of 8
+    /// (see [`Y2r::convert_yuv422()`]). All the [`ViewSize`]s fulfill these requirements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # use std::time::Duration;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::linear::LinearBox;
+    /// use ctru::services::cam::{Cam, Camera, ViewSize};
+    /// use ctru::services::y2r::{OutputFormat, Y2r};
+    /// let mut cam = Cam::new()?;
+    /// let mut y2r = Y2r::new()?;
+    ///
+    /// let inward = &mut cam.inner_cam;
+    /// inward.set_view_size(ViewSize::TopLCD)?;
+    ///
+    /// // Size of the top screen at 2 bytes per pixel (RGB565), in LINEAR memory.
+    /// let mut buffer = LinearBox::new_slice(0u8, 400 * 240 * 2);
+    ///
+    /// inward.take_picture_rgb(&mut y2r, &mut buffer, 400, 240, OutputFormat::Rgb565, Duration::from_secs(3))?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn take_picture_rgb(
+        &mut self,
+        y2r: &mut Y2r,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        format: y2r::OutputFormat,
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        if self.output_format() == Some(OutputFormat::Rgb565) {
+            self.set_output_format(OutputFormat::Yuv422)?;
+        }
+
+        let mut yuv = LinearBox::new_slice(0u8, usize::from(width) * usize::from(height) * 2);
+        self.take_picture_linear(&mut yuv, width, height, timeout)?;
+
+        y2r.convert_yuv422(&yuv, buffer, width, height, format, timeout)
+    }
 }
 
 impl Cam {
@@ -970,6 +1288,8 @@ impl Cam {
     pub fn new() -> crate::Result<Cam> {
         unsafe {
             ResultCode(ctru_sys::camInit())?;
+            ACTIVE_CAMERA.store(ctru_sys::SELECT_NONE, Ordering::Relaxed);
+            *CONFIGURATIONS.lock().unwrap() = [Configuration::new(); 3];
             Ok(Cam {
                 inner_cam: InwardCam,
                 outer_right_cam: OutwardRightCam,
@@ -979,6 +1299,35 @@ impl Cam {
         }
     }
 
+    /// Returns the single cameras available on the console.
+    ///
+    /// # Notes
+    ///
+    /// All models of the 3DS family have the same three cameras: the inward camera and the two outward cameras (right and left, in this order).
+    /// The combination of both outward cameras ([`Cam::both_outer_cams`]) isn't listed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cam::Cam;
+    /// let cam = Cam::new()?;
+    ///
+    /// for camera in cam.available_cameras() {
+    ///     println!("View size: {:?}", camera.view_size());
+    ///     println!("Output format: {:?}", camera.output_format());
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn available_cameras(&self) -> [&dyn Camera; 3] {
+        [&self.inner_cam, &self.outer_right_cam, &self.outer_left_cam]
+    }
+
     /// Play the specified sound based on the [`ShutterSound`] argument
     ///
     /// # Notes
@@ -1015,7 +1364,14 @@ impl Cam {
 impl Drop for Cam {
     #[doc(alias = "camExit")]
     fn drop(&mut self) {
-        unsafe { ctru_sys::camExit() };
+        unsafe {
+            if ACTIVE_CAMERA.swap(ctru_sys::SELECT_NONE, Ordering::Relaxed) != ctru_sys::SELECT_NONE
+            {
+                let _ = ctru_sys::CAMU_Activate(ctru_sys::SELECT_NONE);
+            }
+
+            ctru_sys::camExit()
+        };
     }
 }
 
@@ -5,6 +5,88 @@
 
 use crate::error::ResultCode;
 
+/// Config block holding the user name.
+const USERNAME_BLOCK_ID: u32 = 0x000A0000;
+const USERNAME_BLOCK_SIZE: usize = 0x1C;
+
+/// Config block holding the offset between the RTC and the clock set by the user.
+const USER_TIME_OFFSET_BLOCK_ID: u32 = 0x00030001;
+
+/// Config block holding the touch screen calibration data.
+const TOUCH_CALIBRATION_BLOCK_ID: u32 = 0x00040000;
+const TOUCH_CALIBRATION_BLOCK_SIZE: usize = 0x10;
+
+/// Touch screen calibration data, as stored in the system configuration.
+///
+/// The calibration maps two reference points from the raw readings of the touch panel to screen pixels.
+/// Raw readings in between are converted with a linear interpolation (and extrapolation past the reference points).
+///
+/// Retrieve it with [`Cfgu::touch_calibration()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TouchCalibration {
+    /// Raw reading of the first (top-left) reference point.
+    pub raw_top_left: (u16, u16),
+    /// Position (in pixels) of the first reference point.
+    pub screen_top_left: (u16, u16),
+    /// Raw reading of the second (bottom-right) reference point.
+    pub raw_bottom_right: (u16, u16),
+    /// Position (in pixels) of the second reference point.
+    pub screen_bottom_right: (u16, u16),
+}
+
+impl TouchCalibration {
+    /// Convert a raw touch panel reading to a position in pixels, clamped to the bottom screen (320x240).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::services::cfgu::TouchCalibration;
+    ///
+    /// let calibration = TouchCalibration {
+    ///     raw_top_left: (400, 400),
+    ///     screen_top_left: (32, 24),
+    ///     raw_bottom_right: (3600, 3600),
+    ///     screen_bottom_right: (288, 216),
+    /// };
+    ///
+    /// assert_eq!(calibration.apply((2000, 2000)), (160, 120));
+    /// assert_eq!(calibration.apply((0, 4095)), (0, 239));
+    /// ```
+    pub fn apply(&self, raw: (u16, u16)) -> (u16, u16) {
+        let axis = |raw: u16, raw_0: u16, raw_1: u16, screen_0: u16, screen_1: u16, max: f32| {
+            if raw_0 == raw_1 {
+                return screen_0;
+            }
+
+            let scale =
+                (f32::from(screen_1) - f32::from(screen_0)) / (f32::from(raw_1) - f32::from(raw_0));
+            let position = f32::from(screen_0) + (f32::from(raw) - f32::from(raw_0)) * scale;
+
+            position.round().clamp(0.0, max) as u16
+        };
+
+        (
+            axis(
+                raw.0,
+                self.raw_top_left.0,
+                self.raw_bottom_right.0,
+                self.screen_top_left.0,
+                self.screen_bottom_right.0,
+                319.0,
+            ),
+            axis(
+                raw.1,
+                self.raw_top_left.1,
+                self.raw_bottom_right.1,
+                self.screen_top_left.1,
+                self.screen_bottom_right.1,
+                239.0,
+            ),
+        )
+    }
+}
+
 /// Console region.
 #[doc(alias = "CFG_Region")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -232,6 +314,241 @@ impl Cfgu {
         ResultCode(unsafe { ctru_sys::CFGU_GetModelNintendo2DS(&mut is_2ds_family) })?;
         Ok(is_2ds_family == 0)
     }
+
+    /// Check if the user profile has been configured (i.e. the console's initial setup has been completed).
+    ///
+    /// # Notes
+    ///
+    /// The profile is considered configured if a user name is set in the config block `0x000A0000`,
+    /// which happens during the initial setup. Emulators usually ship a default profile, so this isn't a reliable way to detect them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// let cfgu = Cfgu::new()?;
+    ///
+    /// if !cfgu.is_profile_configured()? {
+    ///     println!("Welcome! It looks like this console is brand new.");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_profile_configured(&self) -> crate::Result<bool> {
+        let mut username = [0u8; USERNAME_BLOCK_SIZE];
+
+        self.config_block(USERNAME_BLOCK_ID, &mut username)?;
+
+        // The user name is stored as a NUL-terminated UTF-16 string.
+        Ok(username[..2] != [0, 0])
+    }
+
+    /// Check if the touch screen calibration data is present.
+    ///
+    /// # Notes
+    ///
+    /// The calibration data is read from the config block `0x00040000`, which is zeroed on consoles that were never calibrated.
+    pub fn has_touch_calibration(&self) -> crate::Result<bool> {
+        let mut calibration = [0u8; TOUCH_CALIBRATION_BLOCK_SIZE];
+
+        self.config_block(TOUCH_CALIBRATION_BLOCK_ID, &mut calibration)?;
+
+        Ok(calibration.iter().any(|&b| b != 0))
+    }
+
+    /// Returns a hash unique to this console, generated from the console's `LocalFriendCodeSeed` and the specified salt.
+    ///
+    /// The hash is stable for a given console and salt (it doesn't change across reboots or reinstalls of the application),
+    /// but it differs from console to console. The secret seed itself is never exposed.
+    /// Only the lower 20 bits of `salt` are used: applications usually pass the unique ID of their title ID, so that
+    /// different applications get unrelated hashes.
+    ///
+    /// This function is only available with the `console-unique` feature, since the hash is a permanent identifier of the console.
+    /// Avoid sending it over the network or storing it in plain sight.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the process isn't allowed to use this command of the `cfg:u` service.
+    /// All applications have access to `cfg:u`, but some environments (like restricted homebrew launchers or emulators) may deny the request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// let cfgu = Cfgu::new()?;
+    ///
+    /// // Derive a per-console key for the save data encryption.
+    /// let key = cfgu.console_unique_hash(0xBEEF0)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "console-unique")]
+    #[doc(alias = "CFGU_GenHashConsoleUnique")]
+    pub fn console_unique_hash(&self, salt: u32) -> crate::Result<u64> {
+        use crate::error::{Error, ResultExt};
+
+        let mut hash = 0;
+        let result = unsafe { ctru_sys::CFGU_GenHashConsoleUnique(salt, &mut hash) };
+
+        if ctru_sys::R_FAILED(result) {
+            return Err(Error::Os(result)).context("unable to generate the console-unique hash");
+        }
+
+        Ok(hash)
+    }
+
+    /// Returns the touch screen calibration data.
+    ///
+    /// See [`Hid::touch_position_calibrated()`](crate::services::hid::Hid::touch_position_calibrated) to learn how to use this.
+    ///
+    /// # Notes
+    ///
+    /// The data is read from the config block `0x00040000`. It is zeroed on consoles that were never calibrated
+    /// (see [`Cfgu::has_touch_calibration()`]), in which case [`TouchCalibration::apply()`] maps every reading to the same point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// let cfgu = Cfgu::new()?;
+    ///
+    /// let calibration = cfgu.touch_calibration()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn touch_calibration(&self) -> crate::Result<TouchCalibration> {
+        let mut block = [0u8; TOUCH_CALIBRATION_BLOCK_SIZE];
+
+        self.config_block(TOUCH_CALIBRATION_BLOCK_ID, &mut block)?;
+
+        let value = |index: usize| u16::from_le_bytes([block[index * 2], block[index * 2 + 1]]);
+
+        Ok(TouchCalibration {
+            raw_top_left: (value(0), value(1)),
+            screen_top_left: (value(2), value(3)),
+            raw_bottom_right: (value(4), value(5)),
+            screen_bottom_right: (value(6), value(7)),
+        })
+    }
+
+    /// Returns the offset (in milliseconds) between the hardware RTC and the date and time set by the user in the System Settings.
+    ///
+    /// # Notes
+    ///
+    /// The console has no notion of time zones: the user sets the local date and time directly, and the system stores the
+    /// difference from the RTC in the config block `0x00030001`. There is no time zone or UTC offset setting and no daylight saving
+    /// time handling (users adjust the clock by hand), so the UTC offset of the console can't be known.
+    ///
+    /// Because of this, the time reported by [`SystemTime`](std::time::SystemTime) (which already includes this offset) is the local time,
+    /// even though it's presented as UTC. Applications which need the actual UTC time must ask the user for their time zone
+    /// or get the time from the network.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// let cfgu = Cfgu::new()?;
+    ///
+    /// let offset = cfgu.user_time_offset()?;
+    /// println!("The clock was moved by {} minutes", offset / 60_000);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn user_time_offset(&self) -> crate::Result<i64> {
+        let mut block = [0u8; 8];
+
+        self.config_block(USER_TIME_OFFSET_BLOCK_ID, &mut block)?;
+
+        Ok(i64::from_le_bytes(block))
+    }
+
+    /// Returns the raw contents of the config block with the specified ID, which is `size` bytes long.
+    ///
+    /// This gives access to the blocks of the config savegame which have no dedicated accessor in this module.
+    ///
+    /// # Notes
+    ///
+    /// Only the blocks readable by the `cfg:u` service can be read. Some common ones are:
+    ///
+    /// | ID           | Size     | Contents                                     |
+    /// |--------------|----------|----------------------------------------------|
+    /// | `0x00030001` | `0x8`    | User time offset                             |
+    /// | `0x00040000` | `0x10`   | Touch screen calibration                     |
+    /// | `0x00070001` | `0x1`    | Sound output mode                            |
+    /// | `0x000A0000` | `0x1C`   | User name (UTF-16) and its flags             |
+    /// | `0x000A0001` | `0x2`    | Birthday (month and day)                     |
+    /// | `0x000A0002` | `0x1`    | System language                              |
+    /// | `0x000B0000` | `0x4`    | Country and region info                      |
+    /// | `0x000C0000` | `0xC0`   | Parental controls                            |
+    /// | `0x000C0001` | `0x14`   | COPPACS restrictions                         |
+    /// | `0x000D0000` | `0x4`    | Accepted EULA version                        |
+    /// | `0x000F0004` | `0x4`    | System model                                 |
+    ///
+    /// See [3dbrew](https://www.3dbrew.org/wiki/Config_Savegame#Configuration_blocks) for the full list and the layout of each block.
+    /// Writing config blocks needs the `cfg:i` service, which is restricted to system titles, and isn't supported.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the block doesn't exist, if it can't be read through `cfg:u`,
+    /// or if `size` doesn't match the size of the block.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// let cfgu = Cfgu::new()?;
+    ///
+    /// // Read the version of the EULA accepted by the user.
+    /// let eula = cfgu.get_block(0x000D0000, 4)?;
+    /// println!("EULA version {}.{}", eula[1], eula[0]);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "CFGU_GetConfigInfoBlk2")]
+    pub fn get_block(&self, block_id: u32, size: usize) -> crate::Result<Vec<u8>> {
+        let mut block = vec![0; size];
+
+        self.config_block(block_id, &mut block)?;
+
+        Ok(block)
+    }
+
+    /// Read the config block with the specified ID into `buf`.
+    ///
+    /// The size of `buf` must match the size of the block.
+    #[doc(alias = "CFGU_GetConfigInfoBlk2")]
+    pub(crate) fn config_block(&self, block_id: u32, buf: &mut [u8]) -> crate::Result<()> {
+        ResultCode(unsafe {
+            ctru_sys::CFGU_GetConfigInfoBlk2(buf.len() as u32, block_id, buf.as_mut_ptr().cast())
+        })?;
+
+        Ok(())
+    }
 }
 
 impl Drop for Cfgu {
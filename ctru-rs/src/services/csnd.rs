@@ -0,0 +1,295 @@
+//! CSND (Sound) service.
+//!
+//! The CSND service is the older, lower-level interface to the console's sound hardware.
+//! It plays PCM samples directly on the hardware sound channels, without going through the DSP processor.
+//!
+//! # CSND vs NDSP
+//!
+//! New code should generally use the [NDSP](crate::services::ndsp) service, which supports streaming (with a queue of [`Wave`](crate::services::ndsp::wave::Wave)s
+//! per channel), stereo samples, filters and a proper mixer. CSND is mostly useful for porting code which already targets it, or in environments where
+//! the DSP firmware (required by NDSP) isn't available, since CSND doesn't need it.
+//!
+//! On the other hand, CSND only plays mono samples (stereo sound requires two channels, one per speaker), doesn't support queueing buffers and
+//! isn't available in every environment: access to it depends on the permissions of the running application.
+//!
+//! # Linear memory
+//!
+//! The sound hardware reads the samples directly from memory, so they must be stored on [LINEAR memory](crate::linear).
+//! [`CsndWave`] enforces this by only accepting buffers allocated with the [`LinearAllocator`].
+#![doc(alias = "audio")]
+
+use crate::error::{Error, ResultCode};
+use crate::linear::LinearAllocator;
+
+/// Number of hardware sound channels. Only some of them are available to the application, see [`Csnd::available_channels()`].
+const NUMBER_OF_CHANNELS: u8 = 32;
+
+/// PCM formats supported by CSND. Samples are always single-channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CsndFormat {
+    /// PCM 8bit.
+    PCM8 = ctru_sys::SOUND_FORMAT_8BIT,
+    /// PCM 16bit.
+    PCM16 = ctru_sys::SOUND_FORMAT_16BIT,
+}
+
+/// Audio buffer played on a CSND channel.
+///
+/// The buffer is kept alive by [`Csnd`] while it's being played.
+pub struct CsndWave {
+    buffer: Box<[u8], LinearAllocator>,
+    format: CsndFormat,
+    sample_rate: u32,
+    looping: bool,
+}
+
+impl CsndWave {
+    /// Build a new wave from a raw buffer on [LINEAR memory](crate::linear), its format and its sample rate (in Hz).
+    ///
+    /// If `looping` is `true`, the whole buffer is repeated until the channel is [stopped](Csnd::stop).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #![feature(allocator_api)]
+    /// # fn main() {
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    /// use ctru::services::csnd::{CsndFormat, CsndWave};
+    ///
+    /// // Zeroed box allocated in the LINEAR memory.
+    /// let audio_data = Box::new_in([0u8; 96], LinearAllocator);
+    ///
+    /// let wave = CsndWave::new(audio_data, CsndFormat::PCM16, 22050, false);
+    /// # }
+    /// ```
+    pub fn new(
+        buffer: Box<[u8], LinearAllocator>,
+        format: CsndFormat,
+        sample_rate: u32,
+        looping: bool,
+    ) -> Self {
+        // The sound hardware reads the samples from memory, so they must not linger in the CPU's data cache.
+        unsafe {
+            let _r = ctru_sys::GSPGPU_FlushDataCache(buffer.as_ptr().cast(), buffer.len() as u32);
+        }
+
+        Self {
+            buffer,
+            format,
+            sample_rate,
+            looping,
+        }
+    }
+
+    /// Returns the format of the audio data.
+    pub fn format(&self) -> CsndFormat {
+        self.format
+    }
+
+    /// Returns the sample rate (in Hz) of the audio data.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns `true` if the wave repeats until the channel is stopped.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Returns a slice to the audio data (on the LINEAR memory).
+    pub fn get_buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+/// Handle to the CSND service.
+pub struct Csnd {
+    /// Waves being played, indexed by channel.
+    playing: Vec<Option<CsndWave>>,
+}
+
+impl Csnd {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// Access to this service depends on the permissions of the running application, so it may be unavailable in some environments.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::csnd::Csnd;
+    ///
+    /// let csnd = Csnd::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "csndInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::csndInit() })?;
+
+        Ok(Self {
+            playing: std::iter::repeat_with(|| None)
+                .take(NUMBER_OF_CHANNELS.into())
+                .collect(),
+        })
+    }
+
+    /// Returns the IDs of the hardware channels available to the application.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::csnd::Csnd;
+    /// let csnd = Csnd::new()?;
+    ///
+    /// println!("Available channels: {:?}", csnd.available_channels());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "csndChannels")]
+    pub fn available_channels(&self) -> Vec<u8> {
+        let mask = unsafe { ctru_sys::csndChannels };
+
+        (0..NUMBER_OF_CHANNELS)
+            .filter(|id| mask & (1 << id) != 0)
+            .collect()
+    }
+
+    /// Play a wave on the specified channel, replacing anything it was playing.
+    ///
+    /// `volume` ranges from 0.0 to 1.0, while `pan` ranges from -1.0 (left speaker only) to 1.0 (right speaker only).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the channel isn't [available](Csnd::available_channels) or if the sound hardware refuses the command.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #![feature(allocator_api)]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::linear::LinearAllocator;
+    /// use ctru::services::csnd::{Csnd, CsndFormat, CsndWave};
+    /// let mut csnd = Csnd::new()?;
+    ///
+    /// let audio_data = Box::new_in([0u8; 96], LinearAllocator);
+    /// let wave = CsndWave::new(audio_data, CsndFormat::PCM16, 22050, false);
+    ///
+    /// let channel = csnd.available_channels()[0];
+    /// csnd.play(channel, wave, 1.0, 0.0)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "csndPlaySound")]
+    pub fn play(
+        &mut self,
+        channel: u8,
+        wave: CsndWave,
+        volume: f32,
+        pan: f32,
+    ) -> crate::Result<()> {
+        self.check_channel(channel)?;
+
+        // Stop the previous wave (if any) before its buffer is released.
+        self.stop(channel)?;
+
+        let mode = if wave.looping {
+            ctru_sys::SOUND_REPEAT
+        } else {
+            ctru_sys::SOUND_ONE_SHOT
+        };
+
+        let data = wave.buffer.as_ptr() as *mut _;
+
+        ResultCode(unsafe {
+            ctru_sys::csndPlaySound(
+                channel.into(),
+                wave.format as u32 | mode,
+                wave.sample_rate,
+                volume.clamp(0.0, 1.0),
+                pan.clamp(-1.0, 1.0),
+                data,
+                data,
+                wave.buffer.len() as u32,
+            )
+        })?;
+
+        self.playing[usize::from(channel)] = Some(wave);
+
+        Ok(())
+    }
+
+    /// Stop the specified channel, releasing the wave it was playing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the channel isn't [available](Csnd::available_channels) or if the sound hardware refuses the command.
+    #[doc(alias = "CSND_SetPlayState")]
+    pub fn stop(&mut self, channel: u8) -> crate::Result<()> {
+        self.check_channel(channel)?;
+
+        unsafe {
+            ctru_sys::CSND_SetPlayState(channel.into(), 0);
+            ResultCode(ctru_sys::csndExecCmds(true))?;
+        }
+
+        self.playing[usize::from(channel)] = None;
+
+        Ok(())
+    }
+
+    /// Returns `true` if the specified channel is currently playing.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the channel isn't [available](Csnd::available_channels) or if its state can't be read.
+    #[doc(alias = "csndIsPlaying")]
+    pub fn is_playing(&self, channel: u8) -> crate::Result<bool> {
+        self.check_channel(channel)?;
+
+        let mut status = 0;
+        ResultCode(unsafe { ctru_sys::csndIsPlaying(channel.into(), &mut status) })?;
+
+        Ok(status != 0)
+    }
+
+    fn check_channel(&self, channel: u8) -> crate::Result<()> {
+        if channel < NUMBER_OF_CHANNELS && unsafe { ctru_sys::csndChannels } & (1 << channel) != 0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument(format!(
+                "channel {channel} is not available"
+            )))
+        }
+    }
+}
+
+impl Drop for Csnd {
+    #[doc(alias = "csndExit")]
+    fn drop(&mut self) {
+        // The sound hardware must not keep reading the buffers after they are released.
+        for channel in 0..NUMBER_OF_CHANNELS {
+            if self.playing[usize::from(channel)].is_some() {
+                let _ = self.stop(channel);
+            }
+        }
+
+        unsafe { ctru_sys::csndExit() };
+    }
+}
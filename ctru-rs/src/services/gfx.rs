@@ -2,18 +2,27 @@
 //!
 //! The GFX service controls (in a somewhat high-level way) the console's LCD screens.
 //! The screens are subordinate to the GFX service handle and can be used by only one borrower at a time.
+//!
+//! # Threads
+//!
+//! [`Gfx`] keeps its screens in [`RefCell`]s, so it can't be shared between threads. To render on a separate thread,
+//! get a [`Presenter`] with [`Gfx::presenter()`] and move it to a scoped thread: it gives exclusive access to both screens.
 #![doc(alias = "graphics")]
 
 use std::cell::{Ref, RefCell, RefMut};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use crate::error::Result;
-use crate::services::gspgpu::{self, FramebufferFormat};
+use crate::error::{Error, Result, ResultCode};
+use crate::services::gspgpu::{self, Color, FramebufferFormat};
 use crate::services::ServiceReference;
 
 mod private {
-    use super::{BottomScreen, TopScreen, TopScreen3D, TopScreenLeft, TopScreenRight};
+    use super::{
+        BottomScreen, Rgb565, Rgb8, Rgba8, TopScreen, TopScreen3D, TopScreenLeft, TopScreenRight,
+    };
 
     pub trait Sealed {}
 
@@ -22,6 +31,10 @@ mod private {
     impl Sealed for TopScreenLeft {}
     impl Sealed for TopScreenRight {}
     impl Sealed for BottomScreen {}
+
+    impl Sealed for Rgb565 {}
+    impl Sealed for Rgb8 {}
+    impl Sealed for Rgba8 {}
 }
 
 /// Trait to handle common functionality for all screens.
@@ -37,10 +50,49 @@ pub trait Screen: private::Sealed {
     /// Returns the Screen side (left or right).
     fn side(&self) -> Side;
 
+    /// Returns the dimensions (width, height) of the screen in pixels, as seen by the user.
+    ///
+    /// # Notes
+    ///
+    /// The top screen is 400×240 pixels (800×240 when [wide mode](TopScreen::set_wide_mode) is enabled),
+    /// while the bottom screen is 320×240 pixels.
+    ///
+    /// The framebuffers are stored in memory rotated by 90 degrees, so the values reported by
+    /// [`Screen::raw_framebuffer()`] are swapped compared to these.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, Screen};
+    /// let gfx = Gfx::new()?;
+    ///
+    /// assert_eq!(gfx.top_screen.borrow().dimensions(), (400, 240));
+    /// assert_eq!(gfx.bottom_screen.borrow().dimensions(), (320, 240));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn dimensions(&self) -> (u16, u16) {
+        let width = match self.as_raw() {
+            ctru_sys::GFX_TOP if unsafe { ctru_sys::gfxIsWide() } => 800,
+            ctru_sys::GFX_TOP => 400,
+            _ => 320,
+        };
+
+        (width, 240)
+    }
+
     /// Returns a [`RawFrameBuffer`] for the screen.
     ///
     /// Note that the pointer of the framebuffer returned by this function can
     /// change after each call to this function if double buffering is enabled.
+    ///
+    /// The [`RawFrameBuffer`] mutably borrows the screen, so the framebuffer format can't be
+    /// [changed](Screen::set_framebuffer_format) while it's alive: a new one must be fetched after every format change.
     #[doc(alias = "gfxGetFramebuffer")]
     fn raw_framebuffer(&mut self) -> RawFrameBuffer {
         let mut width: u16 = 0;
@@ -52,6 +104,7 @@ pub trait Screen: private::Sealed {
             ptr,
             width: width.into(),
             height: height.into(),
+            format: self.framebuffer_format(),
             screen: PhantomData,
         }
     }
@@ -66,10 +119,147 @@ pub trait Screen: private::Sealed {
     ///
     /// [`Swap::swap_buffers`] must be called after this method for the configuration
     /// change to take effect.
+    ///
+    /// # Notes
+    ///
+    /// Changing the format to one with a different pixel size reallocates the framebuffers of the screen.
+    /// Since this method takes the screen mutably, no [`RawFrameBuffer`] or [`PixelWriter`] borrowing it can outlive the change,
+    /// but pointers copied out of a [`RawFrameBuffer`] are left dangling (or with a stale stride): always fetch a new [`RawFrameBuffer`]
+    /// after calling this method, and use its [`format`](RawFrameBuffer::format) to compute the layout of the pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, Screen};
+    /// use ctru::services::gspgpu::FramebufferFormat;
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// bottom_screen.set_framebuffer_format(FramebufferFormat::Rgba8);
+    ///
+    /// let framebuffer = bottom_screen.raw_framebuffer();
+    /// assert_eq!(framebuffer.format, FramebufferFormat::Rgba8);
+    /// assert_eq!(framebuffer.stride(), 240 * 4);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[doc(alias = "gfxSetScreenFormat")]
     fn set_framebuffer_format(&mut self, fmt: FramebufferFormat) {
         unsafe { ctru_sys::gfxSetScreenFormat(self.as_raw(), fmt.into()) }
     }
+
+    /// Configure the screen to show images rendered by the GPU in the specified color format, returning the framebuffer to copy them to.
+    ///
+    /// The GPU renders to its own color buffer, which is then copied to the screen's framebuffer with a display transfer.
+    /// The transfer can convert between formats, but its output format must match the [`FramebufferFormat`] of the screen:
+    /// this method sets the screen's format to the one matching `format` (see [`GpuColorFormat`](gspgpu::GpuColorFormat)),
+    /// so the same value can be used for the color buffer, the transfer's output and the display.
+    ///
+    /// # Notes
+    ///
+    /// The GPU supports [`Rgba8`](gspgpu::GpuColorFormat::Rgba8) (the most common choice, with alpha and full precision),
+    /// [`Rgb8`](gspgpu::GpuColorFormat::Rgb8), [`Rgba5551`](gspgpu::GpuColorFormat::Rgba5551), [`Rgb565`](gspgpu::GpuColorFormat::Rgb565)
+    /// and [`Rgba4`](gspgpu::GpuColorFormat::Rgba4). Smaller formats halve the bandwidth used by the transfer, at the cost of color precision.
+    ///
+    /// Like [`Screen::set_framebuffer_format()`], the change is shown after [`Swap::swap_buffers()`] is called,
+    /// and the returned framebuffer must be fetched again after each swap if double buffering is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, Screen};
+    /// use ctru::services::gspgpu::{FramebufferFormat, GpuColorFormat};
+    /// let gfx = Gfx::new()?;
+    /// let mut top_screen = gfx.top_screen.borrow_mut();
+    ///
+    /// let target = top_screen.set_render_target_format(GpuColorFormat::Rgba8);
+    /// assert_eq!(target.format, FramebufferFormat::Rgba8);
+    ///
+    /// // Render with the GPU, then copy the color buffer to `target.ptr` with a display transfer.
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "gfxSetScreenFormat", alias = "GPU_COLORBUF")]
+    fn set_render_target_format(&mut self, format: gspgpu::GpuColorFormat) -> RawFrameBuffer {
+        self.set_framebuffer_format(format.into());
+        self.raw_framebuffer()
+    }
+
+    /// Fill the current framebuffer with a solid colour.
+    ///
+    /// # Notes
+    ///
+    /// Like any other write to the framebuffer, the result is shown only after [`Flush::flush_buffers()`] and [`Swap::swap_buffers()`] are called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Flush, Gfx, Screen, Swap};
+    /// use ctru::services::gspgpu::Color;
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// bottom_screen.clear(Color::BLUE);
+    ///
+    /// bottom_screen.flush_buffers();
+    /// bottom_screen.swap_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn clear(&mut self, color: Color) {
+        let format = self.framebuffer_format();
+        let depth = format.pixel_depth_bytes();
+        let (width, height) = self.dimensions();
+        let len = usize::from(width) * usize::from(height) * depth;
+
+        let framebuffer = self.raw_framebuffer();
+
+        // Safety: the framebuffer returned by libctru is valid for the whole frame and
+        // its size is described by the screen's dimensions and format.
+        let buffer = unsafe { std::slice::from_raw_parts_mut(framebuffer.ptr, len) };
+
+        // Repeat the packed colour enough times to fill a whole number of 32-bit words.
+        let pattern_len = if depth == 3 { 12 } else { 4 };
+        let mut pattern = [0u8; 12];
+        for pixel in pattern[..pattern_len].chunks_exact_mut(depth) {
+            format.write_color(color, pixel);
+        }
+
+        let mut pattern_words = [0u32; 3];
+        for (word, bytes) in pattern_words.iter_mut().zip(pattern.chunks_exact(4)) {
+            *word = u32::from_ne_bytes(bytes.try_into().unwrap());
+        }
+        let pattern_words = &pattern_words[..pattern_len / 4];
+
+        // Safety: any bit pattern is a valid `u32`.
+        let (prefix, words, _) = unsafe { buffer.align_to_mut::<u32>() };
+
+        if prefix.is_empty() && len % pattern_len == 0 {
+            for chunk in words.chunks_exact_mut(pattern_words.len()) {
+                chunk.copy_from_slice(pattern_words);
+            }
+        } else {
+            // Framebuffers are always word-aligned, but fall back to writing each pixel just in case.
+            for pixel in buffer.chunks_exact_mut(depth) {
+                format.write_color(color, pixel);
+            }
+        }
+    }
 }
 
 /// The top LCD screen.
@@ -101,6 +291,10 @@ pub trait Swap: private::Sealed {
     /// [`Screen::set_framebuffer_format()`], [`Swap::set_double_buffering()`]), so it should still be used.
     ///
     /// This should be called once per frame at most.
+    ///
+    /// # Notes
+    ///
+    /// This function does nothing while [manual presentation](Gfx::set_manual_presentation) is enabled.
     #[doc(alias = "gfxScreenSwapBuffers")]
     fn swap_buffers(&mut self);
 
@@ -111,14 +305,47 @@ pub trait Swap: private::Sealed {
     /// Double buffering is enabled by default.
     /// [`Swap::swap_buffers`] must be called after this function for the configuration
     /// change to take effect.
+    ///
+    /// Toggling double buffering doesn't reallocate anything: `libctru` always allocates two framebuffers per screen
+    /// (only [changing the format](Screen::set_framebuffer_format) to one with a different pixel size reallocates them),
+    /// and this function only changes which of them the next swaps select. It's cheap enough to be called whenever the application
+    /// switches between a mode which redraws every frame (with double buffering) and one which shows a static image (without it).
+    ///
+    /// While double buffering is disabled, the framebuffer being drawn to is the one being displayed, so changes are visible
+    /// as soon as they are [flushed](Flush::flush_buffers), and may tear if drawn while the screen refreshes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, Swap};
+    /// let gfx = Gfx::new()?;
+    /// let mut top_screen = gfx.top_screen.borrow_mut();
+    ///
+    /// // Slideshow: draw each picture once on a single buffer.
+    /// top_screen.set_double_buffering(false);
+    /// top_screen.swap_buffers();
+    ///
+    /// // Game: redraw every frame, showing only complete frames.
+    /// top_screen.set_double_buffering(true);
+    /// top_screen.swap_buffers();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[doc(alias = "gfxSetDoubleBuffering")]
     fn set_double_buffering(&mut self, enabled: bool);
 }
 
 impl Swap for TopScreen3D<'_> {
     fn swap_buffers(&mut self) {
-        unsafe {
-            ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, true);
+        if !MANUAL_PRESENTATION.load(Ordering::Relaxed) {
+            unsafe {
+                ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, true);
+            }
         }
     }
 
@@ -129,8 +356,10 @@ impl Swap for TopScreen3D<'_> {
 
 impl Swap for TopScreen {
     fn swap_buffers(&mut self) {
-        unsafe {
-            ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, false);
+        if !MANUAL_PRESENTATION.load(Ordering::Relaxed) {
+            unsafe {
+                ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_TOP, false);
+            }
         }
     }
 
@@ -141,8 +370,10 @@ impl Swap for TopScreen {
 
 impl Swap for BottomScreen {
     fn swap_buffers(&mut self) {
-        unsafe {
-            ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_BOTTOM, false);
+        if !MANUAL_PRESENTATION.load(Ordering::Relaxed) {
+            unsafe {
+                ctru_sys::gfxScreenSwapBuffers(ctru_sys::GFX_BOTTOM, false);
+            }
         }
     }
 
@@ -162,7 +393,7 @@ pub trait Flush: private::Sealed {
     fn flush_buffers(&mut self);
 }
 
-impl<S: Screen> Flush for S {
+impl<S: Screen + ?Sized> Flush for S {
     fn flush_buffers(&mut self) {
         let framebuffer = self.raw_framebuffer();
 
@@ -215,10 +446,327 @@ pub struct RawFrameBuffer<'screen> {
     pub width: usize,
     /// The height of the framebuffer in pixels.
     pub height: usize,
+    /// The format of the pixels in the framebuffer.
+    pub format: FramebufferFormat,
     /// Keep a mutable reference to the Screen for which this framebuffer is tied.
     screen: PhantomData<&'screen mut dyn Screen>,
 }
 
+impl RawFrameBuffer<'_> {
+    /// Returns the size (in bytes) of a row of the framebuffer, as stored in memory (i.e. [`width`](RawFrameBuffer::width) pixels).
+    pub fn stride(&self) -> usize {
+        self.width * self.format.pixel_depth_bytes()
+    }
+}
+
+/// Safe helper to draw single pixels on a [`Screen`].
+///
+/// The [`PixelWriter`] takes care of converting [`Color`]s to the [`FramebufferFormat`] of the screen
+/// and of the framebuffer's rotation (the 3DS' framebuffers are stored rotated by 90 degrees).
+/// Coordinates are expressed as seen by the user: (0, 0) is the top left corner of the screen.
+///
+/// # Notes
+///
+/// The [`PixelWriter`] targets the framebuffer that was active when it was created.
+/// If double buffering is enabled, a new [`PixelWriter`] must be created after every call to [`Swap::swap_buffers()`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gfx::{Flush, Gfx, PixelWriter, Swap};
+/// use ctru::services::gspgpu::Color;
+///
+/// let gfx = Gfx::new()?;
+/// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+///
+/// let mut writer = PixelWriter::new(&mut *bottom_screen);
+///
+/// // Draw a red diagonal line.
+/// for i in 0..writer.height() {
+///     writer.set_pixel(i, i, Color::RED);
+/// }
+///
+/// bottom_screen.flush_buffers();
+/// bottom_screen.swap_buffers();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct PixelWriter<'screen> {
+    buffer: &'screen mut [u8],
+    format: FramebufferFormat,
+    // Dimensions as seen by the user, not as stored in memory.
+    width: usize,
+    height: usize,
+}
+
+impl<'screen> PixelWriter<'screen> {
+    /// Create a new [`PixelWriter`] targeting the current framebuffer of `screen`.
+    pub fn new<S: Screen + ?Sized>(screen: &'screen mut S) -> Self {
+        let framebuffer = screen.raw_framebuffer();
+        let format = framebuffer.format;
+
+        let len = framebuffer.stride() * framebuffer.height;
+
+        // Safety: the framebuffer returned by libctru is valid for the whole frame and
+        // its size is described by its dimensions and format. The mutable borrow of the screen
+        // guarantees nobody else can write to it through this crate while the writer is alive.
+        let buffer = unsafe { std::slice::from_raw_parts_mut(framebuffer.ptr, len) };
+
+        Self {
+            buffer,
+            format,
+            width: framebuffer.height,
+            height: framebuffer.width,
+        }
+    }
+
+    /// Returns the width of the screen in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the screen in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the [`FramebufferFormat`] colours are converted to.
+    pub fn format(&self) -> FramebufferFormat {
+        self.format
+    }
+
+    /// Set the pixel at the (x, y) coordinates to the specified colour.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the coordinates are out of the screen's bounds.
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({x}, {y}) is out of bounds for a {}x{} screen",
+            self.width,
+            self.height
+        );
+
+        // The framebuffer is rotated: columns of the screen are stored as contiguous rows, from bottom to top.
+        let depth = self.format.pixel_depth_bytes();
+        let index = (x * self.height + (self.height - 1 - y)) * depth;
+
+        self.format
+            .write_color(color, &mut self.buffer[index..index + depth]);
+    }
+}
+
+/// Pixel type of a [`FrameBuffer`], with the same memory layout as the pixels of a [`FramebufferFormat`].
+///
+/// This trait is sealed, and implemented by [`Rgb565`], [`Rgb8`] and [`Rgba8`].
+pub trait Pixel: private::Sealed + Copy {
+    /// Format of the framebuffers holding this pixel type.
+    const FORMAT: FramebufferFormat;
+}
+
+/// Pixel of a framebuffer in the [`FramebufferFormat::Rgb565`] format.
+///
+/// The pixel is packed in a single `u16`, with red in the 5 most significant bits, green in the middle 6 and blue in the 5 least significant bits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Rgb565(pub u16);
+
+/// Pixel of a framebuffer in the [`FramebufferFormat::Bgr8`] format.
+///
+/// The channels are stored in memory in reverse order (blue first).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Rgb8 {
+    /// Blue channel.
+    pub b: u8,
+    /// Green channel.
+    pub g: u8,
+    /// Red channel.
+    pub r: u8,
+}
+
+/// Pixel of a framebuffer in the [`FramebufferFormat::Rgba8`] format.
+///
+/// The channels are stored in memory in reverse order (alpha first).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Rgba8 {
+    /// Alpha channel.
+    pub a: u8,
+    /// Blue channel.
+    pub b: u8,
+    /// Green channel.
+    pub g: u8,
+    /// Red channel.
+    pub r: u8,
+}
+
+impl Pixel for Rgb565 {
+    const FORMAT: FramebufferFormat = FramebufferFormat::Rgb565;
+}
+
+impl Pixel for Rgb8 {
+    const FORMAT: FramebufferFormat = FramebufferFormat::Bgr8;
+}
+
+impl Pixel for Rgba8 {
+    const FORMAT: FramebufferFormat = FramebufferFormat::Rgba8;
+}
+
+impl From<Color> for Rgb565 {
+    fn from(color: Color) -> Self {
+        Self(gspgpu::rgb8_to_rgb565([color.r, color.g, color.b]))
+    }
+}
+
+impl From<Color> for Rgb8 {
+    fn from(Color { r, g, b, .. }: Color) -> Self {
+        Self { b, g, r }
+    }
+}
+
+impl From<Color> for Rgba8 {
+    fn from(Color { r, g, b, a }: Color) -> Self {
+        Self { a, b, g, r }
+    }
+}
+
+/// Typed view over the framebuffer of a [`Screen`], to read and write its pixels without `unsafe` code.
+///
+/// Pixels are accessed with coordinates as seen by the user: (0, 0) is the top left corner of the screen.
+/// The [`FrameBuffer`] takes care of the framebuffer's rotation (the 3DS' framebuffers are stored rotated by 90 degrees),
+/// while the pixel type `P` (one of [`Rgb565`], [`Rgb8`] and [`Rgba8`]) matches the layout of the framebuffer's format,
+/// so no conversion happens when accessing a pixel.
+///
+/// # Notes
+///
+/// Like [`PixelWriter`], the [`FrameBuffer`] targets the framebuffer that was active when it was created.
+/// If double buffering is enabled, a new [`FrameBuffer`] must be created after every call to [`Swap::swap_buffers()`].
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gfx::{Flush, FrameBuffer, Gfx, Rgb565, Swap};
+///
+/// let gfx = Gfx::new()?;
+/// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+///
+/// let mut framebuffer = FrameBuffer::<Rgb565>::new(&mut *bottom_screen);
+///
+/// // A horizontal gradient.
+/// for y in 0..framebuffer.height() {
+///     for x in 0..framebuffer.width() {
+///         framebuffer.set(x, y, Rgb565((x as u16 / 10) << 11));
+///     }
+/// }
+///
+/// // Pixels out of the screen are ignored.
+/// framebuffer.set(1000, 1000, Rgb565(0xFFFF));
+/// assert_eq!(framebuffer.get(1000, 1000), None);
+///
+/// let first_row: Vec<Rgb565> = framebuffer.rows().next().unwrap().collect();
+/// assert_eq!(first_row.len(), 320);
+///
+/// drop(framebuffer);
+/// bottom_screen.flush_buffers();
+/// bottom_screen.swap_buffers();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct FrameBuffer<'screen, P: Pixel> {
+    /// Pixels in memory order (rotated).
+    pixels: &'screen mut [P],
+    width: usize,
+    height: usize,
+}
+
+impl<'screen, P: Pixel> FrameBuffer<'screen, P> {
+    /// Create a new [`FrameBuffer`] targeting the current framebuffer of `screen`.
+    ///
+    /// If the [`FramebufferFormat`] of the screen doesn't match the pixel type, it's changed with [`Screen::set_framebuffer_format()`]
+    /// (and the change is shown after [`Swap::swap_buffers()`] is called).
+    pub fn new<S: Screen + ?Sized>(screen: &'screen mut S) -> Self {
+        if screen.framebuffer_format() != P::FORMAT {
+            screen.set_framebuffer_format(P::FORMAT);
+        }
+
+        let framebuffer = screen.raw_framebuffer();
+
+        assert_eq!(framebuffer.ptr.align_offset(std::mem::align_of::<P>()), 0);
+
+        // Safety: the framebuffer returned by libctru is valid for the whole frame, its format matches the layout of `P`
+        // and its pointer is aligned. The mutable borrow of the screen guarantees nobody else can write to it through
+        // this crate while the view is alive.
+        let pixels = unsafe {
+            std::slice::from_raw_parts_mut(
+                framebuffer.ptr.cast::<P>(),
+                framebuffer.width * framebuffer.height,
+            )
+        };
+
+        Self {
+            pixels,
+            width: framebuffer.height,
+            height: framebuffer.width,
+        }
+    }
+
+    /// Returns the width of the screen in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the screen in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the pixel at the (x, y) coordinates, or [`None`] if they are out of the screen's bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<P> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Returns a mutable reference to the pixel at the (x, y) coordinates, or [`None`] if they are out of the screen's bounds.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut P> {
+        self.index(x, y).map(|index| &mut self.pixels[index])
+    }
+
+    /// Set the pixel at the (x, y) coordinates. Pixels out of the screen's bounds are ignored.
+    pub fn set(&mut self, x: usize, y: usize, pixel: P) {
+        if let Some(target) = self.get_mut(x, y) {
+            *target = pixel;
+        }
+    }
+
+    /// Set every pixel of the screen.
+    pub fn fill(&mut self, pixel: P) {
+        self.pixels.fill(pixel);
+    }
+
+    /// Returns an iterator over the rows of the screen, from top to bottom.
+    /// Each row is an iterator over its pixels, from left to right.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = P> + '_> + '_ {
+        (0..self.height).map(move |y| (0..self.width).map(move |x| self.get(x, y).unwrap()))
+    }
+
+    /// Returns the position of the pixel at the (x, y) coordinates in memory.
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        // The framebuffer is rotated: columns of the screen are stored as contiguous rows, from bottom to top.
+        (x < self.width && y < self.height).then(|| x * self.height + (self.height - 1 - y))
+    }
+}
+
 /// Side of the [`TopScreen`]'s framebuffer.
 ///
 /// The top screen of the 3DS can have two separate sets of framebuffers to support its 3D functionality
@@ -246,6 +794,15 @@ pub struct Gfx {
 
 static GFX_ACTIVE: Mutex<usize> = Mutex::new(0);
 
+/// Whether buffer swaps are left to the caller. See [`Gfx::set_manual_presentation()`].
+static MANUAL_PRESENTATION: AtomicBool = AtomicBool::new(false);
+
+/// GSP address of the first framebuffer setup register of the top screen: the physical address of the first buffer of framebuffer A,
+/// which is shown to the left eye.
+const TOP_FRAMEBUFFER_REGS: u32 = 0x400468;
+/// Number of registers read from [`TOP_FRAMEBUFFER_REGS`], up to the second buffer of framebuffer B (shown to the right eye).
+const TOP_FRAMEBUFFER_REGS_COUNT: usize = 13;
+
 impl Gfx {
     /// Initialize a new default service handle.
     ///
@@ -318,6 +875,7 @@ impl Gfx {
             false,
             || unsafe {
                 ctru_sys::gfxInit(top_fb_fmt.into(), bottom_fb_fmt.into(), use_vram_buffers);
+                MANUAL_PRESENTATION.store(false, Ordering::Relaxed);
 
                 Ok(())
             },
@@ -331,6 +889,71 @@ impl Gfx {
         })
     }
 
+    /// Create a service handle on top of a GFX module which was already initialized by someone else,
+    /// such as an external GPU library or renderer which calls `gfxInit` itself.
+    ///
+    /// Unlike [`Gfx::new()`], this function doesn't call `gfxInit`, and dropping the handle doesn't call `gfxExit`:
+    /// the module (together with its framebuffers and the GSP session opened by `gfxInit`) stays owned by whoever initialized it.
+    /// Calling `gfxInit` twice instead would reallocate the framebuffers behind the back of the first owner.
+    ///
+    /// # Notes
+    ///
+    /// The GSP session alone is reference counted by `libctru`, so libraries which only call `gspInit`/`gspExit`
+    /// (and not `gfxInit`) can be used together with any [`Gfx`] handle without needing this function.
+    ///
+    /// # Errors
+    ///
+    /// This function will return [`Error::ServiceAlreadyActive`] if another [`Gfx`] handle is already active.
+    ///
+    /// # Safety
+    ///
+    /// `gfxInit` must have been called before this function, and `gfxExit` must not be called until the returned handle is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::Gfx;
+    ///
+    /// // The external renderer initializes (and will later exit) the GFX module.
+    /// unsafe {
+    ///     ctru_sys::gfxInitDefault();
+    /// }
+    ///
+    /// let gfx = unsafe { Gfx::from_external()? };
+    ///
+    /// // ...
+    ///
+    /// drop(gfx);
+    ///
+    /// unsafe {
+    ///     ctru_sys::gfxExit();
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn from_external() -> Result<Self> {
+        let handler = ServiceReference::new(
+            &GFX_ACTIVE,
+            false,
+            || {
+                MANUAL_PRESENTATION.store(false, Ordering::Relaxed);
+
+                Ok(())
+            },
+            || {},
+        )?;
+
+        Ok(Self {
+            top_screen: RefCell::new(TopScreen::new()),
+            bottom_screen: RefCell::new(BottomScreen),
+            _service_handler: handler,
+        })
+    }
+
     /// Waits for the vertical blank event.
     ///
     /// Use this to synchronize your application with the refresh rate of the LCD screens
@@ -362,6 +985,423 @@ impl Gfx {
     pub fn wait_for_vblank(&self) {
         gspgpu::wait_for_event(gspgpu::Event::VBlank0, true);
     }
+
+    /// Returns a [`Presenter`], to access the screens from another thread.
+    ///
+    /// The screens are borrowed without going through their [`RefCell`]s, which is why a mutable reference to the handle is needed.
+    /// Have a look at [`Presenter`] for an example.
+    pub fn presenter(&mut self) -> Presenter<'_> {
+        Presenter {
+            top_screen: self.top_screen.get_mut(),
+            bottom_screen: self.bottom_screen.get_mut(),
+        }
+    }
+
+    /// Enable or disable manual presentation of the framebuffers. Defaults to `false`.
+    ///
+    /// # Notes
+    ///
+    /// While manual presentation is enabled, [`Swap::swap_buffers()`] does nothing on all screens,
+    /// so that code using the screens (e.g. a [`Console`](crate::console::Console)) can't interfere with an external renderer.
+    /// The caller is then responsible for presenting the framebuffers, either by calling `gfxScreenSwapBuffers` itself
+    /// (e.g. after a `GX_DisplayTransfer` to the screen's framebuffer, as a `citro3d` renderer does) or via [`Gfx::present_framebuffer()`].
+    ///
+    /// Configuration changes (such as [`Screen::set_framebuffer_format()`]) are still only committed when the buffers are swapped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::Gfx;
+    /// let gfx = Gfx::new()?;
+    ///
+    /// // Let an external renderer take care of the screens.
+    /// gfx.set_manual_presentation(true);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_manual_presentation(&self, enabled: bool) {
+        MANUAL_PRESENTATION.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if manual presentation is enabled.
+    ///
+    /// See [`Gfx::set_manual_presentation()`].
+    pub fn is_manual_presentation(&self) -> bool {
+        MANUAL_PRESENTATION.load(Ordering::Relaxed)
+    }
+
+    /// Copy an externally rendered image to the current framebuffer of `screen` and present it.
+    ///
+    /// `data` must use the same layout as the framebuffer: it must be in the screen's [`FramebufferFormat`] and rotated by 90 degrees
+    /// (see [`Screen::raw_framebuffer()`]). Any data past the size of the framebuffer is ignored.
+    ///
+    /// If `screen` is the top screen and [stereoscopic 3D](TopScreen3D) is enabled, `data` must contain the image for the left eye
+    /// followed by the one for the right eye, and the screen is presented in 3D.
+    ///
+    /// # Notes
+    ///
+    /// The buffers are always swapped, regardless of [manual presentation](Gfx::set_manual_presentation) being enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `data` is smaller than the framebuffer (or both framebuffers, in 3D).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::Gfx;
+    /// let gfx = Gfx::new()?;
+    /// let mut bottom_screen = gfx.bottom_screen.borrow_mut();
+    ///
+    /// gfx.set_manual_presentation(true);
+    ///
+    /// // A black 320x240 image in the default BGR8 format.
+    /// let image = vec![0; 320 * 240 * 3];
+    ///
+    /// gfx.present_framebuffer(&mut *bottom_screen, &image)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "gfxScreenSwapBuffers")]
+    pub fn present_framebuffer<S: Screen + Swap>(&self, screen: &mut S, data: &[u8]) -> Result<()> {
+        let is_3d = screen.as_raw() == ctru_sys::GFX_TOP && unsafe { ctru_sys::gfxIs3D() };
+
+        let depth = screen.framebuffer_format().pixel_depth_bytes();
+        let framebuffer = screen.raw_framebuffer();
+        let len = framebuffer.width * framebuffer.height * depth;
+        let wanted = if is_3d { len * 2 } else { len };
+
+        if data.len() < wanted {
+            return Err(Error::BufferTooShort {
+                provided: data.len(),
+                wanted,
+            });
+        }
+
+        let right_framebuffer = is_3d.then(|| {
+            let (mut width, mut height) = (0, 0);
+            unsafe {
+                ctru_sys::gfxGetFramebuffer(
+                    ctru_sys::GFX_TOP,
+                    ctru_sys::GFX_RIGHT,
+                    &mut width,
+                    &mut height,
+                )
+            }
+        });
+        let framebuffers = std::iter::once(framebuffer.ptr).chain(right_framebuffer);
+
+        // Safety: the framebuffers returned by libctru are valid for the whole frame, and both eyes have the same size.
+        unsafe {
+            for (ptr, image) in framebuffers.zip(data.chunks_exact(len)) {
+                std::ptr::copy_nonoverlapping(image.as_ptr(), ptr, len);
+
+                let _ = ctru_sys::GSPGPU_FlushDataCache(ptr.cast(), len as u32);
+            }
+
+            ctru_sys::gfxScreenSwapBuffers(screen.as_raw(), is_3d);
+        }
+
+        Ok(())
+    }
+
+    /// Capture the images shown to the left and right eye by the top screen, returned as `(left, right)`.
+    ///
+    /// Both images are read from the framebuffers being displayed right after a vertical blank, so they always belong to the same frame.
+    /// They are copied as they are stored in memory: in the top screen's [format](Screen::framebuffer_format),
+    /// rotated by 90 degrees like the [`RawFrameBuffer`] (240 pixels per column, 400 columns).
+    /// To save a stereo picture, rotate each image and store them side by side, or as the two images of an MPO file.
+    ///
+    /// # Notes
+    ///
+    /// The framebuffers are found through the LCD registers, so the images are the ones actually shown by the console,
+    /// whether they were presented with [`Swap::swap_buffers()`] or by an external renderer.
+    /// With [double buffering](Swap::set_double_buffering) disabled, a frame being drawn while capturing may be torn.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the LCD registers can't be read.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if stereoscopic 3D isn't enabled, i.e. if no [`TopScreen3D`] is alive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gfx::{Gfx, TopScreen3D};
+    /// let gfx = Gfx::new()?;
+    ///
+    /// let top_screen = TopScreen3D::from(&gfx.top_screen);
+    ///
+    /// // Draw and present the frame...
+    ///
+    /// let (left, right) = gfx.capture_stereo()?;
+    /// assert_eq!(left.len(), right.len());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "GSPGPU_ReadHWRegs", alias = "screenshot")]
+    pub fn capture_stereo(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        assert!(
+            unsafe { ctru_sys::gfxIs3D() },
+            "stereoscopic 3D must be enabled to capture both eyes"
+        );
+
+        self.wait_for_vblank();
+
+        let mut regs = [0u32; TOP_FRAMEBUFFER_REGS_COUNT];
+        ResultCode(unsafe {
+            ctru_sys::GSPGPU_ReadHWRegs(
+                TOP_FRAMEBUFFER_REGS,
+                regs.as_mut_ptr(),
+                std::mem::size_of_val(&regs) as u8,
+            )
+        })?;
+
+        // Framebuffer A is shown to the left eye and framebuffer B to the right one.
+        // Each has two buffers (for double buffering): bit 0 of the framebuffer select register chooses which one is displayed.
+        let (left, right) = if regs[4] & 1 == 0 {
+            (regs[0], regs[11])
+        } else {
+            (regs[1], regs[12])
+        };
+        // The stride is the length of a column (240 pixels) in bytes.
+        let len = regs[10] as usize * 400;
+
+        let copy = |address: u32| -> Result<Vec<u8>> {
+            let ptr = unsafe { ctru_sys::osConvertPhysToVirt(address) }.cast::<u8>();
+
+            if ptr.is_null() {
+                return Err(Error::Other(format!(
+                    "the framebuffer at {address:#X} is not mapped in memory"
+                )));
+            }
+
+            // Safety: the displayed framebuffer holds 400 columns of the stride's length, and stays allocated while `Gfx` is.
+            unsafe {
+                // The framebuffer may have been written by the GPU, bypassing the CPU cache.
+                let _ = ctru_sys::GSPGPU_InvalidateDataCache(ptr.cast(), len as u32);
+
+                Ok(std::slice::from_raw_parts(ptr, len).to_vec())
+            }
+        };
+
+        Ok((copy(left)?, copy(right)?))
+    }
+}
+
+/// Exclusive access to the screens of a [`Gfx`] handle, which can be moved to another thread.
+///
+/// A [`Presenter`] is created with [`Gfx::presenter()`] and mutably borrows the [`Gfx`] handle, so the screens can't be accessed
+/// in any other way until it's dropped. Unlike the [`RefCell`]s holding the screens, it's [`Send`]: a render thread can draw,
+/// [flush](Flush::flush_buffers) and [swap](Swap::swap_buffers) the buffers through it, while other threads take care of the rest
+/// (e.g. reading input with [`Hid`](crate::services::hid::Hid)).
+///
+/// # Notes
+///
+/// The [`Presenter`] borrows the [`Gfx`] handle, so it can only be moved to threads which can't outlive it,
+/// such as the ones spawned by [`std::thread::scope()`].
+///
+/// Other services don't depend on the screens, so they can be used on any thread. However, the console won't show the frames
+/// drawn while the application is suspended (e.g. in the HOME Menu): the render thread should pause while [`Apt::main_loop()`](crate::services::apt::Apt::main_loop)
+/// is blocked on the main thread.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// use ctru::services::gfx::{Flush, Gfx, Swap};
+/// use ctru::services::hid::{Hid, KeyPad};
+///
+/// let mut gfx = Gfx::new()?;
+/// let mut hid = Hid::new()?;
+/// let running = AtomicBool::new(true);
+///
+/// let mut presenter = gfx.presenter();
+///
+/// std::thread::scope(|scope| {
+///     // Render thread.
+///     scope.spawn(|| {
+///         while running.load(Ordering::Relaxed) {
+///             let screen = presenter.bottom_screen();
+///             // Draw the frame...
+///             screen.flush_buffers();
+///             screen.swap_buffers();
+///
+///             presenter.wait_for_vblank();
+///         }
+///     });
+///
+///     // Input is read on the main thread.
+///     loop {
+///         hid.scan_input();
+/// #       break;
+///
+///         if hid.keys_down().contains(KeyPad::START) {
+///             break;
+///         }
+///     }
+///
+///     running.store(false, Ordering::Relaxed);
+/// });
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct Presenter<'gfx> {
+    top_screen: &'gfx mut TopScreen,
+    bottom_screen: &'gfx mut BottomScreen,
+}
+
+impl Presenter<'_> {
+    /// Returns the top screen.
+    pub fn top_screen(&mut self) -> &mut TopScreen {
+        self.top_screen
+    }
+
+    /// Returns the bottom screen.
+    pub fn bottom_screen(&mut self) -> &mut BottomScreen {
+        self.bottom_screen
+    }
+
+    /// Waits for the vertical blank event. See [`Gfx::wait_for_vblank()`].
+    pub fn wait_for_vblank(&self) {
+        gspgpu::wait_for_event(gspgpu::Event::VBlank0, true);
+    }
+}
+
+/// Approximate time between two vertical blanks of the LCD screens (which refresh at about 59.83Hz).
+const VBLANK_PERIOD: Duration = Duration::from_micros(16_713);
+
+/// Frame pacing helper which locks the main loop to a fraction of the screens' refresh rate.
+///
+/// The limiter waits for the vertical blank event, so frames are always synchronized with the screens' refresh.
+/// A target of 60 fps waits for every vertical blank, 30 fps for every second one, 20 fps for every third one, and so on.
+///
+/// # Notes
+///
+/// If a frame takes longer than its budget, [`FrameLimiter::wait()`] only waits for the next vertical blank
+/// instead of trying to catch up, so a slow frame doesn't make the following one wait longer.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::gfx::{FrameLimiter, Gfx};
+/// let apt = Apt::new()?;
+/// let gfx = Gfx::new()?;
+///
+/// // Run at a stable 30 fps to save some battery.
+/// let mut limiter = FrameLimiter::new(30);
+///
+/// while apt.main_loop() {
+///     // Main program logic
+///
+///     limiter.wait(&gfx);
+/// #   break;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameLimiter {
+    interval: u32,
+    last_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Create a limiter targeting (approximately) the specified frame rate.
+    ///
+    /// The target is rounded to the closest frame rate reachable by waiting for a whole number of vertical blanks
+    /// (60, 30, 20, 15, 12, 10, ...). Values above 60 are capped to 60.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `target_fps` is 0.
+    pub fn new(target_fps: u32) -> Self {
+        assert!(target_fps > 0, "the target frame rate must be at least 1");
+
+        let interval = ((60 + target_fps / 2) / target_fps).max(1);
+
+        Self::with_interval(interval)
+    }
+
+    /// Create a limiter which waits for `interval` vertical blanks each frame.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `interval` is 0.
+    pub fn with_interval(interval: u32) -> Self {
+        assert!(
+            interval > 0,
+            "the interval must be at least 1 vertical blank"
+        );
+
+        Self {
+            interval,
+            last_frame: None,
+        }
+    }
+
+    /// Returns the number of vertical blanks each frame lasts.
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Returns the nominal frame rate targeted by the limiter.
+    pub fn target_fps(&self) -> u32 {
+        60 / self.interval
+    }
+
+    /// Wait until the end of the current frame.
+    ///
+    /// This function always waits for at least one vertical blank, and then keeps waiting
+    /// until the frame budget (counted from the end of the previous call) is used up.
+    pub fn wait(&mut self, gfx: &Gfx) {
+        let budget = VBLANK_PERIOD * self.interval;
+
+        loop {
+            gfx.wait_for_vblank();
+
+            let now = Instant::now();
+
+            // Vertical blanks don't happen at exact times, so half a period is given as tolerance.
+            let done = self
+                .last_frame
+                .map_or(true, |last| now + VBLANK_PERIOD / 2 >= last + budget);
+
+            if done {
+                self.last_frame = Some(now);
+                break;
+            }
+        }
+    }
 }
 
 impl TopScreen3D<'_> {
@@ -1,5 +1,7 @@
 //! GSPGPU service
 
+use crate::error::{Error, Result};
+
 /// GSPGPU events that can be awaited.
 #[doc(alias = "GSPGPU_Event")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -38,6 +40,51 @@ pub enum FramebufferFormat {
     Rgba4 = ctru_sys::GSP_RGBA4_OES,
 }
 
+/// Color formats of the GPU's render targets (color buffers).
+///
+/// Every format has a matching [`FramebufferFormat`] with the same pixel size, which is the format the screen must use
+/// to show the rendered image after it's copied to the framebuffer with a display transfer.
+/// Use [`Screen::set_render_target_format()`](crate::services::gfx::Screen::set_render_target_format) to configure a screen accordingly.
+#[doc(alias = "GPU_COLORBUF")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum GpuColorFormat {
+    /// RGBA8. 4 bytes per pixel, displayed as [`FramebufferFormat::Rgba8`].
+    Rgba8 = ctru_sys::GPU_RB_RGBA8,
+    /// RGB8. 3 bytes per pixel, displayed as [`FramebufferFormat::Bgr8`].
+    Rgb8 = ctru_sys::GPU_RB_RGB8,
+    /// RGBA5551. 2 bytes per pixel, displayed as [`FramebufferFormat::Rgb5A1`].
+    Rgba5551 = ctru_sys::GPU_RB_RGBA5551,
+    /// RGB565. 2 bytes per pixel, displayed as [`FramebufferFormat::Rgb565`].
+    Rgb565 = ctru_sys::GPU_RB_RGB565,
+    /// RGBA4. 2 bytes per pixel, displayed as [`FramebufferFormat::Rgba4`].
+    Rgba4 = ctru_sys::GPU_RB_RGBA4,
+}
+
+impl From<GpuColorFormat> for FramebufferFormat {
+    fn from(format: GpuColorFormat) -> Self {
+        match format {
+            GpuColorFormat::Rgba8 => Self::Rgba8,
+            GpuColorFormat::Rgb8 => Self::Bgr8,
+            GpuColorFormat::Rgba5551 => Self::Rgb5A1,
+            GpuColorFormat::Rgb565 => Self::Rgb565,
+            GpuColorFormat::Rgba4 => Self::Rgba4,
+        }
+    }
+}
+
+impl From<FramebufferFormat> for GpuColorFormat {
+    fn from(format: FramebufferFormat) -> Self {
+        match format {
+            FramebufferFormat::Rgba8 => Self::Rgba8,
+            FramebufferFormat::Bgr8 => Self::Rgb8,
+            FramebufferFormat::Rgb5A1 => Self::Rgba5551,
+            FramebufferFormat::Rgb565 => Self::Rgb565,
+            FramebufferFormat::Rgba4 => Self::Rgba4,
+        }
+    }
+}
+
 impl FramebufferFormat {
     /// Returns the number of bytes per pixel used by this FramebufferFormat
     pub fn pixel_depth_bytes(&self) -> usize {
@@ -50,6 +97,243 @@ impl FramebufferFormat {
             Rgba4 => 2,
         }
     }
+
+    /// Writes `color` into `dst` using the packed representation of this format.
+    ///
+    /// `dst` must be exactly [`FramebufferFormat::pixel_depth_bytes()`] long.
+    /// All formats are stored in little-endian order, which means the colour channels appear "reversed" in memory.
+    pub(crate) fn write_color(&self, color: Color, dst: &mut [u8]) {
+        let Color { r, g, b, a } = color;
+        let (r, g, b, a) = (u16::from(r), u16::from(g), u16::from(b), u16::from(a));
+
+        match *self {
+            Self::Rgba8 => dst.copy_from_slice(&[color.a, color.b, color.g, color.r]),
+            Self::Bgr8 => dst.copy_from_slice(&[color.b, color.g, color.r]),
+            Self::Rgb565 => {
+                let packed = (r >> 3) << 11 | (g >> 2) << 5 | (b >> 3);
+                dst.copy_from_slice(&packed.to_le_bytes());
+            }
+            Self::Rgb5A1 => {
+                let packed = (r >> 3) << 11 | (g >> 3) << 6 | (b >> 3) << 1 | (a >> 7);
+                dst.copy_from_slice(&packed.to_le_bytes());
+            }
+            Self::Rgba4 => {
+                let packed = (r >> 4) << 12 | (g >> 4) << 8 | (b >> 4) << 4 | (a >> 4);
+                dst.copy_from_slice(&packed.to_le_bytes());
+            }
+        }
+    }
+
+    /// Reads a colour from `src`, stored in the packed representation of this format.
+    ///
+    /// `src` must be exactly [`FramebufferFormat::pixel_depth_bytes()`] long.
+    /// Channels with less than 8 bits are scaled to the full range, and formats without an alpha channel produce opaque colours.
+    pub(crate) fn read_color(&self, src: &[u8]) -> Color {
+        match *self {
+            Self::Rgba8 => Color::with_alpha(src[3], src[2], src[1], src[0]),
+            Self::Bgr8 => Color::new(src[2], src[1], src[0]),
+            Self::Rgb565 => {
+                let [r, g, b] = rgb565_to_rgb8(u16::from_le_bytes([src[0], src[1]]));
+                Color::new(r, g, b)
+            }
+            Self::Rgb5A1 => {
+                let packed = u16::from_le_bytes([src[0], src[1]]);
+                Color::with_alpha(
+                    expand_5_bits(packed >> 11),
+                    expand_5_bits(packed >> 6),
+                    expand_5_bits(packed >> 1),
+                    if packed & 1 != 0 { 255 } else { 0 },
+                )
+            }
+            Self::Rgba4 => {
+                let packed = u16::from_le_bytes([src[0], src[1]]);
+                let expand = |channel: u16| (channel & 0xF) as u8 * 0x11;
+                Color::with_alpha(
+                    expand(packed >> 12),
+                    expand(packed >> 8),
+                    expand(packed >> 4),
+                    expand(packed),
+                )
+            }
+        }
+    }
+}
+
+/// Convert an image from one [`FramebufferFormat`] to another.
+///
+/// `src` is read as a sequence of pixels in `src_format` (any trailing bytes not making up a whole pixel are ignored),
+/// and the converted pixels are written at the start of `dst` in `dst_format`.
+/// The pixels are converted one by one, so this works with any image layout (including the rotated layout of the framebuffers).
+///
+/// # Notes
+///
+/// The formats describe the layout of the pixels in memory, which is little-endian: for example, [`FramebufferFormat::Bgr8`]
+/// stores the blue channel first. Most image decoders output the red channel first instead, so have a look at
+/// [`rgb8_to_rgb565()`] and similar functions to convert images with a different channel order.
+///
+/// Converting to a format with less precision discards the lowest bits of each channel.
+///
+/// # Errors
+///
+/// This function will return an error if `dst` is too short to hold the converted pixels.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::gspgpu::{self, FramebufferFormat};
+///
+/// // Two pixels (white and red) in BGR8.
+/// let src = [0xFF, 0xFF, 0xFF, 0x00, 0x00, 0xFF];
+/// let mut dst = [0; 4];
+///
+/// gspgpu::convert(&src, FramebufferFormat::Bgr8, &mut dst, FramebufferFormat::Rgb565)?;
+///
+/// assert_eq!(dst, [0xFF, 0xFF, 0x00, 0xF8]);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub fn convert(
+    src: &[u8],
+    src_format: FramebufferFormat,
+    dst: &mut [u8],
+    dst_format: FramebufferFormat,
+) -> Result<()> {
+    let src_depth = src_format.pixel_depth_bytes();
+    let dst_depth = dst_format.pixel_depth_bytes();
+    let pixels = src.len() / src_depth;
+
+    if dst.len() < pixels * dst_depth {
+        return Err(Error::BufferTooShort {
+            provided: dst.len(),
+            wanted: pixels * dst_depth,
+        });
+    }
+
+    if src_format == dst_format {
+        let len = pixels * src_depth;
+        dst[..len].copy_from_slice(&src[..len]);
+
+        return Ok(());
+    }
+
+    for (src_pixel, dst_pixel) in src
+        .chunks_exact(src_depth)
+        .zip(dst.chunks_exact_mut(dst_depth))
+    {
+        dst_format.write_color(src_format.read_color(src_pixel), dst_pixel);
+    }
+
+    Ok(())
+}
+
+/// Convert an RGB565 pixel to its `[r, g, b]` channels, scaled to 8 bits each.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::services::gspgpu::rgb565_to_rgb8;
+///
+/// assert_eq!(rgb565_to_rgb8(0xF800), [255, 0, 0]);
+/// assert_eq!(rgb565_to_rgb8(0xFFFF), [255, 255, 255]);
+/// ```
+pub fn rgb565_to_rgb8(pixel: u16) -> [u8; 3] {
+    let g = (pixel >> 5) & 0x3F;
+
+    [
+        expand_5_bits(pixel >> 11),
+        ((g << 2) | (g >> 4)) as u8,
+        expand_5_bits(pixel),
+    ]
+}
+
+/// Convert `[r, g, b]` channels to an RGB565 pixel, discarding the lowest bits of each channel.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// use ctru::services::gspgpu::rgb8_to_rgb565;
+///
+/// // Convert a decoded image (with the red channel first) for a screen using `FramebufferFormat::Rgb565`.
+/// let image = [255, 0, 0, 0, 0, 255];
+///
+/// let pixels: Vec<u16> = image
+///     .chunks_exact(3)
+///     .map(|pixel| rgb8_to_rgb565([pixel[0], pixel[1], pixel[2]]))
+///     .collect();
+///
+/// assert_eq!(pixels, [0xF800, 0x001F]);
+/// ```
+pub fn rgb8_to_rgb565([r, g, b]: [u8; 3]) -> u16 {
+    let (r, g, b) = (u16::from(r), u16::from(g), u16::from(b));
+
+    (r >> 3) << 11 | (g >> 2) << 5 | (b >> 3)
+}
+
+/// Convert `[r, g, b, a]` channels to an RGB565 pixel, discarding the alpha channel.
+pub fn rgba8_to_rgb565([r, g, b, _]: [u8; 4]) -> u16 {
+    rgb8_to_rgb565([r, g, b])
+}
+
+/// Convert an RGB565 pixel to opaque `[r, g, b, a]` channels.
+pub fn rgb565_to_rgba8(pixel: u16) -> [u8; 4] {
+    let [r, g, b] = rgb565_to_rgb8(pixel);
+
+    [r, g, b, 255]
+}
+
+/// Scale the lowest 5 bits of `channel` to 8 bits.
+fn expand_5_bits(channel: u16) -> u8 {
+    let channel = channel & 0x1F;
+
+    ((channel << 3) | (channel >> 2)) as u8
+}
+
+/// A colour with 8 bits per channel.
+///
+/// Colours get converted to the [`FramebufferFormat`] of the screen they are drawn on,
+/// which may lose precision (or the alpha channel entirely).
+///
+/// See [`PixelWriter`](crate::services::gfx::PixelWriter) to learn how to use this.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red channel.
+    pub r: u8,
+    /// Green channel.
+    pub g: u8,
+    /// Blue channel.
+    pub b: u8,
+    /// Alpha channel.
+    pub a: u8,
+}
+
+impl Color {
+    /// Opaque black.
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    /// Opaque white.
+    pub const WHITE: Self = Self::new(255, 255, 255);
+    /// Opaque red.
+    pub const RED: Self = Self::new(255, 0, 0);
+    /// Opaque green.
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    /// Opaque blue.
+    pub const BLUE: Self = Self::new(0, 0, 255);
+
+    /// Create a new opaque colour.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self::with_alpha(r, g, b, 255)
+    }
+
+    /// Create a new colour with the specified alpha channel.
+    pub const fn with_alpha(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
 }
 
 /// Waits for a GSPGPU event to occur.
@@ -78,3 +362,142 @@ impl From<ctru_sys::GSPGPU_FramebufferFormat> for FramebufferFormat {
 
 from_impl!(FramebufferFormat, ctru_sys::GSPGPU_FramebufferFormat);
 from_impl!(Event, ctru_sys::GSPGPU_Event);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKED_FORMATS: [FramebufferFormat; 3] = [
+        FramebufferFormat::Rgb565,
+        FramebufferFormat::Rgb5A1,
+        FramebufferFormat::Rgba4,
+    ];
+
+    #[test]
+    fn rgb565_round_trip() {
+        for pixel in 0..=u16::MAX {
+            assert_eq!(rgb8_to_rgb565(rgb565_to_rgb8(pixel)), pixel);
+            assert_eq!(rgba8_to_rgb565(rgb565_to_rgba8(pixel)), pixel);
+        }
+    }
+
+    #[test]
+    fn rgb565_expands_to_full_range() {
+        assert_eq!(rgb565_to_rgb8(0x0000), [0, 0, 0]);
+        assert_eq!(rgb565_to_rgb8(0xFFFF), [255, 255, 255]);
+        assert_eq!(rgb565_to_rgb8(0x07E0), [0, 255, 0]);
+        assert_eq!(rgb565_to_rgba8(0x001F), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn byte_order() {
+        let color = Color::with_alpha(0x11, 0x22, 0x33, 0x44);
+
+        let mut rgba8 = [0; 4];
+        FramebufferFormat::Rgba8.write_color(color, &mut rgba8);
+        assert_eq!(rgba8, [0x44, 0x33, 0x22, 0x11]);
+        assert_eq!(FramebufferFormat::Rgba8.read_color(&rgba8), color);
+
+        let mut bgr8 = [0; 3];
+        FramebufferFormat::Bgr8.write_color(color, &mut bgr8);
+        assert_eq!(bgr8, [0x33, 0x22, 0x11]);
+        assert_eq!(
+            FramebufferFormat::Bgr8.read_color(&bgr8),
+            Color::new(0x11, 0x22, 0x33)
+        );
+
+        let mut rgb565 = [0; 2];
+        FramebufferFormat::Rgb565.write_color(Color::RED, &mut rgb565);
+        assert_eq!(rgb565, 0xF800_u16.to_le_bytes());
+    }
+
+    #[test]
+    fn packed_formats_round_trip() {
+        for format in PACKED_FORMATS {
+            for pixel in 0..=u16::MAX {
+                let bytes = pixel.to_le_bytes();
+                let mut written = [0; 2];
+
+                format.write_color(format.read_color(&bytes), &mut written);
+                assert_eq!(written, bytes, "{format:?} pixel {pixel:#06X}");
+            }
+        }
+    }
+
+    #[test]
+    fn packed_alpha() {
+        let mut pixel = [0; 2];
+
+        FramebufferFormat::Rgb5A1.write_color(Color::with_alpha(255, 255, 255, 127), &mut pixel);
+        assert_eq!(FramebufferFormat::Rgb5A1.read_color(&pixel).a, 0);
+        FramebufferFormat::Rgb5A1.write_color(Color::with_alpha(255, 255, 255, 128), &mut pixel);
+        assert_eq!(FramebufferFormat::Rgb5A1.read_color(&pixel).a, 255);
+
+        FramebufferFormat::Rgba4.write_color(Color::with_alpha(0, 0, 0, 0x80), &mut pixel);
+        assert_eq!(FramebufferFormat::Rgba4.read_color(&pixel).a, 0x88);
+
+        FramebufferFormat::Rgb565.write_color(Color::with_alpha(0, 0, 0, 0), &mut pixel);
+        assert_eq!(FramebufferFormat::Rgb565.read_color(&pixel).a, 255);
+    }
+
+    #[test]
+    fn convert_pixels() {
+        let src = [0x00, 0xF8, 0x1F, 0x00];
+        let mut dst = [0; 6];
+
+        convert(
+            &src,
+            FramebufferFormat::Rgb565,
+            &mut dst,
+            FramebufferFormat::Bgr8,
+        )
+        .unwrap();
+        assert_eq!(dst, [0, 0, 255, 255, 0, 0]);
+
+        let mut copy = [0; 5];
+        convert(
+            &src,
+            FramebufferFormat::Rgb565,
+            &mut copy,
+            FramebufferFormat::Rgb565,
+        )
+        .unwrap();
+        assert_eq!(copy, [0x00, 0xF8, 0x1F, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn convert_ignores_trailing_bytes() {
+        let src = [0xFF; 5];
+        let mut dst = [0; 8];
+
+        convert(
+            &src,
+            FramebufferFormat::Bgr8,
+            &mut dst,
+            FramebufferFormat::Rgba8,
+        )
+        .unwrap();
+        assert_eq!(dst, [255, 255, 255, 255, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn convert_buffer_too_short() {
+        let src = [0; 8];
+        let mut dst = [0; 5];
+
+        let result = convert(
+            &src,
+            FramebufferFormat::Rgba8,
+            &mut dst,
+            FramebufferFormat::Bgr8,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::BufferTooShort {
+                provided: 5,
+                wanted: 6
+            })
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! GSP LCD service.
+//!
+//! This service controls the LCD screens themselves, independently of what's drawn on them (which is handled by [GFX](crate::services::gfx)).
+//! It can be used to turn off the screens' backlights, e.g. to save battery while the application only plays audio.
+//!
+//! See also <https://www.3dbrew.org/wiki/GSP_Services>
+#![doc(alias = "backlight")]
+#![doc(alias = "lcd")]
+
+use crate::error::ResultCode;
+
+/// LCD screen targeted by a [`GspLcd`] command.
+#[doc(alias = "GSPLCD_Screens")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LcdScreen {
+    /// Top screen.
+    Top = ctru_sys::GSPLCD_SCREEN_TOP,
+    /// Bottom screen.
+    Bottom = ctru_sys::GSPLCD_SCREEN_BOTTOM,
+    /// Both screens.
+    Both = ctru_sys::GSPLCD_SCREEN_BOTH,
+}
+
+/// Handle to the GSP LCD service.
+pub struct GspLcd {
+    /// Screens whose backlight was turned off with this handle.
+    powered_off: u32,
+}
+
+impl GspLcd {
+    /// Initialize a new service handle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gsplcd::GspLcd;
+    ///
+    /// let lcd = GspLcd::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "gspLcdInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::gspLcdInit() })?;
+
+        Ok(Self { powered_off: 0 })
+    }
+
+    /// Turn off the backlight of the specified screen.
+    ///
+    /// # Notes
+    ///
+    /// Turning off the backlight is different from setting the screen's brightness to its lowest level, which still keeps the backlight lit:
+    /// with the backlight off, the screen is completely dark and uses much less power. The contents of the framebuffers aren't affected,
+    /// and they are shown again as soon as the backlight is turned back on.
+    ///
+    /// Everything else keeps working while the screens are off: input (including the touch screen) is still read by [`Hid`](crate::services::hid::Hid)
+    /// and audio keeps playing, so the user needs a way to turn the screens back on (e.g. pressing any button).
+    ///
+    /// Backlights turned off with this handle are turned back on when it's dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gsplcd::{GspLcd, LcdScreen};
+    /// use ctru::services::hid::Hid;
+    /// let mut lcd = GspLcd::new()?;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // Screens off while the music plays.
+    /// lcd.power_off(LcdScreen::Both)?;
+    ///
+    /// // Back on as soon as the user presses something.
+    /// # if false {
+    /// hid.wait_for_key();
+    /// # }
+    /// lcd.power_on(LcdScreen::Both)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "GSPLCD_PowerOffBacklight")]
+    pub fn power_off(&mut self, screen: LcdScreen) -> crate::Result<()> {
+        ResultCode(unsafe { ctru_sys::GSPLCD_PowerOffBacklight(screen as u32) })?;
+
+        self.powered_off |= screen as u32;
+
+        Ok(())
+    }
+
+    /// Turn on the backlight of the specified screen.
+    ///
+    /// See [`GspLcd::power_off()`].
+    #[doc(alias = "GSPLCD_PowerOnBacklight")]
+    pub fn power_on(&mut self, screen: LcdScreen) -> crate::Result<()> {
+        ResultCode(unsafe { ctru_sys::GSPLCD_PowerOnBacklight(screen as u32) })?;
+
+        self.powered_off &= !(screen as u32);
+
+        Ok(())
+    }
+}
+
+impl Drop for GspLcd {
+    #[doc(alias = "gspLcdExit")]
+    fn drop(&mut self) {
+        unsafe {
+            // Don't leave the user with a dark screen.
+            if self.powered_off != 0 {
+                let _ = ctru_sys::GSPLCD_PowerOnBacklight(self.powered_off);
+            }
+
+            ctru_sys::gspLcdExit();
+        }
+    }
+}
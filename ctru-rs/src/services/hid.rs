@@ -1,15 +1,32 @@
 //! Human Interface Device service.
 //!
 //! The HID service provides read access to user input such as [button presses](Hid::keys_down), [touch screen presses](Hid::touch_position),
-//! and [circle pad information](Hid::circlepad_position). It also provides information from the sound volume slider, the accelerometer, and the gyroscope.
-// TODO: Implement volume slider, accelerometer and gyroscope + any other missing functionality.
+//! and [circle pad information](Hid::circlepad_position). It also provides information from the sound volume slider, the 3D slider, the accelerometer, and the gyroscope.
+//!
+//! The additional controls of the New 3DS models (and of the Circle Pad Pro) are handled by the `ir:rst` service,
+//! which can be enabled via [`Hid::enable_extra_controls()`]. The accelerometer and the gyroscope must be enabled as well before use.
+//! [`HidBuilder`] can be used to configure all optional features at once.
+//!
+//! For testing purposes, the input read by [`Hid::scan_input()`] can be [recorded](Hid::set_recorder) and later [replayed](Hid::set_replay_source)
+//! as a stream of [`InputFrame`]s.
+//!
+//! Common touch screen gestures (taps, drags and swipes) can be recognized with a [`TouchGesture`] detector.
+//!
+//! To support rebindable controls, physical keys can be mapped to logical actions with an [`ActionMap`].
+// TODO: Implement volume slider + any other missing functionality.
 #![doc(alias = "input")]
 #![doc(alias = "controller")]
 #![doc(alias = "gamepad")]
 
-use crate::error::ResultCode;
+use crate::error::{Error, ResultCode};
+use crate::services::cfgu::TouchCalibration;
+use crate::services::ir_user::CirclePadProInput;
 use bitflags::bitflags;
 
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
 bitflags! {
     /// A set of flags corresponding to the button and directional pad inputs present on the 3DS.
     #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
@@ -39,18 +56,30 @@ bitflags! {
         /// Y button.
         const Y             = ctru_sys::KEY_Y;
         /// ZL button.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const ZL            = ctru_sys::KEY_ZL;
         /// ZR button.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const ZR            = ctru_sys::KEY_ZR;
         /// Touchscreen.
         const TOUCH         = ctru_sys::KEY_TOUCH;
         /// C-Stick Right.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const CSTICK_RIGHT  = ctru_sys::KEY_CSTICK_RIGHT;
         /// C-Stick Left.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const CSTICK_LEFT   = ctru_sys::KEY_CSTICK_LEFT;
         /// C-Stick Up.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const CSTICK_UP     = ctru_sys::KEY_CSTICK_UP;
         /// C-Stick Down.
+        ///
+        /// Requires [`Hid::enable_extra_controls()`].
         const CSTICK_DOWN   = ctru_sys::KEY_CSTICK_DOWN;
         /// CirclePad Right.
         const CPAD_RIGHT    = ctru_sys::KEY_CPAD_RIGHT;
@@ -74,8 +103,331 @@ bitflags! {
     }
 }
 
+/// Hardware providing the additional controls (ZL and ZR buttons and C-Stick).
+///
+/// See [`Hid::extra_input_source()`] to learn how to retrieve this.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExtraInputSource {
+    /// The controls built into the New 3DS models.
+    New3ds,
+    /// The Circle Pad Pro accessory, on Old 3DS models.
+    CirclePadPro,
+}
+
+/// Keys reported by the additional controls (see [`ExtraInputSource`]).
+const EXTRA_KEYS: KeyPad = KeyPad::ZL
+    .union(KeyPad::ZR)
+    .union(KeyPad::CSTICK_RIGHT)
+    .union(KeyPad::CSTICK_LEFT)
+    .union(KeyPad::CSTICK_UP)
+    .union(KeyPad::CSTICK_DOWN);
+
+/// Index of the word holding the raw data of the latest touch sample in the HID shared memory.
+const TOUCH_RAW_DATA_WORD: usize = 42 + 6;
+
+/// Size in pixels of the touch screen (the bottom screen).
+const TOUCH_SCREEN_SIZE: (u16, u16) = (320, 240);
+
+/// Distance in pixels from the edges of the touch screen within which filtered samples are clamped instead of discarded.
+const TOUCH_CLAMP_MARGIN: u16 = 16;
+
+/// Default number of frames a key must be held before it starts repeating. See [`Hid::set_key_repeat()`].
+const DEFAULT_KEY_REPEAT_DELAY: u32 = 30;
+
+/// Default number of frames between two repeats of a held key. See [`Hid::set_key_repeat()`].
+const DEFAULT_KEY_REPEAT_INTERVAL: u32 = 6;
+
+/// Approximate distance from the center reached by the circle pad when fully tilted.
+const CIRCLEPAD_MAX: f32 = 156.0;
+
+/// Response curve applied to the circle pad by [`Hid::circlepad_vector()`].
+///
+/// The curve maps the distance of the circle pad from the center (after removing the deadzone)
+/// from the range 0.0–1.0 to the same range. Curves with an exponent greater than 1 give finer control near the center,
+/// while exponents smaller than 1 make small movements more sensitive.
+///
+/// See [`Hid::set_circlepad_curve()`] to learn how to use this.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CirclePadCurve {
+    /// The output is proportional to the tilt of the circle pad.
+    #[default]
+    Linear,
+    /// The output is the square of the tilt of the circle pad.
+    Quadratic,
+    /// The output is the tilt of the circle pad raised to a custom (positive) exponent.
+    Exponent(f32),
+}
+
+impl CirclePadCurve {
+    /// Apply the curve to a distance in the range 0.0–1.0.
+    fn apply(self, distance: f32) -> f32 {
+        match self {
+            Self::Linear => distance,
+            Self::Quadratic => distance * distance,
+            Self::Exponent(exponent) => distance.powf(exponent),
+        }
+    }
+}
+
+/// State of the basic inputs during a single frame, as read by [`Hid::scan_input()`].
+///
+/// # Binary format
+///
+/// Input recordings (see [`Hid::set_recorder()`] and [`Hid::set_replay_source()`]) are a plain sequence of frames, one per call to [`Hid::scan_input()`],
+/// without any header. Each frame is [`InputFrame::SIZE`] bytes long, with all values stored in little-endian order:
+///
+/// | Offset | Type  | Value                              |
+/// |--------|-------|------------------------------------|
+/// | 0x0    | `u32` | Bits of the held [`KeyPad`] keys   |
+/// | 0x4    | `u16` | Touch position (x)                 |
+/// | 0x6    | `u16` | Touch position (y)                 |
+/// | 0x8    | `i16` | Circle pad position (x)            |
+/// | 0xA    | `i16` | Circle pad position (y)            |
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputFrame {
+    /// Keys held down during the frame.
+    pub keys_held: KeyPad,
+    /// Touch position in pixels (x, y).
+    pub touch_position: (u16, u16),
+    /// Circle pad position in relative (x, y).
+    pub circlepad_position: (i16, i16),
+}
+
+/// Scripted sequence of [`InputFrame`]s, to feed predetermined input to a [`Hid`] handle.
+///
+/// This is meant for the crate's own tests (e.g. of menu navigation), which run without a user on Citra in the CI pipeline,
+/// so it is only available under `cfg(test)`. The script is built frame by frame and handed over to [`Hid::set_replay_source()`]:
+/// each call to [`Hid::scan_input()`] then consumes one frame, and the real input is used again once the script is over.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct InputScript {
+    data: io::Cursor<Vec<u8>>,
+}
+
+/// Direction of a [`GestureEvent::Swipe`], as seen by the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    /// Towards the top of the screen.
+    Up,
+    /// Towards the bottom of the screen.
+    Down,
+    /// Towards the left side of the screen.
+    Left,
+    /// Towards the right side of the screen.
+    Right,
+}
+
+/// Touch screen gesture recognized by a [`TouchGesture`] detector.
+///
+/// All positions are in pixels, with (0, 0) being the top left corner of the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GestureEvent {
+    /// The screen was touched and released quickly without moving.
+    Tap {
+        /// Position of the tap.
+        position: (u16, u16),
+    },
+    /// The screen was tapped twice in quick succession, in about the same position.
+    ///
+    /// The first tap is still reported as a [`GestureEvent::Tap`].
+    DoubleTap {
+        /// Position of the second tap.
+        position: (u16, u16),
+    },
+    /// The touch moved far enough from where it started to be considered a drag.
+    DragStart {
+        /// Position where the touch started.
+        start: (u16, u16),
+        /// Current position.
+        position: (u16, u16),
+    },
+    /// The touch moved during a drag.
+    DragMove {
+        /// Current position.
+        position: (u16, u16),
+        /// Movement since the previous frame.
+        delta: (i16, i16),
+    },
+    /// The touch was released at the end of a (slow) drag.
+    DragEnd {
+        /// Last touched position.
+        position: (u16, u16),
+    },
+    /// The touch was released at the end of a fast and long drag.
+    ///
+    /// Swipes end a drag in place of [`GestureEvent::DragEnd`].
+    Swipe(SwipeDirection),
+}
+
+/// Detector of touch screen gestures.
+///
+/// The detector must be updated once per frame (after [`Hid::scan_input()`]) with [`TouchGesture::update()`],
+/// which returns the gesture recognized during that frame, if any. All durations are expressed in frames.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::hid::{GestureEvent, Hid, SwipeDirection, TouchGesture};
+/// let apt = Apt::new()?;
+/// let mut hid = Hid::new()?;
+///
+/// let mut gestures = TouchGesture::new();
+/// let mut scroll = 0i32;
+///
+/// while apt.main_loop() {
+///     hid.scan_input();
+///
+///     match gestures.update(&hid) {
+///         Some(GestureEvent::DragMove { delta: (_, dy), .. }) => scroll += i32::from(dy),
+///         Some(GestureEvent::Swipe(SwipeDirection::Up)) => scroll -= 240,
+///         Some(GestureEvent::Swipe(SwipeDirection::Down)) => scroll += 240,
+///         Some(GestureEvent::Tap { position }) => println!("Tapped at {position:?}"),
+///         _ => (),
+///     }
+/// #   break;
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TouchGesture {
+    drag_threshold: u16,
+    tap_max_frames: u32,
+    double_tap_max_frames: u32,
+    swipe_min_distance: u16,
+    swipe_max_frames: u32,
+
+    frame: u32,
+    touch: Option<TouchState>,
+    last_tap: Option<(u32, (u16, u16))>,
+}
+
+/// State of an ongoing touch.
+#[derive(Clone, Copy, Debug)]
+struct TouchState {
+    start: (u16, u16),
+    start_frame: u32,
+    position: (u16, u16),
+    is_dragging: bool,
+}
+
+/// Mapping between logical actions and the keys bound to them.
+///
+/// The actions are usually represented by a user-defined enum. Each action can be bound to any number of keys,
+/// and it is considered active when any of them is.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::hid::{ActionMap, Hid, KeyPad};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// enum Action {
+///     Jump,
+///     Shoot,
+/// }
+///
+/// let mut hid = Hid::new()?;
+///
+/// let mut actions = ActionMap::new();
+/// actions.bind(Action::Jump, KeyPad::A);
+/// actions.bind(Action::Shoot, KeyPad::B | KeyPad::ZR);
+///
+/// // The player remapped the jump action in the settings.
+/// actions.set_binding(Action::Jump, KeyPad::X);
+///
+/// hid.scan_input();
+///
+/// if actions.is_action_down(&hid, Action::Jump) {
+///     println!("Jump!");
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ActionMap<A> {
+    bindings: Vec<(A, KeyPad)>,
+}
+
+/// Input source of the console, reporting its own buttons and stick. See [`Hid::controller()`] to learn how to retrieve this.
+///
+/// The view reads the state scanned by the [`Hid`] handle it borrows, so [`Hid::scan_input()`] must still be called on every frame.
+#[derive(Clone, Copy)]
+pub struct Controller<'hid> {
+    hid: &'hid Hid,
+    slot: usize,
+}
+
+/// Set while the additional controls are read by either `ir:rst` or `ir:USER`, which can't be used at the same time.
+static EXTRA_CONTROLS_IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// Exclusive access to the additional controls, released when dropped.
+///
+/// Held by [`Hid`] once [`Hid::enable_extra_controls()`] is called, and by [`CirclePadPro`](crate::services::ir_user::CirclePadPro).
+pub(crate) struct ExtraControlsLock(());
+
+impl ExtraControlsLock {
+    /// Acquire the additional controls, returning [`Error::ServiceAlreadyActive`] if they are already in use.
+    pub(crate) fn acquire() -> crate::Result<Self> {
+        if EXTRA_CONTROLS_IN_USE.swap(true, Ordering::AcqRel) {
+            Err(Error::ServiceAlreadyActive)
+        } else {
+            Ok(Self(()))
+        }
+    }
+}
+
+impl Drop for ExtraControlsLock {
+    fn drop(&mut self) {
+        EXTRA_CONTROLS_IN_USE.store(false, Ordering::Release);
+    }
+}
+
+/// Source of replayed input frames.
+struct Replay {
+    source: Box<dyn Read + Send>,
+    frame: InputFrame,
+    keys_held_previous: KeyPad,
+}
+
 /// Handle to the HID service.
-pub struct Hid(());
+pub struct Hid {
+    extra_controls: bool,
+    extra_controls_lock: Option<ExtraControlsLock>,
+    extra_input_source: Option<ExtraInputSource>,
+    accelerometer: bool,
+    gyroscope: bool,
+    extra_keys_held: u32,
+    extra_keys_held_previous: u32,
+    /// Stick position of the Circle Pad Pro, when read through `ir:USER`. See [`Hid::set_circle_pad_pro_input()`].
+    circle_pad_pro_stick: Option<(i16, i16)>,
+    replay: Option<Replay>,
+    recorder: Option<Box<dyn Write + Send>>,
+    circlepad_deadzone: f32,
+    circlepad_curve: CirclePadCurve,
+    last_touch_position: Option<(u16, u16)>,
+    touch_filtering: bool,
+    /// Number of consecutive scanned frames each key (indexed by its bit) has been held for.
+    held_frames: [u32; 32],
+    /// Frames before the first repeat and between the following ones. See [`Hid::set_key_repeat()`].
+    key_repeat: (u32, u32),
+    circlepad_as_dpad: Option<f32>,
+    /// D-Pad keys synthesized from the circle pad during the current and the previous frame.
+    emulated_dpad: KeyPad,
+    emulated_dpad_previous: KeyPad,
+}
 
 impl Hid {
     /// Initialize a new service handle.
@@ -103,14 +455,31 @@ impl Hid {
     pub fn new() -> crate::Result<Hid> {
         unsafe {
             ResultCode(ctru_sys::hidInit())?;
-            Ok(Hid(()))
+            Ok(Hid {
+                extra_controls: false,
+                extra_controls_lock: None,
+                extra_input_source: None,
+                accelerometer: false,
+                gyroscope: false,
+                extra_keys_held: 0,
+                extra_keys_held_previous: 0,
+                circle_pad_pro_stick: None,
+                replay: None,
+                recorder: None,
+                circlepad_deadzone: 0.0,
+                circlepad_curve: CirclePadCurve::Linear,
+                last_touch_position: None,
+                touch_filtering: false,
+                held_frames: [0; 32],
+                key_repeat: (DEFAULT_KEY_REPEAT_DELAY, DEFAULT_KEY_REPEAT_INTERVAL),
+                circlepad_as_dpad: None,
+                emulated_dpad: KeyPad::empty(),
+                emulated_dpad_previous: KeyPad::empty(),
+            })
         }
     }
 
-    /// Scan the HID service for all user input occurring on the current frame.
-    ///
-    /// This function should be called on every frame when polling
-    /// for user input.
+    /// Returns a [`HidBuilder`] to configure which optional features to enable.
     ///
     /// # Example
     ///
@@ -120,20 +489,38 @@ impl Hid {
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
     /// use ctru::services::hid::Hid;
-    /// let mut hid = Hid::new()?;
     ///
-    /// hid.scan_input();
+    /// let hid = Hid::builder()
+    ///     .with_irrst()
+    ///     .with_accelerometer()
+    ///     .with_gyroscope()
+    ///     .build()?;
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidScanInput")]
-    pub fn scan_input(&mut self) {
-        unsafe { ctru_sys::hidScanInput() };
+    pub fn builder() -> HidBuilder {
+        HidBuilder::default()
     }
 
-    /// Returns a bitflag struct representing which buttons have just been pressed
-    /// on the current frame (and were not pressed on the previous frame).
+    /// Enable the additional controls of the New 3DS models (ZL and ZR buttons and C-Stick).
+    ///
+    /// Once enabled, the additional buttons will be reported by [`Hid::keys_down()`], [`Hid::keys_held()`] and [`Hid::keys_up()`]
+    /// and the C-Stick position will be available via [`Hid::cstick_position()`].
+    ///
+    /// # Notes
+    ///
+    /// These controls are handled by the `ir:rst` service, which is also used by the Circle Pad Pro on Old 3DS models.
+    /// Calling this function more than once has no effect.
+    ///
+    /// On Old 3DS models, the Circle Pad Pro can also be read directly through the `ir:USER` service with
+    /// [`CirclePadPro`](crate::services::ir_user::CirclePadPro), which reports its input through the same accessors.
+    /// Only one of them can be used at a time.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `ir:rst` service was unable to be initialized,
+    /// or [`Error::ServiceAlreadyActive`] if a [`CirclePadPro`](crate::services::ir_user::CirclePadPro) is active.
     ///
     /// # Example
     ///
@@ -145,25 +532,50 @@ impl Hid {
     /// use ctru::services::hid::{Hid, KeyPad};
     /// let mut hid = Hid::new()?;
     ///
+    /// hid.enable_extra_controls()?;
+    ///
     /// hid.scan_input();
     ///
-    /// if hid.keys_down().contains(KeyPad::A) {
-    ///     println!("You have pressed the A button!")
+    /// if hid.keys_down().contains(KeyPad::ZL) {
+    ///     println!("You have pressed the ZL button!")
     /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidKeysDown")]
-    pub fn keys_down(&self) -> KeyPad {
-        unsafe {
-            let keys = ctru_sys::hidKeysDown();
-            KeyPad::from_bits_truncate(keys)
+    #[doc(alias = "irrstInit")]
+    pub fn enable_extra_controls(&mut self) -> crate::Result<()> {
+        if !self.extra_controls {
+            let lock = ExtraControlsLock::acquire()?;
+
+            let mut is_new_3ds = false;
+            ResultCode(unsafe { ctru_sys::APT_CheckNew3DS(&mut is_new_3ds) })?;
+
+            ResultCode(unsafe { ctru_sys::irrstInit() })?;
+            self.extra_controls = true;
+            self.extra_controls_lock = Some(lock);
+
+            self.extra_input_source = Some(if is_new_3ds {
+                ExtraInputSource::New3ds
+            } else {
+                ExtraInputSource::CirclePadPro
+            });
         }
+
+        Ok(())
     }
 
-    /// Returns a bitflag struct representing which buttons have been held down
-    /// during the current frame.
+    /// Returns the hardware providing the additional controls, or [`None`] if they haven't been enabled via [`Hid::enable_extra_controls()`]
+    /// and no Circle Pad Pro is connected through [`CirclePadPro`](crate::services::ir_user::CirclePadPro).
+    ///
+    /// # Notes
+    ///
+    /// The `ir:rst` service reads the additional controls from the built-in hardware on New 3DS models,
+    /// and from the Circle Pad Pro (connected via infrared) on Old 3DS models. Since the service provides the same data in both cases,
+    /// the source is determined by checking the console model with `APT_CheckNew3DS`.
+    ///
+    /// On Old 3DS models, [`ExtraInputSource::CirclePadPro`] is returned even if the accessory isn't currently connected
+    /// (in which case the additional controls report no input), since the service doesn't expose the connection state.
     ///
     /// # Example
     ///
@@ -172,28 +584,51 @@ impl Hid {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::hid::{Hid, KeyPad};
+    /// use ctru::services::hid::{ExtraInputSource, Hid};
     /// let mut hid = Hid::new()?;
     ///
-    /// hid.scan_input();
+    /// hid.enable_extra_controls()?;
     ///
-    /// if hid.keys_held().contains(KeyPad::START) {
-    ///     println!("You are holding the START button!")
-    /// }
+    /// // The Circle Pad Pro's stick is less precise than the New 3DS' C-Stick.
+    /// let deadzone = match hid.extra_input_source() {
+    ///     Some(ExtraInputSource::CirclePadPro) => 20,
+    ///     _ => 10,
+    /// };
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidKeysHeld")]
-    pub fn keys_held(&self) -> KeyPad {
-        unsafe {
-            let keys = ctru_sys::hidKeysHeld();
-            KeyPad::from_bits_truncate(keys)
-        }
+    #[doc(alias = "APT_CheckNew3DS")]
+    pub fn extra_input_source(&self) -> Option<ExtraInputSource> {
+        self.extra_input_source
     }
 
-    /// Returns a bitflag struct representing which buttons have just been released on
-    /// the current frame.
+    /// Report the input of a Circle Pad Pro read through `ir:USER` as the additional controls.
+    ///
+    /// While the accessory isn't connected, the additional controls report no input and no source.
+    /// Called by [`CirclePadPro::scan_input()`](crate::services::ir_user::CirclePadPro::scan_input) after every scan.
+    pub(crate) fn set_circle_pad_pro_input(
+        &mut self,
+        connected: bool,
+        input: Option<CirclePadProInput>,
+    ) {
+        self.extra_keys_held_previous = self.extra_keys_held;
+        self.extra_keys_held = input.map_or(0, |input| input.keys.bits());
+        self.circle_pad_pro_stick = input.map(|input| input.stick);
+        self.extra_input_source = connected.then_some(ExtraInputSource::CirclePadPro);
+    }
+
+    /// Returns a view of the inputs of the specified controller slot, or [`None`] if the slot isn't available.
+    ///
+    /// This makes it possible to handle each input source uniformly, as a set of buttons and a stick. The slots are mapped as follows:
+    ///
+    /// | Slot | Buttons                                                               | Stick      | Available                              |
+    /// |------|-----------------------------------------------------------------------|------------|----------------------------------------|
+    /// | 0    | Every key except ZL, ZR, the C-Stick directions and [`KeyPad::TOUCH`] | Circle pad | Always                                 |
+    /// | 1    | ZL, ZR and the C-Stick directions                                     | C-Stick    | After [`Hid::enable_extra_controls()`] |
+    ///
+    /// Slot 1 reports the controls of the New 3DS models, or those of the Circle Pad Pro on Old 3DS models (see [`Hid::extra_input_source()`]).
+    /// No other slot is available, since the console has no way to connect more controllers. The touch screen isn't part of any slot.
     ///
     /// # Example
     ///
@@ -202,31 +637,66 @@ impl Hid {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
-    /// use ctru::services::hid::{Hid, KeyPad};
+    /// use ctru::services::hid::Hid;
     /// let mut hid = Hid::new()?;
     ///
+    /// hid.enable_extra_controls()?;
+    ///
     /// hid.scan_input();
     ///
-    /// if hid.keys_held().contains(KeyPad::B) {
-    ///     println!("You have released the B button!")
+    /// for controller in (0..).map_while(|slot| hid.controller(slot)) {
+    ///     let (x, y) = controller.stick_position();
+    ///
+    ///     println!("Slot {}: {:?}, stick at ({x}, {y})", controller.slot(), controller.keys_held());
     /// }
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidKeysUp")]
-    pub fn keys_up(&self) -> KeyPad {
-        unsafe {
-            let keys = ctru_sys::hidKeysUp();
-            KeyPad::from_bits_truncate(keys)
+    pub fn controller(&self, slot: usize) -> Option<Controller<'_>> {
+        match slot {
+            0 => Some(Controller { hid: self, slot }),
+            1 if self.extra_controls => Some(Controller { hid: self, slot }),
+            _ => None,
         }
     }
 
-    /// Returns the current touch position in pixels (x, y).
+    /// Enable the accelerometer.
     ///
     /// # Notes
     ///
-    /// (0, 0) represents the top left corner of the screen.
+    /// Powering the accelerometer slightly increases battery consumption, so it should only be enabled when needed.
+    /// Calling this function more than once has no effect.
+    #[doc(alias = "HIDUSER_EnableAccelerometer")]
+    pub fn enable_accelerometer(&mut self) -> crate::Result<()> {
+        if !self.accelerometer {
+            ResultCode(unsafe { ctru_sys::HIDUSER_EnableAccelerometer() })?;
+            self.accelerometer = true;
+        }
+
+        Ok(())
+    }
+
+    /// Enable the gyroscope.
+    ///
+    /// # Notes
+    ///
+    /// Powering the gyroscope increases battery consumption, so it should only be enabled when needed.
+    /// Calling this function more than once has no effect.
+    #[doc(alias = "HIDUSER_EnableGyroscope")]
+    pub fn enable_gyroscope(&mut self) -> crate::Result<()> {
+        if !self.gyroscope {
+            ResultCode(unsafe { ctru_sys::HIDUSER_EnableGyroscope() })?;
+            self.gyroscope = true;
+        }
+
+        Ok(())
+    }
+
+    /// Scan the HID service for all user input occurring on the current frame.
+    ///
+    /// This function should be called on every frame when polling
+    /// for user input.
     ///
     /// # Example
     ///
@@ -239,28 +709,141 @@ impl Hid {
     /// let mut hid = Hid::new()?;
     ///
     /// hid.scan_input();
-    ///
-    /// let (touch_x, touch_y) = hid.touch_position();
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidTouchRead")]
-    pub fn touch_position(&self) -> (u16, u16) {
-        let mut res = ctru_sys::touchPosition { px: 0, py: 0 };
+    #[doc(alias = "hidScanInput")]
+    pub fn scan_input(&mut self) {
+        if let Some(replay) = &mut self.replay {
+            match InputFrame::read_from(&mut replay.source) {
+                Ok(frame) => {
+                    replay.keys_held_previous = replay.frame.keys_held;
+                    replay.frame = frame;
+                }
+                // The replay is over (or broken), so go back to the real input.
+                Err(_) => self.replay = None,
+            }
+        }
 
-        unsafe {
-            ctru_sys::hidTouchRead(&mut res);
+        if self.replay.is_none() {
+            unsafe { ctru_sys::hidScanInput() };
+
+            // `hidScanInput()` already scans the irrst input, so it only needs to be read.
+            if self.extra_controls {
+                self.extra_keys_held_previous = self.extra_keys_held;
+                self.extra_keys_held = unsafe { ctru_sys::irrstKeysHeld() };
+            }
+        }
+
+        self.emulated_dpad_previous = self.emulated_dpad;
+        self.emulated_dpad = self.circlepad_dpad();
+
+        let held = self.keys_held();
+
+        if held.contains(KeyPad::TOUCH) {
+            self.last_touch_position = Some(self.touch_position());
+        }
+
+        for (bit, frames) in self.held_frames.iter_mut().enumerate() {
+            *frames = if held.bits() & (1 << bit) != 0 {
+                frames.saturating_add(1)
+            } else {
+                0
+            };
         }
 
-        (res.px, res.py)
+        if self.recorder.is_some() {
+            let frame = self.current_frame();
+
+            if let Some(recorder) = &mut self.recorder {
+                if frame.write_to(recorder).is_err() {
+                    self.recorder = None;
+                }
+            }
+        }
     }
 
-    /// Returns the current circle pad position in relative (x, y).
+    /// Returns the state of the basic inputs (keys, touch screen and circle pad) during the current frame.
+    pub fn current_frame(&self) -> InputFrame {
+        InputFrame {
+            keys_held: self.keys_held(),
+            touch_position: self.touch_position(),
+            circlepad_position: self.circlepad_position(),
+        }
+    }
+
+    /// Replay previously recorded input instead of reading the hardware.
+    ///
+    /// Every call to [`Hid::scan_input()`] reads the next [`InputFrame`] from `source`, which is then returned by
+    /// [`Hid::keys_held()`], [`Hid::keys_down()`], [`Hid::keys_up()`], [`Hid::touch_position()`] and [`Hid::circlepad_position()`].
+    /// Have a look at [`InputFrame`] for the expected binary format.
     ///
     /// # Notes
     ///
-    /// (0, 0) represents the center of the circle pad.
+    /// Once `source` reaches its end (or fails to be read), the replay stops and the real input is used again.
+    /// Other inputs (like the C-Stick or the motion sensors) aren't affected by the replay.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use std::io::Cursor;
+    /// use ctru::services::hid::{Hid, InputFrame, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // A single frame, in which the A button is held.
+    /// let mut recording = Vec::new();
+    /// let frame = InputFrame {
+    ///     keys_held: KeyPad::A,
+    ///     ..Default::default()
+    /// };
+    /// frame.write_to(&mut recording)?;
+    ///
+    /// hid.set_replay_source(Cursor::new(recording));
+    ///
+    /// hid.scan_input();
+    /// assert!(hid.keys_down().contains(KeyPad::A));
+    ///
+    /// // The recording is over.
+    /// hid.scan_input();
+    /// assert!(!hid.is_replaying());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_replay_source(&mut self, source: impl Read + Send + 'static) {
+        self.replay = Some(Replay {
+            source: Box::new(source),
+            frame: InputFrame::default(),
+            keys_held_previous: KeyPad::empty(),
+        });
+    }
+
+    /// Stop replaying input and go back to reading the hardware.
+    pub fn stop_replay(&mut self) {
+        self.replay = None;
+    }
+
+    /// Returns `true` if the input is currently being replayed.
+    ///
+    /// See [`Hid::set_replay_source()`].
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Record the input to a stream.
+    ///
+    /// Every call to [`Hid::scan_input()`] writes the resulting [`InputFrame`] to `sink`,
+    /// which can later be replayed with [`Hid::set_replay_source()`].
+    ///
+    /// # Notes
+    ///
+    /// The recording stops if writing to `sink` fails. Use a buffered writer (e.g. [`BufWriter`](std::io::BufWriter))
+    /// when recording to a file, to avoid a filesystem access on every frame.
     ///
     /// # Example
     ///
@@ -269,31 +852,1618 @@ impl Hid {
     /// # use std::error::Error;
     /// # fn main() -> Result<(), Box<dyn Error>> {
     /// #
+    /// use std::fs::File;
+    /// use std::io::BufWriter;
     /// use ctru::services::hid::Hid;
     /// let mut hid = Hid::new()?;
     ///
+    /// hid.set_recorder(BufWriter::new(File::create("/session.input")?));
+    ///
     /// hid.scan_input();
     ///
-    /// let (pad_x, pad_y) = hid.circlepad_position();
+    /// hid.stop_recording()?;
     /// #
     /// # Ok(())
     /// # }
     /// ```
-    #[doc(alias = "hidCircleRead")]
-    pub fn circlepad_position(&self) -> (i16, i16) {
-        let mut res = ctru_sys::circlePosition { dx: 0, dy: 0 };
+    pub fn set_recorder(&mut self, sink: impl Write + Send + 'static) {
+        self.recorder = Some(Box::new(sink));
+    }
+
+    /// Stop recording the input, flushing the recording stream.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stream could not be flushed.
+    pub fn stop_recording(&mut self) -> io::Result<()> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the input is currently being recorded.
+    ///
+    /// See [`Hid::set_recorder()`].
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Returns a bitflag struct representing which buttons have just been pressed
+    /// on the current frame (and were not pressed on the previous frame).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_down().contains(KeyPad::A) {
+    ///     println!("You have pressed the A button!")
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidKeysDown")]
+    pub fn keys_down(&self) -> KeyPad {
+        let emulated = self.emulated_dpad & !self.emulated_dpad_previous;
+
+        if let Some(replay) = &self.replay {
+            return (replay.frame.keys_held & !replay.keys_held_previous) | emulated;
+        }
 
         unsafe {
-            ctru_sys::hidCircleRead(&mut res);
+            let keys =
+                ctru_sys::hidKeysDown() | (self.extra_keys_held & !self.extra_keys_held_previous);
+            KeyPad::from_bits_truncate(keys) | emulated
         }
+    }
 
-        (res.dx, res.dy)
+    /// Returns `true` if any key (including the touch screen) has been pressed during the current frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.any_key_down() {
+    ///     println!("Let's go!")
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn any_key_down(&self) -> bool {
+        !self.keys_down().is_empty()
     }
-}
 
-impl Drop for Hid {
-    #[doc(alias = "hidExit")]
-    fn drop(&mut self) {
-        unsafe { ctru_sys::hidExit() };
+    /// Block the current thread until any key (including the touch screen) is pressed, then return the keys pressed during that frame.
+    ///
+    /// # Notes
+    ///
+    /// Instead of busy-looping, this function sleeps until the HID module signals that new input data is available (about every 4 milliseconds),
+    /// then scans the input just like [`Hid::scan_input()`] does. Tilting the circle pad past its threshold counts as pressing the matching
+    /// `KeyPad::CPAD_*` key.
+    ///
+    /// The main loop (and [`Apt::main_loop()`](crate::services::apt::Apt::main_loop)) doesn't run while waiting, so this is best suited for
+    /// simple "press any key to continue" screens.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// println!("Press any key to continue");
+    ///
+    /// let keys = hid.wait_for_key();
+    ///
+    /// if keys.contains(KeyPad::START) {
+    ///     println!("You have pressed START!")
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidWaitForEvent")]
+    pub fn wait_for_key(&mut self) -> KeyPad {
+        loop {
+            // Replayed frames are read as fast as possible.
+            if self.replay.is_none() {
+                unsafe { ctru_sys::hidWaitForEvent(ctru_sys::HIDEVENT_PAD0, true) };
+            }
+
+            self.scan_input();
+
+            let keys = self.keys_down();
+            if !keys.is_empty() {
+                return keys;
+            }
+        }
+    }
+
+    /// Returns a bitflag struct representing which buttons have been held down
+    /// during the current frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_held().contains(KeyPad::START) {
+    ///     println!("You are holding the START button!")
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidKeysHeld")]
+    pub fn keys_held(&self) -> KeyPad {
+        if let Some(replay) = &self.replay {
+            return replay.frame.keys_held | self.emulated_dpad;
+        }
+
+        unsafe {
+            let keys = ctru_sys::hidKeysHeld() | self.extra_keys_held;
+            KeyPad::from_bits_truncate(keys) | self.emulated_dpad
+        }
+    }
+
+    /// Returns a bitflag struct representing which buttons have just been released on
+    /// the current frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_held().contains(KeyPad::B) {
+    ///     println!("You have released the B button!")
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidKeysUp")]
+    pub fn keys_up(&self) -> KeyPad {
+        let emulated = self.emulated_dpad_previous & !self.emulated_dpad;
+
+        if let Some(replay) = &self.replay {
+            return (replay.keys_held_previous & !replay.frame.keys_held) | emulated;
+        }
+
+        unsafe {
+            let keys =
+                ctru_sys::hidKeysUp() | (self.extra_keys_held_previous & !self.extra_keys_held);
+            KeyPad::from_bits_truncate(keys) | emulated
+        }
+    }
+
+    /// Returns `true` if the keys in `combo` are all held down, and at least one of them has just been pressed on the current frame.
+    ///
+    /// This is meant to be checked after [`Hid::scan_input()`] to leave the main loop: since the combo must be completed
+    /// on the current frame, it triggers only once even if the keys are kept held down.
+    /// An empty `combo` never triggers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::apt::Apt;
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let apt = Apt::new()?;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// while apt.main_loop() {
+    ///     hid.scan_input();
+    ///
+    ///     if hid.should_quit(KeyPad::START) || hid.should_quit(KeyPad::L | KeyPad::R) {
+    ///         break;
+    ///     }
+    ///     #
+    ///     # break;
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn should_quit(&self, combo: KeyPad) -> bool {
+        self.keys_held().contains(combo) && self.keys_down().intersects(combo)
+    }
+
+    /// Returns for how many frames the specified keys have been held.
+    ///
+    /// The count is 1 on the frame the keys are pressed (i.e. when they are reported by [`Hid::keys_down()`]) and 0 while they aren't held.
+    /// If multiple keys are specified, the count is the one of the key pressed last, i.e. for how long all of them have been held together.
+    ///
+    /// # Notes
+    ///
+    /// The count is updated by [`Hid::scan_input()`], so it represents a number of frames only if input is scanned exactly once per frame.
+    /// Skipping or repeating scans (e.g. while a menu is open) makes the count drift from the actual time the keys have been held.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// // Charge move: hold back for a second, then press forward and attack.
+    /// let charged = hid.key_held_frames(KeyPad::DPAD_LEFT) >= 60;
+    ///
+    /// if charged && hid.keys_down().contains(KeyPad::A) {
+    ///     println!("Sonic boom!");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn key_held_frames(&self, keys: KeyPad) -> u32 {
+        (0..32)
+            .filter(|bit| keys.bits() & (1 << bit) != 0)
+            .map(|bit| self.held_frames[bit])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Set the timing of the key repeats reported by [`Hid::keys_repeated()`] and [`Hid::keys_triggered()`].
+    ///
+    /// A held key first repeats `delay` frames after being pressed, then every `interval` frames for as long as it stays held.
+    /// The default timing is a delay of 30 frames and an interval of 6 frames (half a second, then 10 repeats per second at 60 fps).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `delay` or `interval` is 0.
+    pub fn set_key_repeat(&mut self, delay: u32, interval: u32) {
+        assert!(delay > 0, "the repeat delay must be at least 1 frame");
+        assert!(interval > 0, "the repeat interval must be at least 1 frame");
+
+        self.key_repeat = (delay, interval);
+    }
+
+    /// Returns the keys which are being repeated on the current frame because they have been held long enough.
+    ///
+    /// Unlike [`Hid::keys_triggered()`], the first press of a key isn't reported here, only the following repeats.
+    /// See [`Hid::set_key_repeat()`] to change how often keys repeat.
+    ///
+    /// # Notes
+    ///
+    /// Repeats are based on the counts of [`Hid::key_held_frames()`], so the same caveats about scanning the input
+    /// exactly once per frame apply.
+    pub fn keys_repeated(&self) -> KeyPad {
+        let (delay, interval) = self.key_repeat;
+
+        let bits = self
+            .held_frames
+            .iter()
+            .enumerate()
+            // The count is 1 on the frame the key is pressed.
+            .filter(|&(_, &frames)| frames > delay && (frames - 1 - delay) % interval == 0)
+            .fold(0, |bits, (bit, _)| bits | (1 << bit));
+
+        KeyPad::from_bits_truncate(bits)
+    }
+
+    /// Returns the keys which have just been pressed or are being repeated on the current frame.
+    ///
+    /// This is the union of [`Hid::keys_down()`] (only the first frame of a press) and [`Hid::keys_repeated()`] (only the repeats after the delay),
+    /// which is what menus usually want: a key moves the cursor once when tapped, and keeps moving it at a steady pace while held.
+    /// [`Hid::keys_held()`] instead reports a key on every frame it's held.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    /// let mut selected: usize = 0;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let keys = hid.keys_triggered();
+    ///
+    /// if keys.contains(KeyPad::DPAD_DOWN) {
+    ///     selected += 1;
+    /// } else if keys.contains(KeyPad::DPAD_UP) {
+    ///     selected = selected.saturating_sub(1);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keys_triggered(&self) -> KeyPad {
+        self.keys_down() | self.keys_repeated()
+    }
+
+    /// Returns the current touch position in pixels (x, y).
+    ///
+    /// # Notes
+    ///
+    /// (0, 0) represents the top left corner of the screen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (touch_x, touch_y) = hid.touch_position();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidTouchRead")]
+    pub fn touch_position(&self) -> (u16, u16) {
+        let position = match &self.replay {
+            Some(replay) => replay.frame.touch_position,
+            None => {
+                let mut res = ctru_sys::touchPosition { px: 0, py: 0 };
+
+                unsafe {
+                    ctru_sys::hidTouchRead(&mut res);
+                }
+
+                (res.px, res.py)
+            }
+        };
+
+        if self.touch_filtering {
+            self.filter_touch_position(position)
+        } else {
+            position
+        }
+    }
+
+    /// Enable or disable the validation of the positions returned by [`Hid::touch_position()`].
+    ///
+    /// When enabled, positions slightly outside of the screen (up to 16 pixels) are clamped to the 320x240 range of the touch screen,
+    /// and positions further away (such as `0xFFFF`) are discarded: the [last valid position](Hid::last_touch_position) is returned instead.
+    /// Filtering is disabled by default.
+    ///
+    /// # Notes
+    ///
+    /// On some firmwares, the system's conversion from the raw touch panel reading can briefly produce out-of-range coordinates
+    /// when the screen is touched near its edges (negative coordinates wrap around to very large values).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.set_touch_filtering(true);
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (touch_x, touch_y) = hid.touch_position();
+    /// assert!(touch_x < 320 && touch_y < 240);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_touch_filtering(&mut self, enable: bool) {
+        self.touch_filtering = enable;
+    }
+
+    /// Clamp `position` to the touch screen, or replace it with the last valid position if it's too far out of the screen.
+    fn filter_touch_position(&self, (x, y): (u16, u16)) -> (u16, u16) {
+        let (width, height) = TOUCH_SCREEN_SIZE;
+
+        if x >= width + TOUCH_CLAMP_MARGIN || y >= height + TOUCH_CLAMP_MARGIN {
+            return self.last_touch_position.unwrap_or((0, 0));
+        }
+
+        (x.min(width - 1), y.min(height - 1))
+    }
+
+    /// Returns the touch position in pixels (x, y) during the latest frame in which the touch screen was touched,
+    /// or [`None`] if it hasn't been touched since the handle was created.
+    ///
+    /// # Notes
+    ///
+    /// Unlike [`Hid::touch_position()`], which returns (0, 0) as soon as the screen is released, the last position is kept until the screen
+    /// is touched again. It's updated by [`Hid::scan_input()`], so it's only accurate if the input is scanned every frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// if let Some((x, y)) = hid.last_touch_position() {
+    ///     println!("The screen was last touched at ({x}, {y})");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn last_touch_position(&self) -> Option<(u16, u16)> {
+        self.last_touch_position
+    }
+
+    /// Returns the position in pixels (x, y) where the stylus (or finger) was lifted from the touch screen, if it happened during the current frame.
+    ///
+    /// # Notes
+    ///
+    /// The touch screen is released when `KeyPad::TOUCH` is in [`Hid::keys_up()`]. By then [`Hid::touch_position()`] already reads (0, 0),
+    /// so the position reported here is the [last touched position](Hid::last_touch_position), from the previous frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    /// # let mut draw_line_to = |_: (u16, u16)| {};
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_held().contains(KeyPad::TOUCH) {
+    ///     draw_line_to(hid.touch_position());
+    /// } else if let Some(end) = hid.touch_release_position() {
+    ///     // Finish the stroke where the stylus was lifted, instead of at (0, 0).
+    ///     draw_line_to(end);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn touch_release_position(&self) -> Option<(u16, u16)> {
+        if self.keys_up().contains(KeyPad::TOUCH) {
+            self.last_touch_position
+        } else {
+            None
+        }
+    }
+
+    /// Returns the current touch position in pixels (x, y), recomputed from the raw touch panel reading with the specified calibration.
+    ///
+    /// # Notes
+    ///
+    /// The position returned by [`Hid::touch_position()`] is already calibrated by the system, so it's accurate enough for most uses.
+    /// However, the system's conversion loses some precision and may report slightly wrong positions near the edges of the screen.
+    /// This function applies the calibration to the raw reading with full precision instead, rounding to the closest pixel.
+    ///
+    /// The raw reading is taken from the HID shared memory (where the system stores the uncalibrated data of the latest touch sample)
+    /// when this function is called, so it may be slightly more recent than the state read by [`Hid::scan_input()`].
+    /// The position is (0, 0) if the screen isn't being touched. While [replaying input](Hid::set_replay_source), the recorded position is returned as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::cfgu::Cfgu;
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // The calibration doesn't change while the application is running.
+    /// let calibration = Cfgu::new()?.touch_calibration()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (touch_x, touch_y) = hid.touch_position_calibrated(&calibration);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn touch_position_calibrated(&self, calibration: &TouchCalibration) -> (u16, u16) {
+        if let Some(replay) = &self.replay {
+            return replay.frame.touch_position;
+        }
+
+        if !self.keys_held().contains(KeyPad::TOUCH) {
+            return (0, 0);
+        }
+
+        // The touch screen section starts at word 42 of the shared memory, and its 7th word holds the raw data of the latest sample.
+        let raw = unsafe {
+            ctru_sys::hidSharedMem
+                .add(TOUCH_RAW_DATA_WORD)
+                .read_volatile()
+        };
+
+        calibration.apply(((raw & 0xFFFF) as u16, (raw >> 16) as u16))
+    }
+
+    /// Returns the current circle pad position in relative (x, y).
+    ///
+    /// # Notes
+    ///
+    /// (0, 0) represents the center of the circle pad.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (pad_x, pad_y) = hid.circlepad_position();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidCircleRead")]
+    pub fn circlepad_position(&self) -> (i16, i16) {
+        if let Some(replay) = &self.replay {
+            return replay.frame.circlepad_position;
+        }
+
+        let mut res = ctru_sys::circlePosition { dx: 0, dy: 0 };
+
+        unsafe {
+            ctru_sys::hidCircleRead(&mut res);
+        }
+
+        (res.dx, res.dy)
+    }
+
+    /// Returns the current circle pad position as a vector with a length between 0.0 and 1.0.
+    ///
+    /// # Notes
+    ///
+    /// The raw position (see [`Hid::circlepad_position()`]) is normalized, then the deadzone set with [`Hid::set_circlepad_deadzone()`]
+    /// is removed and the response curve set with [`Hid::set_circlepad_curve()`] is applied to the length of the vector.
+    /// The direction of the vector is unchanged, and the Y axis points up like in the raw position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (x, y) = hid.circlepad_vector();
+    /// assert!(x.hypot(y) <= 1.0);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn circlepad_vector(&self) -> (f32, f32) {
+        let (x, y) = self.circlepad_position();
+        let (x, y) = (f32::from(x) / CIRCLEPAD_MAX, f32::from(y) / CIRCLEPAD_MAX);
+
+        let length = x.hypot(y);
+
+        if length <= self.circlepad_deadzone {
+            return (0.0, 0.0);
+        }
+
+        let scaled = (length.min(1.0) - self.circlepad_deadzone) / (1.0 - self.circlepad_deadzone);
+        let curved = self.circlepad_curve.apply(scaled);
+
+        (x / length * curved, y / length * curved)
+    }
+
+    /// Set the radius of the circle pad's deadzone, as a fraction (0.0–1.0) of its full range. Defaults to 0.0.
+    ///
+    /// Positions inside the deadzone are reported as (0.0, 0.0) by [`Hid::circlepad_vector()`],
+    /// and the rest of the range is stretched to still cover 0.0–1.0.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `deadzone` isn't in the range 0.0 (included) to 1.0 (excluded).
+    pub fn set_circlepad_deadzone(&mut self, deadzone: f32) {
+        assert!(
+            (0.0..1.0).contains(&deadzone),
+            "the deadzone must be between 0.0 (included) and 1.0 (excluded)"
+        );
+
+        self.circlepad_deadzone = deadzone;
+    }
+
+    /// Set the response curve applied by [`Hid::circlepad_vector()`]. Defaults to [`CirclePadCurve::Linear`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the exponent of a [`CirclePadCurve::Exponent`] isn't a positive number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{CirclePadCurve, Hid};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // Finer aiming with small movements.
+    /// hid.set_circlepad_deadzone(0.1);
+    /// hid.set_circlepad_curve(CirclePadCurve::Exponent(1.5));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_circlepad_curve(&mut self, curve: CirclePadCurve) {
+        if let CirclePadCurve::Exponent(exponent) = curve {
+            assert!(exponent > 0.0, "the curve's exponent must be positive");
+        }
+
+        self.circlepad_curve = curve;
+    }
+
+    /// Emulate the D-Pad with the circle pad, or stop doing so if `threshold` is [`None`]. Disabled by default.
+    ///
+    /// While enabled, tilting the circle pad past `threshold` (as a fraction, 0.0–1.0, of its full range) along an axis
+    /// makes [`Hid::keys_held()`], [`Hid::keys_down()`] and [`Hid::keys_up()`] report the matching `DPAD_*` key, as if it was pressed.
+    /// Each axis is checked on its own, so diagonal tilts press two keys at once.
+    ///
+    /// # Notes
+    ///
+    /// The circle pad's own `CPAD_*` keys (and so the [`KeyPad::UP`]-like catch-alls) are still reported as usual, but they are pressed
+    /// at a fixed threshold decided by the system. This makes it possible to choose how far the circle pad must be tilted, and lets code
+    /// written for the D-Pad alone work with the circle pad too. The emulated keys are updated by [`Hid::scan_input()`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `threshold` isn't in the range 0.0 (excluded) to 1.0 (included).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// let mut hid = Hid::new()?;
+    ///
+    /// // Menu navigation with either the D-Pad or the circle pad, tilted at least halfway.
+    /// hid.circlepad_as_dpad(Some(0.5));
+    ///
+    /// hid.scan_input();
+    ///
+    /// if hid.keys_down().contains(KeyPad::DPAD_DOWN) {
+    ///     println!("Next item");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn circlepad_as_dpad(&mut self, threshold: Option<f32>) {
+        if let Some(threshold) = threshold {
+            assert!(
+                threshold > 0.0 && threshold <= 1.0,
+                "the threshold must be between 0.0 (excluded) and 1.0 (included)"
+            );
+        }
+
+        self.circlepad_as_dpad = threshold;
+    }
+
+    /// Returns the D-Pad keys emulated by the circle pad's current position. See [`Hid::circlepad_as_dpad()`].
+    fn circlepad_dpad(&self) -> KeyPad {
+        let Some(threshold) = self.circlepad_as_dpad else {
+            return KeyPad::empty();
+        };
+
+        let (x, y) = self.circlepad_position();
+        let limit = threshold * CIRCLEPAD_MAX;
+        let (x, y) = (f32::from(x), f32::from(y));
+
+        let mut keys = KeyPad::empty();
+        keys.set(KeyPad::DPAD_RIGHT, x >= limit);
+        keys.set(KeyPad::DPAD_LEFT, x <= -limit);
+        keys.set(KeyPad::DPAD_UP, y >= limit);
+        keys.set(KeyPad::DPAD_DOWN, y <= -limit);
+
+        keys
+    }
+
+    /// Returns the current C-Stick position in relative (x, y).
+    ///
+    /// # Notes
+    ///
+    /// (0, 0) represents the center of the C-Stick.
+    /// The position is always (0, 0) if the extra controls haven't been enabled via [`Hid::enable_extra_controls()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.enable_extra_controls()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (stick_x, stick_y) = hid.cstick_position();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "irrstCstickRead")]
+    pub fn cstick_position(&self) -> (i16, i16) {
+        if let Some(position) = self.circle_pad_pro_stick {
+            return position;
+        }
+
+        let mut res = ctru_sys::circlePosition { dx: 0, dy: 0 };
+
+        if self.extra_controls {
+            unsafe {
+                ctru_sys::irrstCstickRead(&mut res);
+            }
+        }
+
+        (res.dx, res.dy)
+    }
+
+    /// Returns the current raw accelerometer reading (x, y, z).
+    ///
+    /// # Notes
+    ///
+    /// The reading is always (0, 0, 0) if the accelerometer hasn't been enabled via [`Hid::enable_accelerometer()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::builder().with_accelerometer().build()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (x, y, z) = hid.accelerometer_vector();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidAccelRead")]
+    pub fn accelerometer_vector(&self) -> (i16, i16, i16) {
+        let mut res = ctru_sys::accelVector { x: 0, y: 0, z: 0 };
+
+        if self.accelerometer {
+            unsafe {
+                ctru_sys::hidAccelRead(&mut res);
+            }
+        }
+
+        (res.x, res.y, res.z)
+    }
+
+    /// Returns the current raw gyroscope angular rate (x, y, z).
+    ///
+    /// # Notes
+    ///
+    /// The rate is always (0, 0, 0) if the gyroscope hasn't been enabled via [`Hid::enable_gyroscope()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::builder().with_gyroscope().build()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// let (roll, pitch, yaw) = hid.gyroscope_rate();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "hidGyroRead")]
+    pub fn gyroscope_rate(&self) -> (i16, i16, i16) {
+        let mut res = ctru_sys::angularRate { x: 0, y: 0, z: 0 };
+
+        if self.gyroscope {
+            unsafe {
+                ctru_sys::hidGyroRead(&mut res);
+            }
+        }
+
+        (res.x, res.y, res.z)
+    }
+
+    /// Returns the current position of the 3D slider, from 0.0 (3D off) to 1.0 (maximum depth).
+    ///
+    /// # Notes
+    ///
+    /// The value is the same returned by [`os::current_3d_slider_state()`](crate::os::current_3d_slider_state):
+    /// it's read from the shared configuration memory, which the OS keeps up to date, so it's cheap enough to be polled every frame.
+    /// Reading the raw slider level from the MCU instead (`MCUHWC_Get3dSliderLevel`) requires the MCU service and an IPC request each time,
+    /// and the raw value isn't calibrated, so it doesn't necessarily span the full 0-255 range.
+    ///
+    /// On consoles without stereoscopic 3D (the 2DS family) the value is always 0.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// let mut hid = Hid::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// // Distance between the left and right eye views, in pixels.
+    /// let depth = hid.slider_3d() * 10.0;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "osGet3DSliderState")]
+    pub fn slider_3d(&self) -> f32 {
+        crate::os::current_3d_slider_state()
+    }
+}
+
+impl Controller<'_> {
+    /// Returns the slot of the controller. See [`Hid::controller()`].
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// Returns the keys of the controller among the specified ones.
+    fn own_keys(&self, keys: KeyPad) -> KeyPad {
+        match self.slot {
+            0 => keys - EXTRA_KEYS - KeyPad::TOUCH,
+            _ => keys & EXTRA_KEYS,
+        }
+    }
+
+    /// Returns the controller's buttons which have just been pressed on the current frame. See [`Hid::keys_down()`].
+    pub fn keys_down(&self) -> KeyPad {
+        self.own_keys(self.hid.keys_down())
+    }
+
+    /// Returns the controller's buttons held down during the current frame. See [`Hid::keys_held()`].
+    pub fn keys_held(&self) -> KeyPad {
+        self.own_keys(self.hid.keys_held())
+    }
+
+    /// Returns the controller's buttons which have just been released on the current frame. See [`Hid::keys_up()`].
+    pub fn keys_up(&self) -> KeyPad {
+        self.own_keys(self.hid.keys_up())
+    }
+
+    /// Returns the position of the controller's stick in relative (x, y), where (0, 0) is the center.
+    ///
+    /// This is the [circle pad](Hid::circlepad_position) for slot 0 and the [C-Stick](Hid::cstick_position) for slot 1.
+    pub fn stick_position(&self) -> (i16, i16) {
+        match self.slot {
+            0 => self.hid.circlepad_position(),
+            _ => self.hid.cstick_position(),
+        }
+    }
+}
+
+impl fmt::Debug for Controller<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Controller")
+            .field("slot", &self.slot)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InputFrame {
+    /// Size (in bytes) of a frame in its binary format.
+    pub const SIZE: usize = 12;
+
+    /// Read a frame in its binary format from `reader`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame could not be read.
+    /// An [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error means the end of the recording was reached.
+    pub fn read_from(mut reader: impl Read) -> io::Result<Self> {
+        let mut buf = [0; Self::SIZE];
+        reader.read_exact(&mut buf)?;
+
+        let u16_at = |offset: usize| u16::from_le_bytes([buf[offset], buf[offset + 1]]);
+        let i16_at = |offset: usize| i16::from_le_bytes([buf[offset], buf[offset + 1]]);
+
+        Ok(Self {
+            keys_held: KeyPad::from_bits_truncate(u32::from_le_bytes([
+                buf[0], buf[1], buf[2], buf[3],
+            ])),
+            touch_position: (u16_at(0x4), u16_at(0x6)),
+            circlepad_position: (i16_at(0x8), i16_at(0xA)),
+        })
+    }
+
+    /// Write the frame in its binary format to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame could not be written.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut buf = [0; Self::SIZE];
+
+        buf[0x0..0x4].copy_from_slice(&self.keys_held.bits().to_le_bytes());
+        buf[0x4..0x6].copy_from_slice(&self.touch_position.0.to_le_bytes());
+        buf[0x6..0x8].copy_from_slice(&self.touch_position.1.to_le_bytes());
+        buf[0x8..0xA].copy_from_slice(&self.circlepad_position.0.to_le_bytes());
+        buf[0xA..0xC].copy_from_slice(&self.circlepad_position.1.to_le_bytes());
+
+        writer.write_all(&buf)
+    }
+}
+
+#[cfg(test)]
+impl InputScript {
+    /// Create an empty script.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a single frame to the script.
+    pub fn frame(mut self, frame: InputFrame) -> Self {
+        // Writing to a `Vec` can't fail.
+        frame.write_to(self.data.get_mut()).unwrap();
+        self
+    }
+
+    /// Add `frames` frames in which `keys` are held and nothing else happens.
+    pub fn hold(mut self, keys: KeyPad, frames: u32) -> Self {
+        for _ in 0..frames {
+            self = self.frame(InputFrame {
+                keys_held: keys,
+                ..Default::default()
+            });
+        }
+
+        self
+    }
+
+    /// Add a frame in which `keys` are pressed, followed by a frame in which they are released.
+    ///
+    /// Thanks to the release, the keys are reported again by [`Hid::keys_down()`] if pressed again right after.
+    pub fn press(self, keys: KeyPad) -> Self {
+        self.hold(keys, 1).idle(1)
+    }
+
+    /// Add `frames` frames in which the touch screen is held at `position` (in pixels).
+    pub fn touch(mut self, position: (u16, u16), frames: u32) -> Self {
+        for _ in 0..frames {
+            self = self.frame(InputFrame {
+                keys_held: KeyPad::TOUCH,
+                touch_position: position,
+                ..Default::default()
+            });
+        }
+
+        self
+    }
+
+    /// Add `frames` frames without any input.
+    pub fn idle(self, frames: u32) -> Self {
+        self.hold(KeyPad::empty(), frames)
+    }
+
+    /// Returns the number of frames left in the script.
+    pub fn len(&self) -> usize {
+        (self.data.get_ref().len() - self.data.position() as usize) / InputFrame::SIZE
+    }
+
+    /// Returns `true` if there are no frames left in the script.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+impl Read for InputScript {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.data.read(buf)
+    }
+}
+
+impl TouchGesture {
+    /// Create a new detector with the default thresholds.
+    ///
+    /// | Threshold                                                      | Default   |
+    /// |----------------------------------------------------------------|-----------|
+    /// | [Drag distance](TouchGesture::set_drag_threshold)              | 8 pixels  |
+    /// | [Tap duration](TouchGesture::set_tap_max_frames)               | 15 frames |
+    /// | [Double tap interval](TouchGesture::set_double_tap_max_frames) | 20 frames |
+    /// | [Swipe distance](TouchGesture::set_swipe_min_distance)         | 48 pixels |
+    /// | [Swipe duration](TouchGesture::set_swipe_max_frames)           | 20 frames |
+    pub fn new() -> Self {
+        Self {
+            drag_threshold: 8,
+            tap_max_frames: 15,
+            double_tap_max_frames: 20,
+            swipe_min_distance: 48,
+            swipe_max_frames: 20,
+            frame: 0,
+            touch: None,
+            last_tap: None,
+        }
+    }
+
+    /// Set the distance (in pixels) a touch must move from its starting point to become a drag.
+    pub fn set_drag_threshold(&mut self, pixels: u16) {
+        self.drag_threshold = pixels;
+    }
+
+    /// Set the maximum duration of a touch to be considered a tap.
+    pub fn set_tap_max_frames(&mut self, frames: u32) {
+        self.tap_max_frames = frames;
+    }
+
+    /// Set the maximum time between two taps to be considered a double tap.
+    pub fn set_double_tap_max_frames(&mut self, frames: u32) {
+        self.double_tap_max_frames = frames;
+    }
+
+    /// Set the minimum distance (in pixels) a drag must cover to be considered a swipe.
+    pub fn set_swipe_min_distance(&mut self, pixels: u16) {
+        self.swipe_min_distance = pixels;
+    }
+
+    /// Set the maximum duration of a drag to be considered a swipe.
+    pub fn set_swipe_max_frames(&mut self, frames: u32) {
+        self.swipe_max_frames = frames;
+    }
+
+    /// Update the detector with the touch state of the current frame, read from `hid`.
+    pub fn update(&mut self, hid: &Hid) -> Option<GestureEvent> {
+        let position = hid
+            .keys_held()
+            .contains(KeyPad::TOUCH)
+            .then(|| hid.touch_position());
+
+        self.update_with(position)
+    }
+
+    /// Update the detector with the touched position of the current frame (or [`None`] if the screen isn't being touched).
+    ///
+    /// This is useful to feed the detector with input that doesn't come from [`Hid`].
+    pub fn update_with(&mut self, position: Option<(u16, u16)>) -> Option<GestureEvent> {
+        self.frame = self.frame.wrapping_add(1);
+
+        match (self.touch, position) {
+            (None, Some(position)) => {
+                self.touch = Some(TouchState {
+                    start: position,
+                    start_frame: self.frame,
+                    position,
+                    is_dragging: false,
+                });
+
+                None
+            }
+            (Some(mut touch), Some(position)) => {
+                let previous = touch.position;
+                touch.position = position;
+
+                let event = if touch.is_dragging {
+                    if position == previous {
+                        None
+                    } else {
+                        Some(GestureEvent::DragMove {
+                            position,
+                            delta: (
+                                position.0 as i16 - previous.0 as i16,
+                                position.1 as i16 - previous.1 as i16,
+                            ),
+                        })
+                    }
+                } else if distance_squared(touch.start, position)
+                    > u32::from(self.drag_threshold).pow(2)
+                {
+                    touch.is_dragging = true;
+                    // A tap before the drag can't be followed by a double tap anymore.
+                    self.last_tap = None;
+
+                    Some(GestureEvent::DragStart {
+                        start: touch.start,
+                        position,
+                    })
+                } else {
+                    None
+                };
+
+                self.touch = Some(touch);
+
+                event
+            }
+            (Some(touch), None) => {
+                self.touch = None;
+
+                self.release(touch)
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Recognize the gesture ending with the release of `touch`.
+    fn release(&mut self, touch: TouchState) -> Option<GestureEvent> {
+        let duration = self.frame.wrapping_sub(touch.start_frame);
+
+        if touch.is_dragging {
+            let (dx, dy) = (
+                i32::from(touch.position.0) - i32::from(touch.start.0),
+                i32::from(touch.position.1) - i32::from(touch.start.1),
+            );
+
+            let is_swipe = duration <= self.swipe_max_frames
+                && distance_squared(touch.start, touch.position)
+                    >= u32::from(self.swipe_min_distance).pow(2);
+
+            return Some(if !is_swipe {
+                GestureEvent::DragEnd {
+                    position: touch.position,
+                }
+            } else if dx.abs() > dy.abs() {
+                GestureEvent::Swipe(if dx > 0 {
+                    SwipeDirection::Right
+                } else {
+                    SwipeDirection::Left
+                })
+            } else {
+                GestureEvent::Swipe(if dy > 0 {
+                    SwipeDirection::Down
+                } else {
+                    SwipeDirection::Up
+                })
+            });
+        }
+
+        // A long press without movement isn't a gesture.
+        if duration > self.tap_max_frames {
+            self.last_tap = None;
+            return None;
+        }
+
+        let position = touch.position;
+
+        let is_double_tap = self.last_tap.take().is_some_and(|(frame, last)| {
+            self.frame.wrapping_sub(frame) <= self.double_tap_max_frames
+                && distance_squared(last, position) <= u32::from(self.drag_threshold).pow(2)
+        });
+
+        if is_double_tap {
+            Some(GestureEvent::DoubleTap { position })
+        } else {
+            self.last_tap = Some((self.frame, position));
+
+            Some(GestureEvent::Tap { position })
+        }
+    }
+}
+
+impl Default for TouchGesture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Copy + Eq> ActionMap<A> {
+    /// Create a new map without any binding.
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind `keys` to `action`, in addition to the keys already bound to it.
+    pub fn bind(&mut self, action: A, keys: KeyPad) {
+        let binding = self.binding(action);
+        self.set_binding(action, binding | keys);
+    }
+
+    /// Bind `keys` to `action`, replacing the keys previously bound to it.
+    pub fn set_binding(&mut self, action: A, keys: KeyPad) {
+        match self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, binding)) => *binding = keys,
+            None => self.bindings.push((action, keys)),
+        }
+    }
+
+    /// Remove all keys bound to `action`.
+    pub fn unbind(&mut self, action: A) {
+        self.bindings.retain(|(a, _)| *a != action);
+    }
+
+    /// Returns the keys bound to `action`.
+    pub fn binding(&self, action: A) -> KeyPad {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(KeyPad::empty(), |&(_, keys)| keys)
+    }
+
+    /// Returns `true` if any key bound to `action` has just been pressed (see [`Hid::keys_down()`]).
+    pub fn is_action_down(&self, hid: &Hid, action: A) -> bool {
+        hid.keys_down().intersects(self.binding(action))
+    }
+
+    /// Returns `true` if any key bound to `action` is being held down (see [`Hid::keys_held()`]).
+    pub fn is_action_held(&self, hid: &Hid, action: A) -> bool {
+        hid.keys_held().intersects(self.binding(action))
+    }
+
+    /// Returns `true` if any key bound to `action` has just been released (see [`Hid::keys_up()`]).
+    pub fn is_action_up(&self, hid: &Hid, action: A) -> bool {
+        hid.keys_up().intersects(self.binding(action))
+    }
+}
+
+impl<A: Copy + Eq> Default for ActionMap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Squared distance between two points.
+fn distance_squared(a: (u16, u16), b: (u16, u16)) -> u32 {
+    let dx = u32::from(a.0.abs_diff(b.0));
+    let dy = u32::from(a.1.abs_diff(b.1));
+
+    dx * dx + dy * dy
+}
+
+/// Builder to configure which optional features a [`Hid`] handle enables.
+///
+/// By default only the basic HID functionality (buttons, touch screen and circle pad) is available,
+/// since every additional feature has a cost.
+///
+/// See [`Hid::builder()`] to learn how to use this.
+#[derive(Clone, Copy, Debug, Default)]
+#[must_use]
+pub struct HidBuilder {
+    irrst: bool,
+    accelerometer: bool,
+    gyroscope: bool,
+}
+
+impl HidBuilder {
+    /// Enable the additional controls of the New 3DS models and of the Circle Pad Pro (ZL and ZR buttons and C-Stick).
+    ///
+    /// This opens a session to the `ir:rst` service and maps its shared memory. See [`Hid::enable_extra_controls()`].
+    pub fn with_irrst(mut self) -> Self {
+        self.irrst = true;
+        self
+    }
+
+    /// Enable the accelerometer.
+    ///
+    /// This powers on the sensor, slightly increasing battery consumption. See [`Hid::enable_accelerometer()`].
+    pub fn with_accelerometer(mut self) -> Self {
+        self.accelerometer = true;
+        self
+    }
+
+    /// Enable the gyroscope.
+    ///
+    /// This powers on the sensor, increasing battery consumption. See [`Hid::enable_gyroscope()`].
+    pub fn with_gyroscope(mut self) -> Self {
+        self.gyroscope = true;
+        self
+    }
+
+    /// Initialize the [`Hid`] handle with the selected features.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service or any of the selected features were unable to be initialized.
+    pub fn build(self) -> crate::Result<Hid> {
+        let mut hid = Hid::new()?;
+
+        if self.irrst {
+            hid.enable_extra_controls()?;
+        }
+
+        if self.accelerometer {
+            hid.enable_accelerometer()?;
+        }
+
+        if self.gyroscope {
+            hid.enable_gyroscope()?;
+        }
+
+        Ok(hid)
+    }
+}
+
+impl Drop for Hid {
+    #[doc(alias = "hidExit")]
+    fn drop(&mut self) {
+        let _ = self.stop_recording();
+
+        unsafe {
+            if self.accelerometer {
+                let _ = ctru_sys::HIDUSER_DisableAccelerometer();
+            }
+
+            if self.gyroscope {
+                let _ = ctru_sys::HIDUSER_DisableGyroscope();
+            }
+
+            if self.extra_controls {
+                ctru_sys::irrstExit();
+            }
+
+            ctru_sys::hidExit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_menu_navigation() {
+        let mut hid = Hid::new().unwrap();
+
+        // Move down twice in the menu, then confirm.
+        let script = InputScript::new()
+            .press(KeyPad::DPAD_DOWN)
+            .press(KeyPad::DPAD_DOWN)
+            .press(KeyPad::A);
+        assert_eq!(script.len(), 6);
+        hid.set_replay_source(script);
+
+        let mut selected = 0;
+        let mut confirmed = false;
+
+        while hid.is_replaying() {
+            hid.scan_input();
+
+            if hid.keys_down().contains(KeyPad::DPAD_DOWN) {
+                selected += 1;
+            }
+            if hid.keys_down().contains(KeyPad::A) {
+                confirmed = true;
+            }
+        }
+
+        assert_eq!(selected, 2);
+        assert!(confirmed);
+    }
+
+    /// Feed `frames` to the detector, returning the recognized gestures.
+    fn gestures(detector: &mut TouchGesture, frames: &[Option<(u16, u16)>]) -> Vec<GestureEvent> {
+        frames
+            .iter()
+            .filter_map(|&position| detector.update_with(position))
+            .collect()
+    }
+
+    /// Touch `position` for `frames` frames, then release it for a frame.
+    fn tap(position: (u16, u16), frames: usize) -> Vec<Option<(u16, u16)>> {
+        let mut touch = vec![Some(position); frames];
+        touch.push(None);
+        touch
+    }
+
+    #[test]
+    fn tap_and_double_tap() {
+        let mut detector = TouchGesture::new();
+
+        let mut frames = tap((100, 100), 3);
+        frames.extend(tap((102, 101), 3));
+        frames.extend(tap((100, 100), 3));
+
+        assert_eq!(
+            gestures(&mut detector, &frames),
+            [
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+                GestureEvent::DoubleTap {
+                    position: (102, 101)
+                },
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn taps_too_far_apart() {
+        let mut detector = TouchGesture::new();
+
+        // Too far in time.
+        let mut frames = tap((100, 100), 3);
+        frames.extend([None; 25]);
+        frames.extend(tap((100, 100), 3));
+        // Too far in space.
+        frames.extend(tap((150, 100), 3));
+
+        assert_eq!(
+            gestures(&mut detector, &frames),
+            [
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+                GestureEvent::Tap {
+                    position: (150, 100)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_press() {
+        let mut detector = TouchGesture::new();
+
+        assert_eq!(gestures(&mut detector, &tap((10, 10), 30)), []);
+    }
+
+    #[test]
+    fn drag() {
+        let mut detector = TouchGesture::new();
+
+        let mut frames = vec![
+            Some((10, 10)),
+            Some((14, 10)),
+            Some((20, 12)),
+            Some((20, 12)),
+            Some((25, 10)),
+        ];
+        // Hold still for long enough not to be a swipe.
+        frames.extend([Some((25, 10)); 30]);
+        frames.push(None);
+
+        assert_eq!(
+            gestures(&mut detector, &frames),
+            [
+                GestureEvent::DragStart {
+                    start: (10, 10),
+                    position: (20, 12)
+                },
+                GestureEvent::DragMove {
+                    position: (25, 10),
+                    delta: (5, -2)
+                },
+                GestureEvent::DragEnd { position: (25, 10) },
+            ]
+        );
+    }
+
+    #[test]
+    fn swipes() {
+        let swipe = |end: (u16, u16)| {
+            let mut detector = TouchGesture::new();
+            let frames = [Some((160, 120)), Some(end), None];
+
+            gestures(&mut detector, &frames).last().copied()
+        };
+
+        assert_eq!(
+            swipe((100, 120)),
+            Some(GestureEvent::Swipe(SwipeDirection::Left))
+        );
+        assert_eq!(
+            swipe((220, 130)),
+            Some(GestureEvent::Swipe(SwipeDirection::Right))
+        );
+        assert_eq!(
+            swipe((150, 60)),
+            Some(GestureEvent::Swipe(SwipeDirection::Up))
+        );
+        assert_eq!(
+            swipe((160, 200)),
+            Some(GestureEvent::Swipe(SwipeDirection::Down))
+        );
+        assert_eq!(
+            swipe((180, 120)),
+            Some(GestureEvent::DragEnd {
+                position: (180, 120)
+            })
+        );
+    }
+
+    #[test]
+    fn drag_between_taps() {
+        let mut detector = TouchGesture::new();
+
+        let mut frames = tap((100, 100), 2);
+        frames.extend([Some((100, 100)), Some((120, 100)), None]);
+        frames.extend(tap((100, 100), 2));
+
+        assert_eq!(
+            gestures(&mut detector, &frames),
+            [
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+                GestureEvent::DragStart {
+                    start: (100, 100),
+                    position: (120, 100)
+                },
+                GestureEvent::DragEnd {
+                    position: (120, 100)
+                },
+                GestureEvent::Tap {
+                    position: (100, 100)
+                },
+            ]
+        );
     }
 }
@@ -0,0 +1,554 @@
+//! Infrared service for user applications.
+//!
+//! This service gives access to the infrared port of the console, which is used to talk to accessories.
+//! The [`CirclePadPro`] helper implements the protocol of the Circle Pad Pro (connection, packet framing and CRC checks),
+//! which makes it possible to use the accessory on Old 3DS models, where the additional controls aren't built into the console.
+//!
+//! See also <https://www.3dbrew.org/wiki/IR_Services> and <https://www.3dbrew.org/wiki/IRUSER_Shared_Memory>
+#![doc(alias = "infrared")]
+
+use std::ptr::NonNull;
+
+use libc::{free, memalign};
+
+use crate::error::ResultCode;
+use crate::services::hid::{ExtraControlsLock, Hid, KeyPad};
+
+/// Size of the memory block shared with the service.
+const SHARED_MEMORY_SIZE: usize = 0x1000;
+/// Offset of the connection status in the shared memory.
+const CONNECTION_STATUS_OFFSET: usize = 0x08;
+/// Offset of the receive buffer's info (index of the first packet, index after the last packet and packet count).
+const RECEIVE_INFO_OFFSET: usize = 0x10;
+/// Offset of the receive buffer, which starts with the info (offset and size) of each packet, followed by the packets' data.
+const RECEIVE_BUFFER_OFFSET: usize = 0x20;
+
+/// Size of the info of each packet in the receive and send buffers.
+const PACKET_INFO_SIZE: usize = 8;
+/// Maximum size of the packets read from the Circle Pad Pro, including their header and CRC.
+const MAX_PACKET_SIZE: usize = 32;
+/// Number of packets the receive buffer can hold. The accessory sends a few packets per frame.
+const RECEIVE_PACKET_COUNT: usize = 16;
+const RECEIVE_BUFFER_SIZE: usize = RECEIVE_PACKET_COUNT * (PACKET_INFO_SIZE + MAX_PACKET_SIZE);
+/// Size of the data section of the receive buffer, where the packets wrap around.
+const RECEIVE_DATA_SIZE: usize = RECEIVE_BUFFER_SIZE - RECEIVE_PACKET_COUNT * PACKET_INFO_SIZE;
+const SEND_PACKET_COUNT: usize = 4;
+const SEND_BUFFER_SIZE: usize = SEND_PACKET_COUNT * (PACKET_INFO_SIZE + MAX_PACKET_SIZE);
+/// Transfer rate setting for 96 kbps, used by the Circle Pad Pro.
+const BAUD_RATE: u32 = 4;
+
+const FINALIZE_IR_NOP: u16 = 0x0002;
+const REQUIRE_CONNECTION: u16 = 0x0006;
+const DISCONNECT: u16 = 0x0009;
+const SEND_IR_NOP: u16 = 0x000D;
+const INITIALIZE_IR_NOP_SHARED: u16 = 0x0018;
+const RELEASE_RECEIVED_DATA: u16 = 0x0019;
+
+/// Device ID of the Circle Pad Pro, used to connect to it.
+const CIRCLE_PAD_PRO_DEVICE_ID: u32 = 1;
+/// First byte of every packet received through the service.
+const PACKET_MAGIC: u8 = 0xA5;
+/// Request to make the Circle Pad Pro send its input periodically.
+const POLLING_REQUEST_ID: u8 = 0x01;
+/// Response of the Circle Pad Pro carrying its input.
+const INPUT_RESPONSE_ID: u8 = 0x10;
+/// Interval (in milliseconds) between the input packets sent by the Circle Pad Pro, short enough to get new input on every frame.
+const POLLING_PERIOD_MS: u8 = 8;
+
+/// Center of the raw stick axes of the Circle Pad Pro (which are 12 bits each).
+const STICK_CENTER: i32 = 0x800;
+/// Maximum value of the scaled stick axes, matching the range of the circle pad.
+const STICK_MAX: i32 = 156;
+/// Stick position past which a direction of the C-Stick is reported as held.
+const STICK_DIRECTION_THRESHOLD: i16 = 40;
+
+/// Connection status of the infrared port. See [`CirclePadPro::connection_status()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConnectionStatus {
+    /// Not connected to any device.
+    Stopped,
+    /// Trying to connect to a device.
+    Connecting,
+    /// Connected to a device.
+    Connected,
+    /// Disconnecting from a device.
+    Disconnecting,
+    /// The connection failed with an unrecoverable error.
+    Error,
+}
+
+/// State of the controls of the Circle Pad Pro, as reported by the accessory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CirclePadProInput {
+    /// Held buttons ([`KeyPad::ZL`], [`KeyPad::ZR`] and [`KeyPad::R`]) and the C-Stick directions matching the stick position.
+    pub keys: KeyPad,
+    /// Position of the stick, in the same range as the circle pad (about -156 to 156 on both axes).
+    pub stick: (i16, i16),
+    /// Battery level of the accessory, from 0 (empty) to 31 (full).
+    pub battery_level: u8,
+}
+
+/// Handle to the `ir:USER` service, connected to a Circle Pad Pro.
+///
+/// # Polling model
+///
+/// The service receives the packets sent by the accessory in the background and stores them in memory shared with the application.
+/// [`CirclePadPro::scan_input()`] must be called once per frame, right after [`Hid::scan_input()`]: it reads the packets received
+/// since the last call, checks their CRC, keeps the most recent input and reports it through the [`Hid`] handle.
+/// The ZL, ZR and R buttons are then returned by [`Hid::keys_held()`] (and the similar functions) and the stick
+/// by [`Hid::cstick_position()`], exactly like the additional controls of the New 3DS models.
+///
+/// The connection is started with [`CirclePadPro::connect()`] and established in the background. Once connected, the accessory is asked
+/// to send its input every few milliseconds. If the connection is lost, the last input is discarded and [`CirclePadPro::connect()`]
+/// must be called again.
+///
+/// # Notes
+///
+/// The infrared port can only be used by one service at a time, so this can't be used along with [`Hid::enable_extra_controls()`].
+/// On New 3DS models, the additional controls are built in and should be read with [`Hid::enable_extra_controls()`] instead.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::apt::Apt;
+/// use ctru::services::hid::{Hid, KeyPad};
+/// use ctru::services::ir_user::{CirclePadPro, ConnectionStatus};
+/// let apt = Apt::new()?;
+/// let mut hid = Hid::new()?;
+/// let mut circle_pad_pro = CirclePadPro::new()?;
+///
+/// while apt.main_loop() {
+///     hid.scan_input();
+///     circle_pad_pro.scan_input(&mut hid)?;
+///
+///     if circle_pad_pro.connection_status() == ConnectionStatus::Stopped {
+///         circle_pad_pro.connect()?;
+///     }
+///
+///     if hid.keys_down().contains(KeyPad::ZR) {
+///         println!("You have pressed the ZR button!");
+///     }
+///
+///     let (stick_x, stick_y) = hid.cstick_position();
+/// }
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct CirclePadPro {
+    handle: ctru_sys::Handle,
+    shared_memory: NonNull<u8>,
+    memory_block: ctru_sys::Handle,
+    polling_requested: bool,
+    input: Option<CirclePadProInput>,
+    _extra_controls: ExtraControlsLock,
+}
+
+impl CirclePadPro {
+    /// Initialize a new service handle, without connecting to the accessory yet.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized, e.g. because it's already in use,
+    /// or [`Error::ServiceAlreadyActive`](crate::Error::ServiceAlreadyActive) if another [`CirclePadPro`] is active
+    /// or the additional controls were enabled with [`Hid::enable_extra_controls()`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the memory shared with the service can't be allocated.
+    #[doc(alias = "IRUSER_InitializeIrNopShared")]
+    pub fn new() -> crate::Result<Self> {
+        let extra_controls = ExtraControlsLock::acquire()?;
+
+        let mut handle = 0;
+        ResultCode(unsafe {
+            ctru_sys::srvGetServiceHandle(&mut handle, b"ir:USER\0".as_ptr().cast())
+        })?;
+
+        let shared_memory = unsafe { memalign(0x1000, SHARED_MEMORY_SIZE) }.cast::<u8>();
+        let shared_memory =
+            NonNull::new(shared_memory).expect("failed to allocate the ir:USER shared memory");
+        unsafe { shared_memory.as_ptr().write_bytes(0, SHARED_MEMORY_SIZE) };
+
+        let mut memory_block = 0;
+        let result = unsafe {
+            ctru_sys::svcCreateMemoryBlock(
+                &mut memory_block,
+                shared_memory.as_ptr() as u32,
+                SHARED_MEMORY_SIZE as u32,
+                ctru_sys::MEMPERM_READ,
+                ctru_sys::MEMPERM_READ | ctru_sys::MEMPERM_WRITE,
+            )
+        };
+
+        if ctru_sys::R_FAILED(result) {
+            unsafe {
+                free(shared_memory.as_ptr().cast());
+                let _ = ctru_sys::svcCloseHandle(handle);
+            }
+        }
+        ResultCode(result)?;
+
+        // From now on, the resources are released by `Drop`.
+        let circle_pad_pro = Self {
+            handle,
+            shared_memory,
+            memory_block,
+            polling_requested: false,
+            input: None,
+            _extra_controls: extra_controls,
+        };
+
+        unsafe {
+            circle_pad_pro.send_request(&[
+                ctru_sys::IPC_MakeHeader(INITIALIZE_IR_NOP_SHARED, 6, 2),
+                SHARED_MEMORY_SIZE as u32,
+                RECEIVE_BUFFER_SIZE as u32,
+                RECEIVE_PACKET_COUNT as u32,
+                SEND_BUFFER_SIZE as u32,
+                SEND_PACKET_COUNT as u32,
+                BAUD_RATE,
+                ctru_sys::IPC_Desc_SharedHandles(1),
+                memory_block,
+            ])?;
+        }
+
+        Ok(circle_pad_pro)
+    }
+
+    /// Start connecting to the Circle Pad Pro in the background.
+    ///
+    /// Use [`CirclePadPro::connection_status()`] to check whether the connection was established.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection attempt couldn't be started.
+    #[doc(alias = "IRUSER_RequireConnection")]
+    pub fn connect(&mut self) -> crate::Result<()> {
+        unsafe {
+            self.send_request(&[
+                ctru_sys::IPC_MakeHeader(REQUIRE_CONNECTION, 1, 0),
+                CIRCLE_PAD_PRO_DEVICE_ID,
+            ])
+        }
+    }
+
+    /// Disconnect from the Circle Pad Pro.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service couldn't be reached.
+    #[doc(alias = "IRUSER_Disconnect")]
+    pub fn disconnect(&mut self) -> crate::Result<()> {
+        unsafe { self.send_request(&[ctru_sys::IPC_MakeHeader(DISCONNECT, 0, 0)]) }
+    }
+
+    /// Returns the connection status of the infrared port.
+    pub fn connection_status(&self) -> ConnectionStatus {
+        match unsafe { self.read_shared(CONNECTION_STATUS_OFFSET) } {
+            0 => ConnectionStatus::Stopped,
+            1 => ConnectionStatus::Connecting,
+            2 => ConnectionStatus::Connected,
+            3 => ConnectionStatus::Disconnecting,
+            _ => ConnectionStatus::Error,
+        }
+    }
+
+    /// Returns the most recent input read by [`CirclePadPro::scan_input()`], or [`None`] if no input was received since the connection started.
+    pub fn input(&self) -> Option<CirclePadProInput> {
+        self.input
+    }
+
+    /// Read the input received from the Circle Pad Pro and report it through `hid`.
+    ///
+    /// This must be called once per frame, right after [`Hid::scan_input()`]. See the [polling model](CirclePadPro#polling-model).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service couldn't be reached.
+    #[doc(alias = "IRUSER_ReleaseReceivedData")]
+    pub fn scan_input(&mut self, hid: &mut Hid) -> crate::Result<()> {
+        let connected = self.connection_status() == ConnectionStatus::Connected;
+
+        if connected {
+            if !self.polling_requested {
+                self.send(&[
+                    POLLING_REQUEST_ID,
+                    POLLING_PERIOD_MS,
+                    (POLLING_PERIOD_MS + 2) << 2,
+                ])?;
+                self.polling_requested = true;
+            }
+        } else {
+            self.polling_requested = false;
+            self.input = None;
+        }
+
+        let first_packet = unsafe { self.read_shared_u32(RECEIVE_INFO_OFFSET) } as usize;
+        let packet_count = unsafe { self.read_shared_u32(RECEIVE_INFO_OFFSET + 8) };
+
+        // The packets' info is stored in a ring buffer, followed by the packets' data (in another ring buffer).
+        let data = RECEIVE_BUFFER_OFFSET + RECEIVE_PACKET_COUNT * PACKET_INFO_SIZE;
+
+        for i in 0..packet_count as usize {
+            let index = (first_packet + i) % RECEIVE_PACKET_COUNT;
+            let info = RECEIVE_BUFFER_OFFSET + index * PACKET_INFO_SIZE;
+            let offset = unsafe { self.read_shared_u32(info) } as usize;
+            let len = unsafe { self.read_shared_u32(info + 4) } as usize;
+
+            if len > MAX_PACKET_SIZE {
+                continue;
+            }
+
+            let mut packet = [0; MAX_PACKET_SIZE];
+            for (j, byte) in packet[..len].iter_mut().enumerate() {
+                *byte = unsafe { self.read_shared(data + (offset + j) % RECEIVE_DATA_SIZE) };
+            }
+
+            if let Some(input) = parse_input_packet(&packet[..len]) {
+                self.input = Some(input);
+            }
+        }
+
+        if packet_count > 0 {
+            unsafe {
+                self.send_request(&[
+                    ctru_sys::IPC_MakeHeader(RELEASE_RECEIVED_DATA, 1, 0),
+                    packet_count,
+                ])?;
+            }
+        }
+
+        hid.set_circle_pad_pro_input(connected, self.input);
+
+        Ok(())
+    }
+
+    /// Send a packet to the connected device. The service takes care of the packet framing.
+    #[doc(alias = "IRUSER_SendIrNop")]
+    fn send(&mut self, payload: &[u8]) -> crate::Result<()> {
+        unsafe {
+            self.send_request(&[
+                ctru_sys::IPC_MakeHeader(SEND_IR_NOP, 1, 2),
+                payload.len() as u32,
+                ctru_sys::IPC_Desc_StaticBuffer(payload.len(), 0),
+                payload.as_ptr() as u32,
+            ])
+        }
+    }
+
+    /// Send a request to the service and check its result.
+    ///
+    /// # Safety
+    ///
+    /// `request` must be a valid request for the service, and any buffer it points to must be valid for the duration of the call.
+    unsafe fn send_request(&self, request: &[u32]) -> crate::Result<()> {
+        let command_buffer = ctru_sys::getThreadCommandBuffer();
+
+        command_buffer.copy_from_nonoverlapping(request.as_ptr(), request.len());
+
+        ResultCode(ctru_sys::svcSendSyncRequest(self.handle))?;
+        ResultCode(*command_buffer.add(1) as ctru_sys::Result)?;
+
+        Ok(())
+    }
+
+    /// Read a byte of the shared memory, which is written by the service at any time.
+    ///
+    /// # Safety
+    ///
+    /// `offset` must be less than [`SHARED_MEMORY_SIZE`].
+    unsafe fn read_shared(&self, offset: usize) -> u8 {
+        self.shared_memory.as_ptr().add(offset).read_volatile()
+    }
+
+    /// Read a (little-endian) word of the shared memory, which is written by the service at any time.
+    ///
+    /// # Safety
+    ///
+    /// `offset` must be aligned to 4 bytes and less than [`SHARED_MEMORY_SIZE`].
+    unsafe fn read_shared_u32(&self, offset: usize) -> u32 {
+        self.shared_memory
+            .as_ptr()
+            .add(offset)
+            .cast::<u32>()
+            .read_volatile()
+    }
+}
+
+impl Drop for CirclePadPro {
+    #[doc(alias = "IRUSER_FinalizeIrNop")]
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.send_request(&[ctru_sys::IPC_MakeHeader(FINALIZE_IR_NOP, 0, 0)]);
+
+            let _ = ctru_sys::svcCloseHandle(self.memory_block);
+            free(self.shared_memory.as_ptr().cast());
+
+            let _ = ctru_sys::svcCloseHandle(self.handle);
+        }
+    }
+}
+
+/// Parse a packet received from the Circle Pad Pro, returning its input if it's a valid input response.
+///
+/// Packets start with [`PACKET_MAGIC`] and the network ID, followed by the payload size (in 1 or 2 bytes),
+/// the payload and the CRC-8 of everything before it.
+fn parse_input_packet(packet: &[u8]) -> Option<CirclePadProInput> {
+    let (&crc, frame) = packet.split_last()?;
+
+    if frame.len() < 3 || frame[0] != PACKET_MAGIC || crc8(frame) != crc {
+        return None;
+    }
+
+    // Sizes which don't fit in 6 bits take 2 bytes, with bit 6 of the first byte set.
+    let (len, payload) = if frame[2] & 0x40 != 0 {
+        let len = usize::from(frame[2] & 0x3F) << 8 | usize::from(*frame.get(3)?);
+        (len, &frame[4..])
+    } else {
+        (usize::from(frame[2]), &frame[3..])
+    };
+
+    if payload.len() != len {
+        return None;
+    }
+
+    let [INPUT_RESPONSE_ID, x_low, xy, y_high, buttons, _] = *payload else {
+        return None;
+    };
+
+    // Both axes are 12 bits long, packed into 3 bytes.
+    let scale =
+        |raw: u16| ((i32::from(raw) - STICK_CENTER) * STICK_MAX / (STICK_CENTER - 1)) as i16;
+    let stick = (
+        scale(u16::from(x_low) | u16::from(xy & 0x0F) << 8),
+        scale(u16::from(xy >> 4) | u16::from(y_high) << 4),
+    );
+
+    // The buttons are reported as released when their bit is set.
+    let mut keys = KeyPad::empty();
+    keys.set(KeyPad::ZL, buttons & 0x20 == 0);
+    keys.set(KeyPad::ZR, buttons & 0x40 == 0);
+    keys.set(KeyPad::R, buttons & 0x80 == 0);
+    keys.set(KeyPad::CSTICK_RIGHT, stick.0 > STICK_DIRECTION_THRESHOLD);
+    keys.set(KeyPad::CSTICK_LEFT, stick.0 < -STICK_DIRECTION_THRESHOLD);
+    keys.set(KeyPad::CSTICK_UP, stick.1 > STICK_DIRECTION_THRESHOLD);
+    keys.set(KeyPad::CSTICK_DOWN, stick.1 < -STICK_DIRECTION_THRESHOLD);
+
+    Some(CirclePadProInput {
+        keys,
+        stick,
+        battery_level: buttons & 0x1F,
+    })
+}
+
+/// CRC-8 (with polynomial 0x07) used by the infrared packets.
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame `payload` like the service does, appending the CRC.
+    fn packet(payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![PACKET_MAGIC, 0x00, payload.len() as u8];
+        packet.extend_from_slice(payload);
+        packet.push(crc8(&packet));
+        packet
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc8(b""), 0x00);
+        assert_eq!(crc8(b"123456789"), 0xF4);
+    }
+
+    #[test]
+    fn centered_input() {
+        // Stick centered, no button held (the released bits are set) and a full battery.
+        let input = parse_input_packet(&packet(&[INPUT_RESPONSE_ID, 0x00, 0x08, 0x80, 0xFF, 0x00]));
+
+        assert_eq!(
+            input,
+            Some(CirclePadProInput {
+                keys: KeyPad::empty(),
+                stick: (0, 0),
+                battery_level: 31,
+            })
+        );
+    }
+
+    #[test]
+    fn held_buttons_and_stick() {
+        // x = 0xFFF (right), y = 0x000 (down), ZL and R held.
+        let input = parse_input_packet(&packet(&[INPUT_RESPONSE_ID, 0xFF, 0x0F, 0x00, 0x4A, 0x00]))
+            .unwrap();
+
+        assert_eq!(input.stick, (156, -156));
+        assert_eq!(
+            input.keys,
+            KeyPad::ZL | KeyPad::R | KeyPad::CSTICK_RIGHT | KeyPad::CSTICK_DOWN
+        );
+        assert_eq!(input.battery_level, 10);
+    }
+
+    #[test]
+    fn invalid_packets() {
+        let valid = packet(&[INPUT_RESPONSE_ID, 0x00, 0x08, 0x80, 0xFF, 0x00]);
+
+        // Wrong CRC.
+        let mut corrupted = valid.clone();
+        corrupted[4] ^= 1;
+        assert_eq!(parse_input_packet(&corrupted), None);
+
+        // Wrong magic.
+        let mut frame = valid[..valid.len() - 1].to_vec();
+        frame[0] = 0x00;
+        let crc = crc8(&frame);
+        frame.push(crc);
+        assert_eq!(parse_input_packet(&frame), None);
+
+        // Wrong size.
+        let mut frame = valid[..valid.len() - 1].to_vec();
+        frame[2] = 5;
+        let crc = crc8(&frame);
+        frame.push(crc);
+        assert_eq!(parse_input_packet(&frame), None);
+
+        // Other responses.
+        assert_eq!(parse_input_packet(&packet(&[0x11, 0, 0, 0, 0, 0])), None);
+        assert_eq!(parse_input_packet(&packet(&[INPUT_RESPONSE_ID])), None);
+
+        // Truncated packets.
+        assert_eq!(parse_input_packet(&[]), None);
+        assert_eq!(parse_input_packet(&valid[..3]), None);
+    }
+
+    #[test]
+    fn extended_size() {
+        let payload = [INPUT_RESPONSE_ID, 0x00, 0x08, 0x80, 0xFF, 0x00];
+
+        let mut frame = vec![PACKET_MAGIC, 0x00, 0x40, payload.len() as u8];
+        frame.extend_from_slice(&payload);
+        let crc = crc8(&frame);
+        frame.push(crc);
+
+        assert_eq!(
+            parse_input_packet(&frame).map(|input| input.stick),
+            Some((0, 0))
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! MCU Hardware Controller service.
+//!
+//! The MCU is a microcontroller which handles low-level hardware functionality, such as the console's LEDs and power management.
+//! This service provides access to some of its registers, which can be used (among other things) to control the notification LED.
+//!
+//! See also <https://www.3dbrew.org/wiki/MCU_Services>
+#![doc(alias = "led")]
+
+use crate::error::ResultCode;
+use crate::services::gspgpu::Color;
+use crate::services::hid::{Hid, KeyPad};
+
+/// MCU register holding the notification LED pattern.
+const NOTIFICATION_LED_REGISTER: u8 = 0x2D;
+
+/// Number of steps in a [`LedPattern`].
+pub const LED_PATTERN_STEPS: usize = 32;
+
+/// Animation played by the notification LED.
+///
+/// The pattern is made of [`LED_PATTERN_STEPS`] colours which are shown one after another.
+///
+/// See [`McuHwc::set_notification_led()`] to learn how to use this.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct LedPattern {
+    /// Time spent on each step of the pattern (higher is slower).
+    pub delay: u8,
+    /// Amount of smoothing between steps (0 for none).
+    pub smoothing: u8,
+    /// Time to wait before repeating the pattern. `0xFF` means the pattern is not repeated.
+    pub loop_delay: u8,
+    _unknown: u8,
+    /// Red channel of each step.
+    pub red: [u8; LED_PATTERN_STEPS],
+    /// Green channel of each step.
+    pub green: [u8; LED_PATTERN_STEPS],
+    /// Blue channel of each step.
+    pub blue: [u8; LED_PATTERN_STEPS],
+}
+
+impl LedPattern {
+    /// Pattern that turns the LED off.
+    pub const OFF: Self = Self::solid(Color::BLACK);
+
+    /// Create a pattern where every step has the same colour, keeping the LED lit.
+    pub const fn solid(color: Color) -> Self {
+        Self {
+            delay: 0xFF,
+            smoothing: 0,
+            loop_delay: 0xFF,
+            _unknown: 0,
+            red: [color.r; LED_PATTERN_STEPS],
+            green: [color.g; LED_PATTERN_STEPS],
+            blue: [color.b; LED_PATTERN_STEPS],
+        }
+    }
+
+    /// Create a pattern that briefly flashes the LED once with the specified colour.
+    pub fn flash(color: Color) -> Self {
+        let mut pattern = Self {
+            delay: 0x20,
+            smoothing: 0,
+            loop_delay: 0xFF,
+            ..Self::OFF
+        };
+
+        let lit = LED_PATTERN_STEPS / 4;
+        pattern.red[..lit].fill(color.r);
+        pattern.green[..lit].fill(color.g);
+        pattern.blue[..lit].fill(color.b);
+
+        pattern
+    }
+
+    /// Create a pattern that repeatedly plays the specified animation with the specified colour.
+    pub fn blink(color: Color, blink: BlinkPattern) -> Self {
+        let (delay, smoothing, period) = match blink {
+            BlinkPattern::Solid => return Self::solid(color),
+            BlinkPattern::Slow => (0x20, 0, LED_PATTERN_STEPS),
+            BlinkPattern::Fast => (0x08, 0, LED_PATTERN_STEPS / 4),
+            BlinkPattern::Breathing => (0x20, 0xFF, LED_PATTERN_STEPS),
+        };
+
+        let mut pattern = Self {
+            delay,
+            smoothing,
+            loop_delay: 0,
+            ..Self::OFF
+        };
+
+        for step in 0..LED_PATTERN_STEPS {
+            let phase = step % period;
+
+            let lit = match blink {
+                // Ramp up during the first half of the period and down during the second one.
+                BlinkPattern::Breathing => {
+                    let distance = phase.min(period - phase) as u32;
+                    (distance * 0xFF / (period as u32 / 2)) as u8
+                }
+                _ if phase < period / 2 => 0xFF,
+                _ => 0,
+            };
+
+            pattern.red[step] = (color.r as u32 * lit as u32 / 0xFF) as u8;
+            pattern.green[step] = (color.g as u32 * lit as u32 / 0xFF) as u8;
+            pattern.blue[step] = (color.b as u32 * lit as u32 / 0xFF) as u8;
+        }
+
+        pattern
+    }
+}
+
+/// Animation used by [`LedPattern::blink()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum BlinkPattern {
+    /// The LED stays lit.
+    #[default]
+    Solid,
+    /// The LED is lit for about a second, then turns off for about a second.
+    Slow,
+    /// The LED turns on and off several times per second.
+    Fast,
+    /// The LED slowly fades in and out.
+    Breathing,
+}
+
+/// Handle to the MCU Hardware Controller service.
+pub struct McuHwc(());
+
+impl McuHwc {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// Access to this service is restricted, so it may be unavailable depending on the environment the application runs in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::mcuhwc::McuHwc;
+    ///
+    /// let mcu = McuHwc::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "mcuHwcInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::mcuHwcInit() })?;
+
+        Ok(Self(()))
+    }
+
+    /// Play a pattern on the notification LED.
+    ///
+    /// # Notes
+    ///
+    /// The pattern keeps playing after the application exits, so make sure to turn the LED off (with [`LedPattern::OFF`]) when it isn't needed anymore.
+    /// Patterns for common animations can be built with [`LedPattern::blink()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gspgpu::Color;
+    /// use ctru::services::mcuhwc::{BlinkPattern, LedPattern, McuHwc};
+    /// let mut mcu = McuHwc::new()?;
+    ///
+    /// mcu.set_notification_led(&LedPattern::solid(Color::BLUE))?;
+    ///
+    /// mcu.set_notification_led(&LedPattern::blink(Color::GREEN, BlinkPattern::Slow))?;
+    ///
+    /// mcu.set_notification_led(&LedPattern::OFF)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "MCUHWC_WriteRegister")]
+    pub fn set_notification_led(&mut self, pattern: &LedPattern) -> crate::Result<()> {
+        ResultCode(unsafe {
+            ctru_sys::MCUHWC_WriteRegister(
+                NOTIFICATION_LED_REGISTER,
+                (pattern as *const LedPattern).cast(),
+                std::mem::size_of::<LedPattern>() as u32,
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Enable or disable the slow "breathing" animation of the power LED, normally shown while the console is in sleep mode.
+    ///
+    /// When disabled, the power LED goes back to its normal state (steady blue, or red when the battery is low).
+    ///
+    /// # Notes
+    ///
+    /// The power LED state is held by MCU register `0x29`, while the notification LED pattern (see [`McuHwc::set_notification_led()`]) is held by register `0x2D`.
+    /// Unlike the notification LED pattern, which is kept until it's changed or the console is turned off, the power LED state
+    /// is also set by the system whenever the console enters or leaves sleep mode, so the setting doesn't survive a sleep cycle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::mcuhwc::{LedPattern, McuHwc};
+    /// let mut mcu = McuHwc::new()?;
+    ///
+    /// // "Do not disturb": no notifications and a steady power LED.
+    /// mcu.set_notification_led(&LedPattern::OFF)?;
+    /// mcu.set_sleep_led(false)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "MCUHWC_SetPowerLedState")]
+    pub fn set_sleep_led(&mut self, enabled: bool) -> crate::Result<()> {
+        let state = if enabled {
+            ctru_sys::LED_SLEEP_MODE
+        } else {
+            ctru_sys::LED_NORMAL
+        };
+
+        ResultCode(unsafe { ctru_sys::MCUHWC_SetPowerLedState(state) })?;
+
+        Ok(())
+    }
+
+    /// Flash the notification LED with the specified colour if any of `keys` has just been pressed.
+    ///
+    /// Returns `true` if the LED was flashed.
+    ///
+    /// # Notes
+    ///
+    /// This function checks [`Hid::keys_down()`], so it should be called once per frame, after [`Hid::scan_input()`].
+    /// It can be used to provide visual feedback for button presses (e.g. for accessibility purposes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::gspgpu::Color;
+    /// use ctru::services::hid::{Hid, KeyPad};
+    /// use ctru::services::mcuhwc::McuHwc;
+    /// let mut hid = Hid::new()?;
+    /// let mut mcu = McuHwc::new()?;
+    ///
+    /// hid.scan_input();
+    ///
+    /// mcu.flash_led_on_key(&hid, KeyPad::A | KeyPad::B, Color::GREEN)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flash_led_on_key(
+        &mut self,
+        hid: &Hid,
+        keys: KeyPad,
+        color: Color,
+    ) -> crate::Result<bool> {
+        if !hid.keys_down().intersects(keys) {
+            return Ok(false);
+        }
+
+        self.set_notification_led(&LedPattern::flash(color))?;
+
+        Ok(true)
+    }
+}
+
+impl Drop for McuHwc {
+    #[doc(alias = "mcuHwcExit")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::mcuHwcExit() };
+    }
+}
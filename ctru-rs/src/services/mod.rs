@@ -10,20 +10,32 @@
 //! which enables all network communications via sockets.
 //!
 //! In [`ctru-rs`](crate) some services only allow a single handle to be created at a time, to ensure a safe and controlled environment.
+//!
+//! All service handles implement the [`Service`] trait, which allows initializing several of them at once in a [`Services`] bundle.
 
+pub mod ac;
 pub mod am;
 pub mod apt;
 pub mod cam;
 pub mod cfgu;
+pub mod csnd;
 pub mod fs;
 pub mod gfx;
 pub mod gspgpu;
+pub mod gsplcd;
 pub mod hid;
+pub mod ir_user;
+pub mod mcuhwc;
 pub mod ndsp;
+pub mod pm;
 pub mod ps;
+pub mod ptm;
 mod reference;
+mod service;
 pub mod soc;
+pub mod srv;
 pub mod sslc;
+pub mod y2r;
 
 cfg_if::cfg_if! {
     if #[cfg(all(feature = "romfs", romfs_exists))] {
@@ -49,3 +61,4 @@ cfg_if::cfg_if! {
 }
 
 pub(crate) use self::reference::ServiceReference;
+pub use self::service::{Service, Services};
@@ -0,0 +1,266 @@
+//! Sound effects mixer.
+//!
+//! This module provides a [`SoundMixer`], which plays [`Sound`]s on a pool of NDSP channels without the need to manage each channel manually.
+
+use super::wave::{self, WavError};
+use super::{AudioFormat, AudioMix, Channel, Ndsp, NdspError};
+use crate::linear::LinearAllocator;
+
+use std::rc::Rc;
+
+/// Audio data which can be played by a [`SoundMixer`], even multiple times at once.
+///
+/// Cloning a [`Sound`] is cheap, since the audio data (on [LINEAR memory](crate::linear)) is shared between the clones.
+#[derive(Clone)]
+pub struct Sound {
+    data: Rc<Box<[u8], LinearAllocator>>,
+    format: AudioFormat,
+    sample_rate: f32,
+}
+
+impl Sound {
+    /// Build a new sound from a raw buffer on [LINEAR memory](crate::linear), its format and its sample rate (in Hz).
+    pub fn new(data: Box<[u8], LinearAllocator>, format: AudioFormat, sample_rate: f32) -> Self {
+        // See `Wave::new()`.
+        unsafe {
+            let _r = ctru_sys::DSP_FlushDataCache(data.as_ptr().cast(), data.len() as u32);
+        }
+
+        Self {
+            data: Rc::new(data),
+            format,
+            sample_rate,
+        }
+    }
+
+    /// Build a new sound from the contents of a WAV file.
+    ///
+    /// See [`Wave::from_wav()`](super::wave::Wave::from_wav) for the supported formats.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the WAV file is malformed or if its audio format is unsupported.
+    pub fn from_wav(data: &[u8]) -> Result<Self, WavError> {
+        let (buffer, info) = wave::decode_wav(data)?;
+
+        Ok(Self::new(buffer, info.format, info.sample_rate as f32))
+    }
+
+    /// Returns the format of the audio data.
+    pub fn format(&self) -> AudioFormat {
+        self.format
+    }
+
+    /// Returns the sample rate (in Hz) of the audio data.
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Returns the amount of samples in the audio data.
+    pub fn sample_count(&self) -> usize {
+        self.data.len() / self.format.size()
+    }
+}
+
+/// Mixer which plays overlapping [`Sound`]s on a pool of NDSP channels.
+///
+/// Every call to [`SoundMixer::play()`] uses a channel of the pool, which is reclaimed automatically once the sound is over.
+///
+/// # Voice stealing
+///
+/// The mixer can play at most as many sounds at once as the channels it was given (24 at most, the number of NDSP channels).
+/// If every channel is busy when a new sound is played, the sound that started playing first is stopped to make room for the new one.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::ndsp::mixer::{Sound, SoundMixer};
+/// use ctru::services::ndsp::Ndsp;
+/// let ndsp = Ndsp::new()?;
+///
+/// // Leave the first channel for the background music.
+/// let mut mixer = SoundMixer::new(&ndsp, 1..24)?;
+///
+/// let explosion = Sound::from_wav(&std::fs::read("romfs:/explosion.wav")?)?;
+///
+/// // Both explosions play at the same time.
+/// mixer.play(&explosion, 1.0, -0.5);
+/// mixer.play(&explosion, 0.5, 0.5);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct SoundMixer<'ndsp> {
+    voices: Vec<Voice<'ndsp>>,
+    plays: u64,
+}
+
+/// A channel of the mixer's pool.
+struct Voice<'ndsp> {
+    channel: Channel<'ndsp>,
+    playing: Option<Playing>,
+}
+
+/// A sound being played on a [`Voice`].
+struct Playing {
+    // The DSP reads both the wave buffer and the audio data until playback ends or the channel is cleared.
+    wave_buf: Box<ctru_sys::ndspWaveBuf>,
+    _sound: Sound,
+    /// Number of the play that started this sound, used to find the oldest sound.
+    started: u64,
+}
+
+impl Playing {
+    fn is_done(&self) -> bool {
+        self.wave_buf.status == ctru_sys::NDSP_WBUF_DONE as u8
+    }
+}
+
+impl<'ndsp> SoundMixer<'ndsp> {
+    /// Create a mixer which plays sounds on the channels with the specified IDs.
+    ///
+    /// The mixer takes control of the channels until it's dropped, and changes their format, sample rate and mix on every play.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the channel IDs is not between 0 and 23 or if the channel is already being used.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if no channel IDs are specified.
+    pub fn new(
+        ndsp: &'ndsp Ndsp,
+        channel_ids: impl IntoIterator<Item = u8>,
+    ) -> Result<Self, NdspError> {
+        let voices = channel_ids
+            .into_iter()
+            .map(|id| {
+                ndsp.channel(id).map(|channel| Voice {
+                    channel,
+                    playing: None,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert!(
+            !voices.is_empty(),
+            "the mixer needs at least one channel to play sounds"
+        );
+
+        Ok(Self { voices, plays: 0 })
+    }
+
+    /// Play a sound with the specified volume and pan.
+    ///
+    /// `volume` is a multiplier of the sound's amplitude (1.0 leaves it unchanged),
+    /// while `pan` ranges from -1.0 (left speaker only) to 1.0 (right speaker only), with 0.0 playing on both speakers at full volume.
+    ///
+    /// # Notes
+    ///
+    /// If every channel of the mixer is busy, the oldest sound is stopped to play this one. See [voice stealing](SoundMixer#voice-stealing).
+    ///
+    /// Sounds without any samples are ignored, since the DSP would never mark them as finished and their channel would stay busy.
+    #[doc(alias = "ndspChnWaveBufAdd")]
+    pub fn play(&mut self, sound: &Sound, volume: f32, pan: f32) {
+        if sound.sample_count() == 0 {
+            return;
+        }
+
+        let pan = pan.clamp(-1.0, 1.0);
+
+        self.plays += 1;
+        let started = self.plays;
+
+        let voice = self.free_voice();
+        let channel = &mut voice.channel;
+
+        // Stop the stolen sound (if any) before its data is released.
+        channel.clear_queue();
+        voice.playing = None;
+
+        channel.set_format(sound.format);
+        channel.set_sample_rate(sound.sample_rate);
+
+        let mut mix = AudioMix::zeroed();
+        mix.set_front(volume * (1.0 - pan).min(1.0), volume * (1.0 + pan).min(1.0));
+        channel.set_mix(&mix);
+
+        let mut wave_buf = Box::new(ctru_sys::ndspWaveBuf {
+            __bindgen_anon_1: ctru_sys::tag_ndspWaveBuf__bindgen_ty_1 {
+                data_vaddr: sound.data.as_ptr().cast(),
+            },
+            nsamples: sound.sample_count() as u32,
+            adpcm_data: std::ptr::null_mut(),
+            offset: 0,
+            looping: false,
+            status: 0,
+            sequence_id: 0,
+            next: std::ptr::null_mut(),
+        });
+
+        unsafe { ctru_sys::ndspChnWaveBufAdd(channel.id().into(), wave_buf.as_mut()) };
+
+        voice.playing = Some(Playing {
+            wave_buf,
+            _sound: sound.clone(),
+            started,
+        });
+    }
+
+    /// Stop all sounds played by the mixer.
+    #[doc(alias = "ndspChnWaveBufClear")]
+    pub fn stop_all(&mut self) {
+        for voice in &mut self.voices {
+            voice.channel.clear_queue();
+            voice.playing = None;
+        }
+    }
+
+    /// Returns the number of sounds currently playing.
+    pub fn playing_count(&self) -> usize {
+        self.voices
+            .iter()
+            .filter(|voice| {
+                voice
+                    .playing
+                    .as_ref()
+                    .is_some_and(|playing| !playing.is_done())
+            })
+            .count()
+    }
+
+    /// Returns the number of channels used by the mixer, which is the maximum number of sounds it can play at once.
+    pub fn channel_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    /// Returns a channel which isn't playing anything, or the one playing the oldest sound.
+    fn free_voice(&mut self) -> &mut Voice<'ndsp> {
+        let index = self
+            .voices
+            .iter()
+            .position(|voice| voice.playing.as_ref().map_or(true, Playing::is_done))
+            .unwrap_or_else(|| {
+                self.voices
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, voice)| {
+                        voice.playing.as_ref().map_or(0, |playing| playing.started)
+                    })
+                    .map(|(index, _)| index)
+                    .unwrap()
+            });
+
+        &mut self.voices[index]
+    }
+}
+
+impl Drop for SoundMixer<'_> {
+    fn drop(&mut self) {
+        // The sounds' data must not be released while the DSP is still reading it.
+        self.stop_all();
+    }
+}
@@ -8,19 +8,23 @@
 //! something like [DSP1](https://www.gamebrew.org/wiki/DSP1_3DS).
 //!
 //! `libctru` expects to find it at `sdmc:/3ds/dspfirm.cdc` when initializing the NDSP service.
+//! If the firmware is missing, [`Ndsp::new()`] returns an error explaining so. Alternatively, the firmware can be embedded
+//! in the application and loaded with [`Ndsp::with_component()`].
 #![doc(alias = "audio")]
 
 // As a result of requiring DSP firmware to initialize, all of the doctests in
 // this module are `no_run`, since Citra doesn't provide a stub for the DSP firmware:
 // https://github.com/citra-emu/citra/issues/6111
 
+pub mod mixer;
 pub mod wave;
 use wave::{Status, Wave};
 
-use crate::error::ResultCode;
+use crate::error::{Error, ResultCode};
 use crate::services::ServiceReference;
+use ctru_sys::result::{R_DESCRIPTION, R_MODULE};
 
-use std::cell::{RefCell, RefMut};
+use std::cell::{Cell, RefCell, RefMut};
 use std::default::Default;
 use std::error;
 use std::fmt;
@@ -74,6 +78,75 @@ pub enum InterpolationType {
     None = ctru_sys::NDSP_INTERP_NONE,
 }
 
+/// Clipping mode applied to the final output mix.
+#[doc(alias = "ndspClippingMode")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ClippingMode {
+    /// Samples outside of the valid range are clamped.
+    Normal = ctru_sys::NDSP_CLIP_NORMAL,
+    /// Samples are softly compressed before reaching the limits of the valid range.
+    Soft = ctru_sys::NDSP_CLIP_SOFT,
+}
+
+/// Monopole IIR filter applied to a [`Channel`].
+///
+/// See [`Channel::set_iir_mono()`] to learn how to use this.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MonoFilter {
+    /// Low pass filter with the specified cut-off frequency (in Hz).
+    LowPass(f32),
+    /// High pass filter with the specified cut-off frequency (in Hz).
+    HighPass(f32),
+}
+
+/// Biquad IIR filter applied to a [`Channel`].
+///
+/// Frequencies are in Hz. The `quality` factor controls the width of the filter's transition band:
+/// higher values result in a sharper filter, with a resonance peak around the target frequency.
+///
+/// See [`Channel::set_iir_biquad()`] to learn how to use this.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BiquadFilter {
+    /// Low pass filter.
+    LowPass {
+        /// Cut-off frequency.
+        cut_off_freq: f32,
+        /// Quality factor.
+        quality: f32,
+    },
+    /// High pass filter.
+    HighPass {
+        /// Cut-off frequency.
+        cut_off_freq: f32,
+        /// Quality factor.
+        quality: f32,
+    },
+    /// Band pass filter.
+    BandPass {
+        /// Central frequency of the pass band.
+        mid_freq: f32,
+        /// Quality factor.
+        quality: f32,
+    },
+    /// Notch (band stop) filter.
+    Notch {
+        /// Central frequency of the stop band.
+        notch_freq: f32,
+        /// Quality factor.
+        quality: f32,
+    },
+    /// Peaking equalizer.
+    PeakingEqualizer {
+        /// Central frequency of the boosted/attenuated band.
+        central_freq: f32,
+        /// Quality factor.
+        quality: f32,
+        /// Gain applied to the band.
+        gain: f32,
+    },
+}
+
 /// Errors returned by [`ndsp`](self) functions.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum NdspError {
@@ -109,12 +182,31 @@ pub struct Channel<'ndsp> {
 
 static NDSP_ACTIVE: Mutex<usize> = Mutex::new(0);
 
+/// Number of times the queue of each channel was cleared, used to tell which [`Wave`]s were removed from it.
+static QUEUE_CLEARS: Mutex<[u32; NUMBER_OF_CHANNELS as usize]> =
+    Mutex::new([0; NUMBER_OF_CHANNELS as usize]);
+
+/// Returns the number of times the queue of the channel was cleared.
+pub(crate) fn queue_clears(id: u8) -> u32 {
+    QUEUE_CLEARS.lock().unwrap()[usize::from(id)]
+}
+
+/// Clear the queue of the channel, marking the waves it held as removed.
+pub(crate) fn clear_channel_queue(id: u8) {
+    unsafe { ctru_sys::ndspChnWaveBufClear(id.into()) };
+
+    let mut clears = QUEUE_CLEARS.lock().unwrap();
+    clears[usize::from(id)] = clears[usize::from(id)].wrapping_add(1);
+}
+
 /// Handle to the DSP service.
 ///
 /// Only one handle for this service can exist at a time.
 pub struct Ndsp {
     _service_handler: ServiceReference,
     channel_flags: [RefCell<()>; NUMBER_OF_CHANNELS as usize],
+    /// Bitmask of the channels paused by [`Ndsp::pause_all()`].
+    paused_channels: Cell<u32>,
 }
 
 impl Ndsp {
@@ -126,6 +218,8 @@ impl Ndsp {
     /// or if there are any issues during initialization (for example, DSP firmware
     /// cannot be found. See [module documentation](super::ndsp) for more details.).
     ///
+    /// If the DSP firmware is missing, the returned error is an [`Error::Context`] describing how to dump it.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -141,11 +235,74 @@ impl Ndsp {
     /// ```
     #[doc(alias = "ndspInit")]
     pub fn new() -> crate::Result<Self> {
+        Self::init(None)
+    }
+
+    /// Initialize the DSP service using the specified DSP firmware component, instead of the one dumped on the SD card.
+    ///
+    /// This makes it possible to embed the firmware in the application (e.g. with [`include_bytes!`]), so that audio works
+    /// without the user having to dump it. Keep in mind the firmware is copyrighted and shouldn't be redistributed.
+    ///
+    /// # Notes
+    ///
+    /// The component is only used if the service isn't already active.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an instance of the [`Ndsp`] struct already exists
+    /// or if the component can't be loaded on the DSP processor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    ///
+    /// # let component: &'static [u8] = &[];
+    /// // let component = include_bytes!("../dspfirm.cdc");
+    /// let ndsp = Ndsp::with_component(component)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspUseComponent")]
+    pub fn with_component(component: &'static [u8]) -> crate::Result<Self> {
+        Self::init(Some(component))
+    }
+
+    fn init(component: Option<&'static [u8]>) -> crate::Result<Self> {
         let _service_handler = ServiceReference::new(
             &NDSP_ACTIVE,
             false,
             || {
-                ResultCode(unsafe { ctru_sys::ndspInit() })?;
+                if let Some(component) = component {
+                    unsafe {
+                        ctru_sys::ndspUseComponent(
+                            component.as_ptr().cast(),
+                            component.len() as u32,
+                            0xFF,
+                            0xFF,
+                        );
+                    }
+                }
+
+                let result = unsafe { ctru_sys::ndspInit() };
+
+                // libctru returns this code when there is neither an embedded component nor a dumped one.
+                if R_MODULE(result) as u32 == ctru_sys::RM_DSP
+                    && R_DESCRIPTION(result) as u32 == ctru_sys::RD_NOT_FOUND
+                {
+                    return Err(Error::Context {
+                        description: String::from(
+                            "DSP firmware not found at sdmc:/3ds/dspfirm.cdc, dump it with DSP1",
+                        ),
+                        source: Box::new(Error::Os(result)),
+                    });
+                }
+
+                ResultCode(result)?;
 
                 Ok(())
             },
@@ -157,6 +314,7 @@ impl Ndsp {
         Ok(Self {
             _service_handler,
             channel_flags: Default::default(),
+            paused_channels: Cell::new(0),
         })
     }
 
@@ -216,6 +374,149 @@ impl Ndsp {
     pub fn set_output_mode(&mut self, mode: OutputMode) {
         unsafe { ctru_sys::ndspSetOutputMode(mode.into()) };
     }
+
+    /// Set the master volume applied to the final output mix, where `1.0` is the original volume. Defaults to `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// // Play everything at half volume.
+    /// ndsp.set_master_volume(0.5);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspSetMasterVol")]
+    pub fn set_master_volume(&mut self, volume: f32) {
+        unsafe { ctru_sys::ndspSetMasterVol(volume) };
+    }
+
+    /// Pause the playback of every channel which is currently playing.
+    ///
+    /// Paused channels keep their queued waves and their sample position, so [`Ndsp::resume_all()`] resumes them exactly where they stopped.
+    ///
+    /// # Notes
+    ///
+    /// This function only needs a shared reference, so it can be called while [`Channel`] handles are in use.
+    /// Channels which were already paused (e.g. with [`Channel::pause()`]) aren't affected by [`Ndsp::resume_all()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::Ndsp;
+    /// let ndsp = Ndsp::new()?;
+    /// let music = ndsp.channel(0)?;
+    /// # let playing = music.is_playing();
+    ///
+    /// // The game got paused: freeze all audio.
+    /// ndsp.pause_all();
+    /// # assert_eq!(music.is_paused(), playing);
+    ///
+    /// // And continue once it's resumed.
+    /// ndsp.resume_all();
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn pause_all(&self) {
+        let mut paused = self.paused_channels.get();
+
+        for id in 0..NUMBER_OF_CHANNELS {
+            unsafe {
+                if ctru_sys::ndspChnIsPlaying(id.into()) && !ctru_sys::ndspChnIsPaused(id.into()) {
+                    ctru_sys::ndspChnSetPaused(id.into(), true);
+                    paused |= 1 << id;
+                }
+            }
+        }
+
+        self.paused_channels.set(paused);
+    }
+
+    /// Resume the playback of the channels paused by [`Ndsp::pause_all()`].
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn resume_all(&self) {
+        let paused = self.paused_channels.replace(0);
+
+        for id in (0..NUMBER_OF_CHANNELS).filter(|id| paused & (1 << id) != 0) {
+            unsafe { ctru_sys::ndspChnSetPaused(id.into(), false) };
+        }
+    }
+
+    /// Set the clipping mode of the final output mix. Defaults to [`ClippingMode::Soft`].
+    #[doc(alias = "ndspSetClippingMode")]
+    pub fn set_clipping_mode(&mut self, mode: ClippingMode) {
+        unsafe { ctru_sys::ndspSetClippingMode(mode.into()) };
+    }
+}
+
+/// Functions to handle the auxiliary output buses.
+///
+/// Each [`Channel`] can send its output to the two auxiliary buses (0 or 1) through the "aux" volumes of its [`AudioMix`].
+/// This is useful to share a single effect (e.g. a reverb) between many channels, with each of them choosing how much to send to it.
+impl Ndsp {
+    /// Enable/disable the specified auxiliary output bus (either 0 or 1).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `id` isn't a valid auxiliary bus.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{AudioMix, Ndsp};
+    /// let mut ndsp = Ndsp::new()?;
+    ///
+    /// // Route the sound of channel 0 to auxiliary bus 0 at 40% volume.
+    /// ndsp.set_aux_enabled(0, true);
+    /// ndsp.set_aux_volume(0, 1.0);
+    ///
+    /// let mut mix = AudioMix::default();
+    /// mix.set_aux_front(0.4, 0.4, 0);
+    ///
+    /// let mut channel_0 = ndsp.channel(0)?;
+    /// channel_0.set_mix(&mix);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspAuxSetEnable")]
+    pub fn set_aux_enabled(&mut self, id: usize, enable: bool) {
+        unsafe { ctru_sys::ndspAuxSetEnable(aux_bus_id(id), enable) };
+    }
+
+    /// Set whether the front channels of the specified auxiliary output bus (either 0 or 1) bypass the surround processing.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `id` isn't a valid auxiliary bus.
+    #[doc(alias = "ndspAuxSetFrontBypass")]
+    pub fn set_aux_front_bypass(&mut self, id: usize, bypass: bool) {
+        unsafe { ctru_sys::ndspAuxSetFrontBypass(aux_bus_id(id), bypass) };
+    }
+
+    /// Set the output volume of the specified auxiliary output bus (either 0 or 1).
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `id` isn't a valid auxiliary bus.
+    #[doc(alias = "ndspAuxSetVolume")]
+    pub fn set_aux_volume(&mut self, id: usize, volume: f32) {
+        unsafe { ctru_sys::ndspAuxSetVolume(aux_bus_id(id), volume) };
+    }
 }
 
 impl Channel<'_> {
@@ -239,6 +540,10 @@ impl Channel<'_> {
     #[doc(alias = "ndspChnReset")]
     pub fn reset(&mut self) {
         unsafe { ctru_sys::ndspChnReset(self.id.into()) };
+
+        // Resetting the channel also clears its queue.
+        let mut clears = QUEUE_CLEARS.lock().unwrap();
+        clears[usize::from(self.id)] = clears[usize::from(self.id)].wrapping_add(1);
     }
 
     /// Initialize the channel's parameters with default values.
@@ -370,6 +675,21 @@ impl Channel<'_> {
         unsafe { ctru_sys::ndspChnSetPaused(self.id.into(), state) };
     }
 
+    /// Pause the channel's playback.
+    ///
+    /// The channel keeps its queued waves and its [sample position](Channel::sample_position), so [`Channel::resume()`]
+    /// continues exactly where the playback stopped. This is the same as calling [`Channel::set_paused()`] with `true`.
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    /// Resume the channel's playback after a call to [`Channel::pause()`].
+    #[doc(alias = "ndspChnSetPaused")]
+    pub fn resume(&mut self) {
+        self.set_paused(false);
+    }
+
     /// Set the channel's output format.
     ///
     /// Change this setting based on the used wave's format.
@@ -472,6 +792,14 @@ impl Channel<'_> {
 
     /// Clear the wave buffer queue and stop playback.
     ///
+    /// # Notes
+    ///
+    /// The DSP stops reading the queued waves at its next audio frame (within about 5 milliseconds).
+    /// From then on the waves removed from the queue aren't used anymore: their [status](Wave::status) becomes [`Status::Done`],
+    /// so they can be modified, queued again (on any channel) or dropped right away.
+    ///
+    /// The channel keeps its paused state: use [`Channel::stop()`] to also make it ready to play the next queued wave.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -490,7 +818,42 @@ impl Channel<'_> {
     /// ```
     #[doc(alias = "ndspChnWaveBufClear")]
     pub fn clear_queue(&mut self) {
-        unsafe { ctru_sys::ndspChnWaveBufClear(self.id.into()) };
+        clear_channel_queue(self.id);
+    }
+
+    /// Stop the playback right away, e.g. to switch to another music track.
+    ///
+    /// The queue is [cleared](Channel::clear_queue) and the channel is un-paused, so the next queued wave starts playing immediately.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{wave::Wave, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut music = ndsp.channel(0)?;
+    ///
+    /// let (mut town_theme, _) = Wave::from_wav(&std::fs::read("romfs:/town.wav")?, true)?;
+    /// let (mut battle_theme, _) = Wave::from_wav(&std::fs::read("romfs:/battle.wav")?, true)?;
+    ///
+    /// music.queue_wave(&mut town_theme)?;
+    ///
+    /// // A battle starts: switch tracks without hearing the rest of the old one.
+    /// music.stop();
+    /// music.queue_wave(&mut battle_theme)?;
+    ///
+    /// // The town theme isn't queued anymore, so it can be dropped or reused.
+    /// drop(town_theme);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ndspChnWaveBufClear")]
+    pub fn stop(&mut self) {
+        self.clear_queue();
+        self.set_paused(false);
     }
 
     /// Add a wave buffer to the channel's queue.
@@ -534,9 +897,16 @@ impl Channel<'_> {
             _ => (),
         }
 
-        wave.set_channel(self.id);
+        wave.set_channel(self.id, queue_clears(self.id));
 
-        unsafe { ctru_sys::ndspChnWaveBufAdd(self.id.into(), &mut wave.raw_data) };
+        unsafe {
+            // The intro and the looping part are played back to back, without any gap.
+            if wave.has_intro() {
+                ctru_sys::ndspChnWaveBufAdd(self.id.into(), &mut wave.intro_data);
+            }
+
+            ctru_sys::ndspChnWaveBufAdd(self.id.into(), &mut wave.raw_data);
+        }
 
         Ok(())
     }
@@ -546,6 +916,86 @@ impl Channel<'_> {
 ///
 /// Refer to [`libctru`](https://libctru.devkitpro.org/channel_8h.html#a1da3b363c2edfd318c92276b527daae6) for more info.
 impl Channel<'_> {
+    /// Set (or remove, with [`None`]) the monopole filter of the channel.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{MonoFilter, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// channel_0.set_iir_mono(Some(MonoFilter::HighPass(100.0)));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_iir_mono(&mut self, filter: Option<MonoFilter>) {
+        match filter {
+            Some(MonoFilter::LowPass(cut_off_freq)) => {
+                self.iir_mono_set_params_low_pass_filter(cut_off_freq)
+            }
+            Some(MonoFilter::HighPass(cut_off_freq)) => {
+                self.iir_mono_set_params_high_pass_filter(cut_off_freq)
+            }
+            None => (),
+        }
+
+        self.iir_mono_set_enabled(filter.is_some());
+    }
+
+    /// Set (or remove, with [`None`]) the biquad filter of the channel.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{BiquadFilter, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    /// let mut channel_0 = ndsp.channel(0)?;
+    ///
+    /// // Muffle the sound, as if it was heard underwater.
+    /// channel_0.set_iir_biquad(Some(BiquadFilter::low_pass(500.0)));
+    ///
+    /// // Back to the surface.
+    /// channel_0.set_iir_biquad(None);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_iir_biquad(&mut self, filter: Option<BiquadFilter>) {
+        match filter {
+            Some(BiquadFilter::LowPass {
+                cut_off_freq,
+                quality,
+            }) => self.iir_biquad_set_params_low_pass_filter(cut_off_freq, quality),
+            Some(BiquadFilter::HighPass {
+                cut_off_freq,
+                quality,
+            }) => self.iir_biquad_set_params_high_pass_filter(cut_off_freq, quality),
+            Some(BiquadFilter::BandPass { mid_freq, quality }) => {
+                self.iir_biquad_set_params_band_pass_filter(mid_freq, quality)
+            }
+            Some(BiquadFilter::Notch {
+                notch_freq,
+                quality,
+            }) => self.iir_biquad_set_params_notch_filter(notch_freq, quality),
+            Some(BiquadFilter::PeakingEqualizer {
+                central_freq,
+                quality,
+                gain,
+            }) => self.iir_biquad_set_params_peaking_equalizer(central_freq, quality, gain),
+            None => (),
+        }
+
+        self.iir_biquad_set_enabled(filter.is_some());
+    }
+
     /// Enables/disables monopole filters.
     #[doc(alias = "ndspChnIirMonoSetEnable")]
     pub fn iir_mono_set_enabled(&mut self, enable: bool) {
@@ -645,6 +1095,34 @@ impl AudioFormat {
     }
 }
 
+impl BiquadFilter {
+    /// Quality factor of a Butterworth filter, which has the flattest possible pass band.
+    pub const BUTTERWORTH_QUALITY: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+    /// Low pass filter with the specified cut-off frequency and no resonance.
+    ///
+    /// # Notes
+    ///
+    /// Some common cut-off frequencies are:
+    /// - around 500 Hz for a heavily muffled sound (e.g. underwater or behind a wall).
+    /// - around 3000 Hz for a "telephone" or radio-like sound.
+    /// - around 8000 Hz to soften harsh high frequencies.
+    pub const fn low_pass(cut_off_freq: f32) -> Self {
+        Self::LowPass {
+            cut_off_freq,
+            quality: Self::BUTTERWORTH_QUALITY,
+        }
+    }
+
+    /// High pass filter with the specified cut-off frequency and no resonance.
+    pub const fn high_pass(cut_off_freq: f32) -> Self {
+        Self::HighPass {
+            cut_off_freq,
+            quality: Self::BUTTERWORTH_QUALITY,
+        }
+    }
+}
+
 impl AudioMix {
     /// Creates a new [`AudioMix`] with all volumes set to 0.
     pub fn zeroed() -> Self {
@@ -779,6 +1257,15 @@ impl fmt::Display for NdspError {
 
 impl error::Error for NdspError {}
 
+/// Checks the validity of an auxiliary output bus index.
+fn aux_bus_id(id: usize) -> i32 {
+    if id > 1 {
+        panic!("invalid auxiliary output device index")
+    }
+
+    id as i32
+}
+
 impl Drop for Ndsp {
     #[doc(alias = "ndspExit")]
     fn drop(&mut self) {
@@ -790,4 +1277,5 @@ impl Drop for Ndsp {
 
 from_impl!(InterpolationType, ctru_sys::ndspInterpType);
 from_impl!(OutputMode, ctru_sys::ndspOutputMode);
+from_impl!(ClippingMode, ctru_sys::ndspClippingMode);
 from_impl!(AudioFormat, u16);
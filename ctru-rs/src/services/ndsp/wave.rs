@@ -5,6 +5,9 @@
 use super::{AudioFormat, NdspError};
 use crate::linear::LinearAllocator;
 
+use std::error;
+use std::fmt;
+
 /// Informational struct holding the raw audio data and playback info.
 ///
 /// You can play audio [`Wave`]s by using [`Channel::queue_wave()`](super::Channel::queue_wave).
@@ -12,9 +15,29 @@ pub struct Wave {
     /// Data block of the audio wave (and its format information).
     buffer: Box<[u8], LinearAllocator>,
     audio_format: AudioFormat,
+    sample_count: usize,
+    looping: bool,
+    loop_start: usize,
     // Holding the data with the raw format is necessary since `libctru` will access it.
     pub(crate) raw_data: ctru_sys::ndspWaveBuf,
+    // Non-looping part of the wave, queued before `raw_data` when the loop doesn't start at the beginning.
+    pub(crate) intro_data: ctru_sys::ndspWaveBuf,
     played_on_channel: Option<u8>,
+    // Number of times the channel's queue had been cleared when the wave was queued.
+    queue_clears: u32,
+}
+
+/// Errors returned by [`Wave::from_wav()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WavError {
+    /// The data isn't a valid WAV file (or a required chunk is missing).
+    Malformed,
+    /// The audio data is compressed with the specified format tag. Only uncompressed PCM data is supported.
+    UnsupportedCompression(u16),
+    /// The samples have an unsupported bit depth. Only 8 and 16 bit samples are supported.
+    UnsupportedBitDepth(u16),
+    /// The audio has an unsupported number of channels. Only mono and stereo audio is supported.
+    UnsupportedChannels(u16),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -27,7 +50,7 @@ pub enum Status {
     Queued = ctru_sys::NDSP_WBUF_QUEUED as u8,
     /// Wave is currently playing.
     Playing = ctru_sys::NDSP_WBUF_PLAYING as u8,
-    /// Wave has finished playing.
+    /// Wave has finished playing, or was removed from its channel's queue.
     Done = ctru_sys::NDSP_WBUF_DONE as u8,
 }
 
@@ -82,11 +105,66 @@ impl Wave {
         Self {
             buffer,
             audio_format,
+            sample_count,
+            looping,
+            loop_start: 0,
             raw_data,
+            intro_data: raw_data,
             played_on_channel: None,
+            queue_clears: 0,
         }
     }
 
+    /// Build a new playable wave object from the contents of a WAV file.
+    ///
+    /// The audio samples are copied to a new buffer on [LINEAR memory](`crate::linear`).
+    /// Returns the wave along with its sample rate (in Hz), which should be set on the [`Channel`](super::Channel) playing it
+    /// with [`Channel::set_sample_rate()`](super::Channel::set_sample_rate).
+    ///
+    /// # Notes
+    ///
+    /// Only uncompressed PCM data, with 8 or 16 bit samples and 1 or 2 channels, is supported.
+    /// 8 bit samples are converted from the unsigned format used by WAV files to the signed format expected by the DSP.
+    ///
+    /// If the file has a `smpl` chunk, the start of its first loop becomes the wave's [loop start](Wave::set_loop_start).
+    /// The loop always ends with the wave, since the DSP can't repeat only a part of it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the WAV file is malformed or if its audio format is unsupported.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{wave::Wave, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// let (mut wave, sample_rate) = Wave::from_wav(&std::fs::read("romfs:/jump.wav")?, false)?;
+    ///
+    /// let mut channel_0 = ndsp.channel(0)?;
+    /// channel_0.set_format(wave.format());
+    /// channel_0.set_sample_rate(sample_rate as f32);
+    /// channel_0.queue_wave(&mut wave)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_wav(data: &[u8], looping: bool) -> Result<(Self, u32), WavError> {
+        let (buffer, info) = decode_wav(data)?;
+
+        let mut wave = Self::new(buffer, info.format, looping);
+
+        if let Some(loop_start) = info.loop_start {
+            wave.loop_start = loop_start;
+            wave.update_raw_data();
+        }
+
+        Ok((wave, info.sample_rate))
+    }
+
     /// Returns a slice to the audio data (on the LINEAR memory).
     pub fn get_buffer(&self) -> &[u8] {
         &self.buffer
@@ -129,7 +207,25 @@ impl Wave {
     /// # }
     /// ```
     pub fn status(&self) -> Status {
-        self.raw_data.status.try_into().unwrap()
+        let status = self.raw_data.status.try_into().unwrap();
+
+        // Clearing a channel's queue doesn't update the status of the waves it held.
+        let removed = self
+            .played_on_channel
+            .is_some_and(|id| super::queue_clears(id) != self.queue_clears);
+        if removed && matches!(status, Status::Queued | Status::Playing) {
+            return Status::Done;
+        }
+
+        // The looping part waits in the queue while the intro is playing.
+        if self.has_intro()
+            && status == Status::Queued
+            && self.intro_data.status == Status::Playing as u8
+        {
+            Status::Playing
+        } else {
+            status
+        }
     }
 
     /// Returns the amount of samples *read* by the NDSP process.
@@ -138,7 +234,111 @@ impl Wave {
     ///
     /// This value varies depending on [`Wave::set_sample_count`].
     pub fn sample_count(&self) -> usize {
-        self.raw_data.nsamples as usize
+        self.sample_count
+    }
+
+    /// Returns `true` if the wave repeats until the channel's queue is cleared.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Set whether the wave repeats until the channel's queue is cleared.
+    ///
+    /// # Notes
+    ///
+    /// Looping is handled by the DSP, so the wave repeats without any gap or click, unlike queueing it again once it's done.
+    /// A looping wave never finishes, so any wave queued after it on the same channel is never played.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Wave`] is currently queued.
+    pub fn set_looping(&mut self, looping: bool) -> Result<(), NdspError> {
+        self.check_not_busy()?;
+
+        self.looping = looping;
+        self.update_raw_data();
+
+        Ok(())
+    }
+
+    /// Returns the sample from which the wave repeats when looping.
+    pub fn loop_start(&self) -> usize {
+        self.loop_start
+    }
+
+    /// Set the sample from which the wave repeats when looping. Defaults to 0, which repeats the whole wave.
+    ///
+    /// The samples before the loop start (e.g. the intro of a music track) are only played once, then the rest of the wave repeats seamlessly.
+    /// This has no effect if the wave isn't [looping](Wave::set_looping).
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `sample` isn't less than the [sample count](Wave::sample_count)
+    /// or if the [`Wave`] is currently queued.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ndsp::{wave::Wave, Ndsp};
+    /// let ndsp = Ndsp::new()?;
+    ///
+    /// let (mut music, sample_rate) = Wave::from_wav(&std::fs::read("romfs:/theme.wav")?, true)?;
+    ///
+    /// // Skip the first 4 seconds of intro when looping.
+    /// music.set_loop_start(4 * sample_rate as usize)?;
+    ///
+    /// let mut channel_0 = ndsp.channel(0)?;
+    /// channel_0.set_format(music.format());
+    /// channel_0.set_sample_rate(sample_rate as f32);
+    /// channel_0.queue_wave(&mut music)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_loop_start(&mut self, sample: usize) -> Result<(), NdspError> {
+        self.check_not_busy()?;
+
+        if sample >= self.sample_count {
+            return Err(NdspError::SampleCountOutOfBounds(sample, self.sample_count));
+        }
+
+        self.loop_start = sample;
+        self.update_raw_data();
+
+        Ok(())
+    }
+
+    /// Returns `true` if the wave is split into an intro and a looping part.
+    pub(crate) fn has_intro(&self) -> bool {
+        self.looping && self.loop_start > 0
+    }
+
+    fn check_not_busy(&self) -> Result<(), NdspError> {
+        match self.status() {
+            Status::Playing | Status::Queued => {
+                Err(NdspError::WaveBusy(self.played_on_channel.unwrap()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Split the raw wave buffers according to the current loop settings.
+    fn update_raw_data(&mut self) {
+        let loop_start = if self.has_intro() { self.loop_start } else { 0 };
+        let data = self.buffer.as_ptr();
+
+        self.intro_data.__bindgen_anon_1.data_vaddr = data.cast();
+        self.intro_data.nsamples = loop_start as u32;
+        self.intro_data.looping = false;
+
+        // Safety: the loop start is always within the buffer.
+        self.raw_data.__bindgen_anon_1.data_vaddr =
+            unsafe { data.add(loop_start * self.audio_format.size()) }.cast();
+        self.raw_data.nsamples = (self.sample_count - loop_start) as u32;
+        self.raw_data.looping = self.looping;
     }
 
     /// Returns the format of the audio data.
@@ -149,8 +349,9 @@ impl Wave {
     // Set the internal flag for the id of the channel playing this wave.
     //
     // Internal Use Only.
-    pub(crate) fn set_channel(&mut self, id: u8) {
-        self.played_on_channel = Some(id)
+    pub(crate) fn set_channel(&mut self, id: u8, queue_clears: u32) {
+        self.played_on_channel = Some(id);
+        self.queue_clears = queue_clears;
     }
 
     /// Set the amount of samples to be read.
@@ -166,12 +367,7 @@ impl Wave {
     /// This function will return an error if the sample size exceeds the buffer's capacity
     /// or if the [`Wave`] is currently queued.
     pub fn set_sample_count(&mut self, sample_count: usize) -> Result<(), NdspError> {
-        match self.status() {
-            Status::Playing | Status::Queued => {
-                return Err(NdspError::WaveBusy(self.played_on_channel.unwrap()));
-            }
-            _ => (),
-        }
+        self.check_not_busy()?;
 
         let max_count = self.buffer.len() / self.audio_format.size();
 
@@ -179,12 +375,134 @@ impl Wave {
             return Err(NdspError::SampleCountOutOfBounds(sample_count, max_count));
         }
 
-        self.raw_data.nsamples = sample_count as u32;
+        self.sample_count = sample_count;
+        // The loop start must stay within the samples to be read.
+        self.loop_start = self.loop_start.min(sample_count.saturating_sub(1));
+        self.update_raw_data();
 
         Ok(())
     }
 }
 
+/// Format of the samples of a WAV file, as found by [`decode_wav()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(super) struct WavInfo {
+    pub(super) format: AudioFormat,
+    /// Sample rate (in Hz).
+    pub(super) sample_rate: u32,
+    /// First sample of the first loop in the `smpl` chunk, if any.
+    pub(super) loop_start: Option<usize>,
+}
+
+/// Parse a WAV file, copying its samples to a new buffer on LINEAR memory.
+///
+/// See [`Wave::from_wav()`] for the supported formats.
+pub(super) fn decode_wav(data: &[u8]) -> Result<(Box<[u8], LinearAllocator>, WavInfo), WavError> {
+    let (samples, info) = parse_wav(data)?;
+
+    let mut buffer = Vec::with_capacity_in(samples.len(), LinearAllocator);
+    buffer.extend_from_slice(samples);
+
+    if matches!(info.format, AudioFormat::PCM8Mono | AudioFormat::PCM8Stereo) {
+        for sample in buffer.iter_mut() {
+            *sample ^= 0x80;
+        }
+    }
+
+    Ok((buffer.into_boxed_slice(), info))
+}
+
+/// Find the audio samples and their format in a WAV file.
+///
+/// Any incomplete sample at the end of the data is left out.
+fn parse_wav(data: &[u8]) -> Result<(&[u8], WavInfo), WavError> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(WavError::Malformed);
+    }
+
+    let u16_at =
+        |chunk: &[u8], offset: usize| u16::from_le_bytes([chunk[offset], chunk[offset + 1]]);
+    let u32_at = |chunk: &[u8], offset: usize| {
+        u32::from_le_bytes([
+            chunk[offset],
+            chunk[offset + 1],
+            chunk[offset + 2],
+            chunk[offset + 3],
+        ])
+    };
+
+    let mut format = None;
+    let mut samples = None;
+    let mut sampler = None;
+
+    // Walk the chunks following the RIFF header.
+    let mut rest = &data[12..];
+    while rest.len() >= 8 {
+        let id = &rest[0..4];
+        let size = u32_at(rest, 4) as usize;
+        // The last chunk's size may be wrong in streamed files, so clamp it to the available data.
+        let chunk = &rest[8..8 + size.min(rest.len() - 8)];
+
+        match id {
+            b"fmt " if chunk.len() >= 16 => format = Some(chunk),
+            b"data" => samples = Some(chunk),
+            b"smpl" => sampler = Some(chunk),
+            _ => (),
+        }
+
+        // Chunks are padded to an even size.
+        let next = 8usize.saturating_add(size).saturating_add(size & 1);
+        rest = rest.get(next..).unwrap_or_default();
+    }
+
+    let (Some(format), Some(samples)) = (format, samples) else {
+        return Err(WavError::Malformed);
+    };
+
+    let mut format_tag = u16_at(format, 0);
+    let channels = u16_at(format, 2);
+    let sample_rate = u32_at(format, 4);
+    let bits_per_sample = u16_at(format, 14);
+
+    // WAVE_FORMAT_EXTENSIBLE stores the actual format at the start of the sub-format GUID.
+    if format_tag == 0xFFFE && format.len() >= 26 {
+        format_tag = u16_at(format, 24);
+    }
+
+    if format_tag != 1 {
+        return Err(WavError::UnsupportedCompression(format_tag));
+    }
+
+    let audio_format = match (channels, bits_per_sample) {
+        (1, 8) => AudioFormat::PCM8Mono,
+        (1, 16) => AudioFormat::PCM16Mono,
+        (2, 8) => AudioFormat::PCM8Stereo,
+        (2, 16) => AudioFormat::PCM16Stereo,
+        (1 | 2, bits) => return Err(WavError::UnsupportedBitDepth(bits)),
+        (channels, _) => return Err(WavError::UnsupportedChannels(channels)),
+    };
+
+    // Ignore any incomplete sample at the end of the data.
+    let len = samples.len() - samples.len() % audio_format.size();
+    let samples = &samples[..len];
+
+    // The sampler chunk has a 36 byte header (ending with the number of loops) followed by 24 bytes per loop,
+    // each with the loop's first sample at offset 8. Loops starting outside of the samples are ignored.
+    let loop_start = sampler
+        .filter(|sampler| sampler.len() >= 36 + 24 && u32_at(sampler, 28) > 0)
+        .map(|sampler| u32_at(sampler, 36 + 8) as usize)
+        .filter(|&start| start < len / audio_format.size());
+
+    Ok((
+        samples,
+        WavInfo {
+            format: audio_format,
+            sample_rate,
+            loop_start,
+        },
+    ))
+}
+
 impl TryFrom<u8> for Status {
     type Error = &'static str;
 
@@ -199,6 +517,19 @@ impl TryFrom<u8> for Status {
     }
 }
 
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "the data is not a valid WAV file"),
+            Self::UnsupportedCompression(tag) => write!(f, "the WAV file uses an unsupported compression format ({tag:#06X}), only uncompressed PCM is supported"),
+            Self::UnsupportedBitDepth(bits) => write!(f, "the WAV file has an unsupported bit depth ({bits} bits), only 8 and 16 bit samples are supported"),
+            Self::UnsupportedChannels(channels) => write!(f, "the WAV file has an unsupported number of channels ({channels}), only mono and stereo audio is supported"),
+        }
+    }
+}
+
+impl error::Error for WavError {}
+
 impl Drop for Wave {
     fn drop(&mut self) {
         // This was the only way I found I could check for improper drops of `Wave`.
@@ -208,7 +539,7 @@ impl Drop for Wave {
             // If the status flag is "unfinished"
             _ => {
                 // The unwrap is safe, since it must have a value in the case the status is "unfinished".
-                unsafe { ctru_sys::ndspChnWaveBufClear(self.played_on_channel.unwrap().into()) };
+                super::clear_channel_queue(self.played_on_channel.unwrap());
             }
         }
 
@@ -222,3 +553,213 @@ impl Drop for Wave {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a WAV file with the specified chunks.
+    fn riff(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut body = b"WAVE".to_vec();
+
+        for (id, data) in chunks {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            body.extend_from_slice(data);
+
+            if data.len() % 2 == 1 {
+                body.push(0);
+            }
+        }
+
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        file.extend_from_slice(&body);
+        file
+    }
+
+    fn fmt(format_tag: u16, channels: u16, sample_rate: u32, bits_per_sample: u16) -> Vec<u8> {
+        let block_align = channels * bits_per_sample / 8;
+
+        let mut chunk = Vec::new();
+        chunk.extend_from_slice(&format_tag.to_le_bytes());
+        chunk.extend_from_slice(&channels.to_le_bytes());
+        chunk.extend_from_slice(&sample_rate.to_le_bytes());
+        chunk.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+        chunk.extend_from_slice(&block_align.to_le_bytes());
+        chunk.extend_from_slice(&bits_per_sample.to_le_bytes());
+        chunk
+    }
+
+    fn smpl(loop_starts: &[u32]) -> Vec<u8> {
+        let mut chunk = vec![0; 36];
+        chunk[28..32].copy_from_slice(&(loop_starts.len() as u32).to_le_bytes());
+
+        for start in loop_starts {
+            let mut sample_loop = [0; 24];
+            sample_loop[8..12].copy_from_slice(&start.to_le_bytes());
+            sample_loop[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+            chunk.extend_from_slice(&sample_loop);
+        }
+
+        chunk
+    }
+
+    #[test]
+    fn pcm16_stereo() {
+        let file = riff(&[
+            (b"fmt ", &fmt(1, 2, 32000, 16)),
+            (b"data", &[1, 2, 3, 4, 5, 6, 7, 8]),
+        ]);
+
+        assert_eq!(
+            parse_wav(&file),
+            Ok((
+                &[1, 2, 3, 4, 5, 6, 7, 8][..],
+                WavInfo {
+                    format: AudioFormat::PCM16Stereo,
+                    sample_rate: 32000,
+                    loop_start: None,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn extensible_format() {
+        let mut format = fmt(0xFFFE, 1, 22050, 8);
+        format.extend_from_slice(&[22, 0, 8, 0, 4, 0, 0, 0, 1, 0]);
+
+        let file = riff(&[(b"fmt ", &format), (b"data", &[0x80])]);
+
+        assert_eq!(
+            parse_wav(&file).map(|(_, info)| info.format),
+            Ok(AudioFormat::PCM8Mono)
+        );
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        let wav = |format: Vec<u8>| riff(&[(b"fmt ", &format), (b"data", &[0; 4])]);
+
+        assert_eq!(
+            parse_wav(&wav(fmt(2, 1, 44100, 4))),
+            Err(WavError::UnsupportedCompression(2))
+        );
+        assert_eq!(
+            parse_wav(&wav(fmt(1, 2, 44100, 24))),
+            Err(WavError::UnsupportedBitDepth(24))
+        );
+        assert_eq!(
+            parse_wav(&wav(fmt(1, 6, 44100, 16))),
+            Err(WavError::UnsupportedChannels(6))
+        );
+    }
+
+    #[test]
+    fn missing_chunks() {
+        assert_eq!(parse_wav(b"RIFF\0\0\0\0WAV"), Err(WavError::Malformed));
+        assert_eq!(
+            parse_wav(&riff(&[(b"data", &[0; 4])])),
+            Err(WavError::Malformed)
+        );
+        assert_eq!(
+            parse_wav(&riff(&[(b"fmt ", &fmt(1, 1, 44100, 16))])),
+            Err(WavError::Malformed)
+        );
+
+        // A format chunk too short to hold the format is ignored.
+        let format = fmt(1, 1, 44100, 16);
+        assert_eq!(
+            parse_wav(&riff(&[(b"fmt ", &format[..14]), (b"data", &[0; 4])])),
+            Err(WavError::Malformed)
+        );
+    }
+
+    #[test]
+    fn truncated_chunks() {
+        // The size of the data chunk is larger than the rest of the file.
+        let mut file = riff(&[
+            (b"fmt ", &fmt(1, 1, 44100, 16)),
+            (b"data", &[1, 2, 3, 4, 5, 6]),
+        ]);
+        let size_offset = file.len() - 10;
+        file[size_offset..size_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        // The last sample is incomplete.
+        file.pop();
+
+        assert_eq!(
+            parse_wav(&file).map(|(samples, _)| samples),
+            Ok(&[1, 2, 3, 4][..])
+        );
+
+        // The file ends in the middle of a chunk header.
+        let mut file = riff(&[(b"fmt ", &fmt(1, 1, 44100, 8)), (b"data", &[1, 2, 3])]);
+        file.extend_from_slice(b"LIST\x10");
+
+        assert_eq!(
+            parse_wav(&file).map(|(samples, _)| samples),
+            Ok(&[1, 2, 3][..])
+        );
+    }
+
+    #[test]
+    fn odd_chunk_padding() {
+        let file = riff(&[
+            (b"LIST", &[0; 3]),
+            (b"fmt ", &fmt(1, 1, 11025, 8)),
+            (b"junk", &[0xFF]),
+            (b"data", &[1, 2, 3]),
+        ]);
+
+        assert_eq!(
+            parse_wav(&file),
+            Ok((
+                &[1, 2, 3][..],
+                WavInfo {
+                    format: AudioFormat::PCM8Mono,
+                    sample_rate: 11025,
+                    loop_start: None,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn empty_data() {
+        let wav = |data: &[u8]| riff(&[(b"fmt ", &fmt(1, 2, 44100, 16)), (b"data", data)]);
+
+        assert_eq!(
+            parse_wav(&wav(&[])).map(|(samples, _)| samples),
+            Ok(&[][..])
+        );
+        assert_eq!(
+            parse_wav(&wav(&[1, 2, 3])).map(|(samples, _)| samples),
+            Ok(&[][..])
+        );
+    }
+
+    #[test]
+    fn smpl_loop_points() {
+        let wav = |sampler: Vec<u8>| {
+            riff(&[
+                (b"fmt ", &fmt(1, 1, 44100, 16)),
+                (b"data", &[0; 20]),
+                (b"smpl", &sampler),
+            ])
+        };
+        let loop_start = |file: Vec<u8>| parse_wav(&file).map(|(_, info)| info.loop_start);
+
+        assert_eq!(loop_start(wav(smpl(&[4, 8]))), Ok(Some(4)));
+        assert_eq!(loop_start(wav(smpl(&[0]))), Ok(Some(0)));
+        assert_eq!(loop_start(wav(smpl(&[]))), Ok(None));
+
+        // The loop starts after the last sample.
+        assert_eq!(loop_start(wav(smpl(&[10]))), Ok(None));
+
+        // The chunk is too short to hold the loop.
+        let mut sampler = smpl(&[4]);
+        sampler.truncate(50);
+        assert_eq!(loop_start(wav(sampler)), Ok(None));
+    }
+}
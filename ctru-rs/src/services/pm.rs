@@ -0,0 +1,153 @@
+//! Process Manager service.
+//!
+//! This service launches and terminates processes on the console. It's mostly used by the Home Menu and other system applets,
+//! but it makes it possible to start a title directly, without going through the usual application jump.
+//!
+//! # Permissions
+//!
+//! Access to the `pm:app` service is restricted to the system processes which need it. Homebrew applications can use it only if their
+//! exheader lists it (or if the running environment grants access to every service), otherwise [`Pm::new()`] fails.
+//! Launching titles on a retail system without a proper reason can easily leave the console in an unusable state until it's rebooted, so use with care.
+//!
+//! See also <https://www.3dbrew.org/wiki/Process_Manager_Services>
+#![doc(alias = "process")]
+#![doc(alias = "launch")]
+
+use bitflags::bitflags;
+
+use crate::error::ResultCode;
+use crate::services::fs::FsMediaType;
+
+bitflags! {
+    /// Flags used to configure the launch of a title with [`Pm::launch_title()`].
+    #[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+    pub struct LaunchFlags: u32 {
+        /// Launch the title as a regular application (it becomes the current application).
+        const NORMAL_APPLICATION          = ctru_sys::PMLAUNCHFLAG_NORMAL_APPLICATION;
+        /// Launch the system modules the title depends on as well.
+        const LOAD_DEPENDENCIES           = ctru_sys::PMLAUNCHFLAG_LOAD_DEPENDENCIES;
+        /// Notify the caller when the launched title terminates.
+        const NOTIFY_TERMINATION          = ctru_sys::PMLAUNCHFLAG_NOTIFY_TERMINATION;
+        /// Queue the title to be launched as soon as a debugger attaches.
+        const QUEUE_DEBUG_APPLICATION     = ctru_sys::PMLAUNCHFLAG_QUEUE_DEBUG_APPLICATION;
+        /// Run the title with the application memory layout of the Old 3DS, even on New 3DS models.
+        const FORCE_USE_O3DS_APP_MEM      = ctru_sys::PMLAUNCHFLAG_FORCE_USE_O3DS_APP_MEM;
+        /// Run the title with the largest application memory layout of the Old 3DS. Only valid together with [`LaunchFlags::FORCE_USE_O3DS_APP_MEM`].
+        const FORCE_USE_O3DS_MAX_APP_MEM  = ctru_sys::PMLAUNCHFLAG_FORCE_USE_O3DS_MAX_APP_MEM;
+        /// Launch the installed update of the title instead of the base version, if there is one.
+        const USE_UPDATE_TITLE            = ctru_sys::PMLAUNCHFLAG_USE_UPDATE_TITLE;
+    }
+}
+
+/// Handle to the Process Manager service.
+pub struct Pm(());
+
+impl Pm {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized,
+    /// which is the case if the application hasn't got access to `pm:app` (see the [module documentation](self)).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::pm::Pm;
+    ///
+    /// let pm = Pm::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "pmAppInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::pmAppInit() })?;
+
+        Ok(Self(()))
+    }
+
+    /// Launch the title with the specified ID, installed on the specified media.
+    ///
+    /// # Notes
+    ///
+    /// The Process Manager doesn't pass any parameter to the launched title: its only configuration is the set of [`LaunchFlags`].
+    /// Arguments for the title (e.g. to boot it in a special mode) must be delivered through the title's own channels,
+    /// such as the parameter of an APT application jump or a file on the SD card read by the title.
+    ///
+    /// Only a single application can run at a time: launching a title with [`LaunchFlags::NORMAL_APPLICATION`] fails while another application
+    /// (including the calling one) is running, unless it is terminated first with [`Pm::terminate_current_application()`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the title isn't installed on the specified media, if its exheader can't be loaded,
+    /// if there isn't enough memory to run it or if the requested flags aren't allowed in the current state of the system.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::fs::FsMediaType;
+    /// use ctru::services::pm::{LaunchFlags, Pm};
+    /// let mut pm = Pm::new()?;
+    ///
+    /// // Launch a system module along with its dependencies.
+    /// pm.launch_title(
+    ///     0x0004_0130_0000_2B02,
+    ///     FsMediaType::Nand,
+    ///     LaunchFlags::LOAD_DEPENDENCIES,
+    /// )?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "PMAPP_LaunchTitle")]
+    pub fn launch_title(
+        &mut self,
+        title_id: u64,
+        mediatype: FsMediaType,
+        flags: LaunchFlags,
+    ) -> crate::Result<()> {
+        let mut info: ctru_sys::FS_ProgramInfo = unsafe { std::mem::zeroed() };
+        info.programId = title_id;
+        info.set_mediaType(mediatype as u32);
+
+        ResultCode(unsafe { ctru_sys::PMAPP_LaunchTitle(&info, flags.bits()) })?;
+
+        Ok(())
+    }
+
+    /// Terminate the application currently running, waiting at most `timeout` for it to exit.
+    ///
+    /// # Notes
+    ///
+    /// The application is asked to exit first, and forcefully terminated if it doesn't do it in time.
+    /// If the caller is the current application itself, it won't get the chance to clean up its state.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no application is running.
+    #[doc(alias = "PMAPP_TerminateCurrentApplication")]
+    pub fn terminate_current_application(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> crate::Result<()> {
+        let timeout = timeout.as_nanos().try_into().unwrap_or(i64::MAX);
+
+        ResultCode(unsafe { ctru_sys::PMAPP_TerminateCurrentApplication(timeout) })?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Pm {
+    #[doc(alias = "pmAppExit")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::pmAppExit() };
+    }
+}
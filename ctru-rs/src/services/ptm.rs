@@ -0,0 +1,221 @@
+//! Power-Time service.
+//!
+//! This service handles information about the power state of the console, such as the state of the shell (lid).
+//! It also keeps track of the steps counted by the pedometer.
+//!
+//! See also <https://www.3dbrew.org/wiki/PTM_Services>
+#![doc(alias = "power")]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::ResultCode;
+
+/// Seconds between the Unix epoch and the epoch used by the console (January 1st, 2000).
+const EPOCH_2000_OFFSET: u64 = 946_684_800;
+
+/// Number of steps counted by the pedometer during an hour.
+///
+/// See [`Ptm::step_history()`] to learn how to retrieve this.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HourlySteps {
+    /// Hour of the day (0-23), in the console's local time.
+    pub hour: u8,
+    /// Number of steps.
+    pub steps: u16,
+}
+
+/// Handle to the PTM service.
+pub struct Ptm {
+    last_shell_state: Option<bool>,
+}
+
+impl Ptm {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// Since this service requires no special or elevated permissions, errors are rare in practice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ptm::Ptm;
+    ///
+    /// let ptm = Ptm::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "ptmuInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::ptmuInit() })?;
+
+        Ok(Self {
+            last_shell_state: None,
+        })
+    }
+
+    /// Returns `true` if the console's shell (lid) is open.
+    ///
+    /// # Notes
+    ///
+    /// This value is reported correctly even when the console is prevented from going to sleep,
+    /// which makes it useful to pause playback or gameplay when the lid gets closed.
+    /// Consoles without a lid (such as the Nintendo 2DS) always report an open shell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ptm::Ptm;
+    /// let ptm = Ptm::new()?;
+    ///
+    /// if ptm.is_shell_open()? {
+    ///     println!("The lid is open!");
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "PTMU_GetShellState")]
+    pub fn is_shell_open(&self) -> crate::Result<bool> {
+        let mut state: u8 = 0;
+
+        ResultCode(unsafe { ctru_sys::PTMU_GetShellState(&mut state) })?;
+
+        Ok(state != 0)
+    }
+
+    /// Returns the new state of the shell (lid) if it has changed since the last call to this function.
+    ///
+    /// The returned value follows the same convention as [`Ptm::is_shell_open()`] (`true` means open).
+    /// The first call always returns the current state.
+    ///
+    /// # Notes
+    ///
+    /// The system doesn't provide an event to wait on for shell state changes (sleep notifications are not sent if sleep is disabled),
+    /// so this function is meant to be polled once per frame within the application's main loop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ptm::Ptm;
+    /// let mut ptm = Ptm::new()?;
+    ///
+    /// match ptm.shell_state_changed()? {
+    ///     Some(false) => println!("Lid closed, pausing playback."),
+    ///     Some(true) => println!("Lid opened, resuming playback."),
+    ///     None => (),
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shell_state_changed(&mut self) -> crate::Result<Option<bool>> {
+        let open = self.is_shell_open()?;
+
+        if self.last_shell_state.replace(open) == Some(open) {
+            Ok(None)
+        } else {
+            Ok(Some(open))
+        }
+    }
+}
+
+/// Functions to access the pedometer data.
+impl Ptm {
+    /// Returns the total number of steps counted by the pedometer since the console was set up.
+    ///
+    /// # Notes
+    ///
+    /// This is a lifetime total, not the number of steps of the current day: use [`Ptm::step_history()`] to count the steps
+    /// of a specific period of time.
+    #[doc(alias = "PTMU_GetTotalStepCount")]
+    pub fn total_step_count(&self) -> crate::Result<u32> {
+        let mut steps = 0;
+
+        ResultCode(unsafe { ctru_sys::PTMU_GetTotalStepCount(&mut steps) })?;
+
+        Ok(steps)
+    }
+
+    /// Returns the number of steps counted during each of the last `hours` hours, including the current one.
+    ///
+    /// The entries are ordered from the oldest to the most recent.
+    ///
+    /// # Notes
+    ///
+    /// The console keeps track of (at most) the steps of the last few weeks.
+    /// The history is read through the `ptm:u` service, which (unlike `ptm:sysm`) is available to every application.
+    /// [`Ptm::total_step_count()`] can't be used in its place if this fails, since it counts every step taken since the console was set up.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the step history could not be retrieved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::ptm::Ptm;
+    /// let ptm = Ptm::new()?;
+    ///
+    /// for entry in ptm.step_history(24)? {
+    ///     println!("{:02}:00 - {} steps", entry.hour, entry.steps);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "PTMU_GetStepHistory")]
+    pub fn step_history(&self, hours: usize) -> crate::Result<Vec<HourlySteps>> {
+        // The system clock uses the console's local time.
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+
+        let current_hour = now.saturating_sub(EPOCH_2000_OFFSET) / 3600;
+        let first_hour = (current_hour + 1).saturating_sub(hours as u64);
+
+        let mut steps = vec![0u16; hours];
+
+        ResultCode(unsafe {
+            ctru_sys::PTMU_GetStepHistory(
+                hours as u32,
+                (first_hour * 3600) as u32,
+                steps.as_mut_ptr(),
+            )
+        })?;
+
+        Ok(steps
+            .into_iter()
+            .zip(first_hour..)
+            .map(|(steps, hour)| HourlySteps {
+                hour: (hour % 24) as u8,
+                steps,
+            })
+            .collect())
+    }
+}
+
+impl Drop for Ptm {
+    #[doc(alias = "ptmuExit")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::ptmuExit() };
+    }
+}
@@ -0,0 +1,286 @@
+use std::any::{self, Any};
+use std::time::Duration;
+
+use super::{
+    ac, am, apt, cam, cfgu, csnd, fs, gfx, gsplcd, hid, ir_user, mcuhwc, ndsp, pm, ps, ptm, soc,
+    sslc, y2r,
+};
+
+/// Common interface to initialize service handles.
+///
+/// Every service handle is initialized when created and exited when dropped. This trait makes it possible
+/// to write code generic over the services it needs, such as the [`Services`] bundle.
+pub trait Service: Sized + 'static {
+    /// Initialize a new service handle.
+    ///
+    /// This is equivalent to calling the service's own constructor (e.g. [`Hid::new()`](hid::Hid::new)).
+    fn init() -> crate::Result<Self>;
+
+    /// Initialize a new service handle, retrying up to `attempts` times if the initialization fails with a [transient](crate::Error::is_transient) error.
+    ///
+    /// The delay between attempts starts at 10 milliseconds and doubles after each failure.
+    /// Errors which aren't transient are returned immediately.
+    ///
+    /// # Notes
+    ///
+    /// This is mostly useful for applications launched right after boot, when some services may not be available yet.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the last error if every attempt failed, or the first error which isn't transient.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `attempts` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::hid::Hid;
+    /// use ctru::services::Service;
+    ///
+    /// let hid = Hid::init_with_retries(5)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn init_with_retries(attempts: u32) -> crate::Result<Self> {
+        assert!(attempts > 0, "at least one attempt is needed");
+
+        let mut delay = RETRY_DELAY;
+
+        for _ in 1..attempts {
+            match Self::init() {
+                Err(e) if e.is_transient() => {
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+
+        Self::init()
+    }
+
+    /// Returns `true` if the running application is allowed to access the service.
+    ///
+    /// The services in this crate check this by briefly acquiring a handle to one of their ports, without initializing them.
+    /// The default implementation (used by services defined outside this crate) initializes the service and exits it right away.
+    ///
+    /// # Notes
+    ///
+    /// Access to each service depends on the permissions of the running application and on the environment it runs in
+    /// (e.g. the Homebrew Launcher, a custom firmware or an emulator). Some services (such as [`Am`](am::Am), [`Pm`](pm::Pm) or
+    /// [`McuHwc`](mcuhwc::McuHwc)) are restricted to system titles, so checking this first makes it possible to disable features
+    /// which need them, instead of failing during initialization.
+    ///
+    /// Access doesn't guarantee initialization will succeed (e.g. the [`Ndsp`](ndsp::Ndsp) service can still miss its firmware).
+    ///
+    /// The check never waits for a port to accept a new session: a port which reached its session limit is reported as unavailable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// use ctru::services::am::Am;
+    /// use ctru::services::Service;
+    ///
+    /// if !Am::is_available() {
+    ///     println!("Title management is unavailable");
+    /// }
+    /// ```
+    #[doc(alias = "srvGetServiceHandle")]
+    fn is_available() -> bool {
+        Self::init().is_ok()
+    }
+}
+
+/// Returns `true` if a session to any of the specified service ports can be opened.
+fn can_access_any(ports: &[&str]) -> bool {
+    ports.iter().any(|port| can_access(port))
+}
+
+/// Returns `true` if a session to the specified service port can be opened, without waiting if the port reached its session limit.
+///
+/// The request is sent to `srv:` by hand, since `srvSetBlockingPolicy()` would change the policy used by every other connection.
+fn can_access(port: &str) -> bool {
+    // Command ID of `GetServiceHandle`.
+    const GET_SERVICE_HANDLE: u32 = 0x5;
+    // Flag which makes the request fail instead of waiting for a session to be closed.
+    const NON_BLOCKING: u32 = 1;
+
+    let mut name = [0u8; 8];
+
+    match name.get_mut(..port.len()) {
+        Some(prefix) => prefix.copy_from_slice(port.as_bytes()),
+        None => return false,
+    }
+
+    unsafe {
+        let command_buffer = ctru_sys::getThreadCommandBuffer();
+
+        *command_buffer = ctru_sys::IPC_MakeHeader(GET_SERVICE_HANDLE, 4, 0);
+        *command_buffer.add(1) = u32::from_ne_bytes([name[0], name[1], name[2], name[3]]);
+        *command_buffer.add(2) = u32::from_ne_bytes([name[4], name[5], name[6], name[7]]);
+        *command_buffer.add(3) = port.len() as u32;
+        *command_buffer.add(4) = NON_BLOCKING;
+
+        let result = ctru_sys::svcSendSyncRequest(*ctru_sys::srvGetSessionHandle());
+
+        if ctru_sys::R_FAILED(result)
+            || ctru_sys::R_FAILED(*command_buffer.add(1) as ctru_sys::Result)
+        {
+            return false;
+        }
+
+        let _ = ctru_sys::svcCloseHandle(*command_buffer.add(3));
+    }
+
+    true
+}
+
+/// Initial delay between service initialization attempts. See [`Service::init_with_retries()`].
+const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+// Each service is listed with the ports it can be accessed through. See <https://www.3dbrew.org/wiki/Services_API>
+macro_rules! impl_service {
+    ($($service:ty => [$($port:literal),+]),* $(,)?) => {
+        $(
+            impl Service for $service {
+                fn init() -> crate::Result<Self> {
+                    Self::new()
+                }
+
+                fn is_available() -> bool {
+                    can_access_any(&[$($port),+])
+                }
+            }
+        )*
+    };
+}
+
+impl_service!(
+    ac::Ac => ["ac:u", "ac:i"],
+    am::Am => ["am:net", "am:u", "am:sys", "am:app"],
+    apt::Apt => ["APT:S", "APT:A", "APT:U"],
+    cam::Cam => ["cam:u"],
+    cfgu::Cfgu => ["cfg:i", "cfg:s", "cfg:u"],
+    csnd::Csnd => ["csnd:SND"],
+    fs::Fs => ["fs:USER"],
+    gfx::Gfx => ["gsp::Gpu"],
+    gsplcd::GspLcd => ["gsp::Lcd"],
+    hid::Hid => ["hid:USER", "hid:SPVR"],
+    ir_user::CirclePadPro => ["ir:USER"],
+    mcuhwc::McuHwc => ["mcu::HWC"],
+    ndsp::Ndsp => ["dsp::DSP"],
+    pm::Pm => ["pm:app"],
+    ps::Ps => ["ps:ps"],
+    ptm::Ptm => ["ptm:u"],
+    soc::Soc => ["soc:U"],
+    sslc::SslC => ["ssl:C"],
+    y2r::Y2r => ["y2r:u"],
+);
+
+#[cfg(all(feature = "romfs", romfs_exists))]
+impl_service!(super::romfs::RomFS => ["fs:USER"]);
+
+/// A bundle of service handles, initialized together and exited in reverse order.
+///
+/// Some services depend on others being active (e.g. applets require [`Apt`](apt::Apt) and [`Gfx`](gfx::Gfx)),
+/// so the handles are dropped in the reverse order of their initialization, like local variables would be.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::Services;
+/// use ctru::services::{apt::Apt, gfx::Gfx, hid::Hid};
+///
+/// let mut services = Services::new().with::<Apt>()?.with::<Hid>()?.with::<Gfx>()?;
+///
+/// let hid = services.get_mut::<Hid>().unwrap();
+/// hid.scan_input();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Services {
+    handles: Vec<Box<dyn Any>>,
+}
+
+impl Services {
+    /// Create an empty bundle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initialize the service `S` and add it to the bundle, consuming and returning the bundle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// In that case, all services already in the bundle are exited.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the bundle already contains a handle to `S`.
+    pub fn with<S: Service>(mut self) -> crate::Result<Self> {
+        self.add::<S>()?;
+        Ok(self)
+    }
+
+    /// Initialize the service `S` and add it to the bundle, returning a reference to the new handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the bundle already contains a handle to `S`.
+    pub fn add<S: Service>(&mut self) -> crate::Result<&mut S> {
+        assert!(
+            !self.contains::<S>(),
+            "the bundle already contains a handle to {}",
+            any::type_name::<S>()
+        );
+
+        self.handles.push(Box::new(S::init()?));
+
+        Ok(self.get_mut::<S>().unwrap())
+    }
+
+    /// Returns `true` if the bundle contains a handle to `S`.
+    pub fn contains<S: Service>(&self) -> bool {
+        self.get::<S>().is_some()
+    }
+
+    /// Returns a reference to the handle of `S`, if present.
+    pub fn get<S: Service>(&self) -> Option<&S> {
+        self.handles.iter().find_map(|handle| handle.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the handle of `S`, if present.
+    pub fn get_mut<S: Service>(&mut self) -> Option<&mut S> {
+        self.handles
+            .iter_mut()
+            .find_map(|handle| handle.downcast_mut())
+    }
+}
+
+impl Drop for Services {
+    fn drop(&mut self) {
+        // Exit the services in the reverse order of initialization.
+        while let Some(handle) = self.handles.pop() {
+            drop(handle);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Service Manager.
+//!
+//! Every system service is reached through a named port registered to the service manager (`srv:`), such as `hid:USER` or `cfg:u`.
+//! The typed service handles in this crate (e.g. [`Hid`](crate::services::hid::Hid)) take care of this on their own:
+//! this module is an escape hatch to open a session to a service which doesn't have a typed wrapper yet.
+//!
+//! # Manual IPC
+//!
+//! A [`ServiceHandle`] only gives access to the raw session. Requests must be written by hand to the thread's command buffer
+//! (`ctru_sys::getThreadCommandBuffer()`) following the service's IPC protocol, then sent with `ctru_sys::svcSendSyncRequest(handle.raw())`,
+//! and the response must be read back from the same buffer. Nothing is checked by this crate, so a malformed request can easily
+//! crash the application or the service itself.
+//!
+//! See also <https://www.3dbrew.org/wiki/Services> for the list of services and their commands.
+#![doc(alias = "ipc")]
+
+use std::ffi::CString;
+
+use crate::error::{Error, ResultCode};
+
+/// Maximum length (in bytes) of a service name.
+const MAX_NAME_LEN: usize = 8;
+
+/// Session to a system service, opened with [`get_service_handle()`].
+///
+/// The session is closed when the handle is dropped.
+#[derive(Debug)]
+pub struct ServiceHandle(ctru_sys::Handle);
+
+impl ServiceHandle {
+    /// Returns the raw handle of the session, to be used with `ctru_sys::svcSendSyncRequest`.
+    ///
+    /// The handle is only valid as long as the [`ServiceHandle`] is alive.
+    pub fn raw(&self) -> ctru_sys::Handle {
+        self.0
+    }
+}
+
+impl Drop for ServiceHandle {
+    #[doc(alias = "svcCloseHandle")]
+    fn drop(&mut self) {
+        let _ = unsafe { ctru_sys::svcCloseHandle(self.0) };
+    }
+}
+
+/// Open a new session to the service registered with the specified name.
+///
+/// See the [module documentation](self) to learn how to use the returned handle.
+///
+/// # Errors
+///
+/// This function will return an error if the name is longer than 8 bytes or contains a NUL byte,
+/// if no service is registered with that name, or if the application isn't allowed to access it.
+///
+/// # Example
+///
+/// ```
+/// # let _runner = test_runner::GdbRunner::default();
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// #
+/// use ctru::services::srv;
+///
+/// let handle = srv::get_service_handle("cfg:u")?;
+///
+/// // Write a request to the command buffer and send it with `svcSendSyncRequest(handle.raw())`.
+/// # let _ = handle.raw();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[doc(alias = "srvGetServiceHandle")]
+pub fn get_service_handle(name: &str) -> crate::Result<ServiceHandle> {
+    let name = CString::new(name)
+        .ok()
+        .filter(|name| name.as_bytes().len() <= MAX_NAME_LEN)
+        .ok_or_else(|| {
+            Error::InvalidArgument(format!(
+                "the service name must be at most {MAX_NAME_LEN} bytes long, without NUL bytes"
+            ))
+        })?;
+
+    let mut handle = 0;
+    ResultCode(unsafe { ctru_sys::srvGetServiceHandle(&mut handle, name.as_ptr()) })?;
+
+    Ok(ServiceHandle(handle))
+}
@@ -0,0 +1,196 @@
+//! YUV to RGB conversion service.
+//!
+//! The Y2R service controls the hardware unit converting YUV images (such as the ones taken by the [cameras](crate::services::cam)
+//! or decoded from videos) to RGB images, which can be shown on the screens or uploaded as textures.
+//!
+//! See also <https://www.3dbrew.org/wiki/Camera_Services#Y2R_Service>
+#![doc(alias = "yuv")]
+
+use std::time::Duration;
+
+use crate::error::{Error, ResultCode};
+
+/// Pixel format of the images produced by the [`Y2r`] service.
+#[doc(alias = "Y2RU_OutputFormat")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum OutputFormat {
+    /// RGBA8 output format. 32 bits per pixel.
+    Rgba8 = ctru_sys::OUTPUT_RGB_32,
+    /// RGB8 output format. 24 bits per pixel.
+    Rgb8 = ctru_sys::OUTPUT_RGB_24,
+    /// RGB5A1 output format. 16 bits per pixel.
+    Rgb5A1 = ctru_sys::OUTPUT_RGB_16_555,
+    /// RGB565 output format. 16 bits per pixel.
+    Rgb565 = ctru_sys::OUTPUT_RGB_16_565,
+}
+
+impl OutputFormat {
+    /// Returns the size of a pixel in bytes.
+    pub fn pixel_depth_bytes(&self) -> usize {
+        match self {
+            Self::Rgba8 => 4,
+            Self::Rgb8 => 3,
+            Self::Rgb5A1 | Self::Rgb565 => 2,
+        }
+    }
+}
+
+/// Handle to the Y2R service.
+pub struct Y2r(());
+
+impl Y2r {
+    /// Initialize a new service handle.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the service was unable to be initialized.
+    /// Since this service requires no special or elevated permissions, errors are rare in practice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let _runner = test_runner::GdbRunner::default();
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// #
+    /// use ctru::services::y2r::Y2r;
+    ///
+    /// let y2r = Y2r::new()?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[doc(alias = "y2rInit")]
+    pub fn new() -> crate::Result<Self> {
+        ResultCode(unsafe { ctru_sys::y2rInit() })?;
+
+        Ok(Self(()))
+    }
+
+    /// Convert a YUV422 image (as taken by the cameras with [`cam::OutputFormat::Yuv422`](crate::services::cam::OutputFormat::Yuv422))
+    /// to an RGB image in the chosen format, waiting at most `timeout` for the conversion to end.
+    ///
+    /// The image is read from `input` (2 bytes per pixel) and written to `output` ([`OutputFormat::pixel_depth_bytes()`] bytes per pixel),
+    /// line by line and without rotation. The output alpha (for formats which have one) is opaque.
+    ///
+    /// # Notes
+    ///
+    /// Both buffers are accessed via DMA, which requires physically contiguous memory: allocate them in LINEAR memory
+    /// (e.g. with [`LinearBox`](crate::linear::LinearBox)) to avoid garbled images. The data cache is flushed and invalidated
+    /// by this function, so the output can be read as soon as it returns.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a buffer is too short for the image, if the service is busy or if the timeout is reached.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `width` isn't a multiple of 8 between 8 and 1024, or if `height` isn't a multiple of 8.
+    #[doc(alias = "Y2RU_StartConversion", alias = "Y2RU_SetConversionParams")]
+    pub fn convert_yuv422(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+        width: u16,
+        height: u16,
+        format: OutputFormat,
+        timeout: Duration,
+    ) -> crate::Result<()> {
+        assert!(
+            width % 8 == 0 && (8..=1024).contains(&width),
+            "the width must be a multiple of 8 between 8 and 1024"
+        );
+        assert!(height % 8 == 0, "the height must be a multiple of 8");
+
+        let input_len = usize::from(width) * usize::from(height) * 2;
+        let output_len = usize::from(width) * usize::from(height) * format.pixel_depth_bytes();
+
+        if input.len() < input_len {
+            return Err(Error::BufferTooShort {
+                provided: input.len(),
+                wanted: input_len,
+            });
+        }
+        if output.len() < output_len {
+            return Err(Error::BufferTooShort {
+                provided: output.len(),
+                wanted: output_len,
+            });
+        }
+
+        let mut params: ctru_sys::Y2RU_ConversionParams = unsafe { std::mem::zeroed() };
+        params.set_input_format(ctru_sys::INPUT_YUV422_BATCH);
+        params.set_output_format(format as u32);
+        params.set_rotation(ctru_sys::ROTATION_NONE);
+        params.set_block_alignment(ctru_sys::BLOCK_LINE);
+        params.set_standard_coefficient(ctru_sys::COEFFICIENT_ITU_R_BT_601_SCALING);
+        params.input_line_width = width as i16;
+        params.input_lines = height as i16;
+        params.alpha = 0xFF;
+
+        // The input is sent one line at a time, while the output is received in blocks of 8 lines
+        // (or 4 lines, if a block is too large for a single transfer).
+        let input_unit = usize::from(width) * 2;
+        let mut output_unit = usize::from(width) * 8 * format.pixel_depth_bytes();
+        if output_unit > i16::MAX as usize {
+            output_unit /= 2;
+        }
+
+        unsafe {
+            // The DMA reads the input from memory, so it must not be left in the data cache.
+            let _ = ctru_sys::GSPGPU_FlushDataCache(input.as_ptr().cast(), input_len as u32);
+
+            ResultCode(ctru_sys::Y2RU_SetConversionParams(&params))?;
+            ResultCode(ctru_sys::Y2RU_SetSendingYUYV(
+                input.as_ptr().cast(),
+                input_len as u32,
+                input_unit as i16,
+                0,
+            ))?;
+            ResultCode(ctru_sys::Y2RU_SetReceiving(
+                output.as_mut_ptr().cast(),
+                output_len as u32,
+                output_unit as i16,
+                0,
+            ))?;
+        }
+
+        let mut end_event: ctru_sys::Handle = 0;
+        ResultCode(unsafe { ctru_sys::Y2RU_GetTransferEndEvent(&mut end_event) })?;
+
+        let result = unsafe {
+            let mut result = ctru_sys::Y2RU_StartConversion();
+
+            if ctru_sys::R_SUCCEEDED(result) {
+                result = ctru_sys::svcWaitSynchronization(
+                    end_event,
+                    timeout.as_nanos().try_into().unwrap_or(i64::MAX),
+                );
+            }
+
+            // Stop the conversion in case the timeout was reached, so that the buffers aren't written after they are released.
+            if ctru_sys::R_FAILED(result) {
+                let _ = ctru_sys::Y2RU_StopConversion();
+            }
+
+            let _ = ctru_sys::svcCloseHandle(end_event);
+
+            // Discard any stale copy of the output from the data cache, since it was written by the DMA.
+            let _ = ctru_sys::GSPGPU_InvalidateDataCache(output.as_ptr().cast(), output_len as u32);
+
+            result
+        };
+
+        ResultCode(result)?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Y2r {
+    #[doc(alias = "y2rExit")]
+    fn drop(&mut self) {
+        unsafe { ctru_sys::y2rExit() };
+    }
+}